@@ -63,6 +63,18 @@ impl GeometryBindGroupObject {
     pub fn get_bind_group(&self) -> &wgpu::BindGroup {
         &self.bind_group
     }
+    pub fn get_model(&self) -> &Mat4 {
+        &self.model
+    }
+
+    pub fn set_model(&mut self, model: Mat4) {
+        self.model = model;
+        self.renderer.get_queue().write_buffer(
+            &self.buffer,
+            std::mem::offset_of!(GeometryUniforms, model) as u64,
+            bytemuck::cast_slice(&[self.model]),
+        );
+    }
 
     pub fn rotate(&mut self, center: Vec3, axis: Vec3, radians: f32) {
         let rotation = Mat4::rotate_center_axis(center, axis, radians);
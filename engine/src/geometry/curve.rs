@@ -1,6 +1,12 @@
 use crate::{
-    gpu_samplers::{curve_sampler::CurveSampler, params::SAMPLES_PER_SEGMENT},
-    math::linear_algebra::vec4::Vec4,
+    gpu_samplers::{
+        adaptive::create_curve_sample_params, curve_sampler::CurveSampler,
+        params::SamplingTolerance,
+    },
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::curve::NurbsCurve,
+    },
 };
 
 use super::{bind_group::GeometryBindGroupObject, utils::default_knot_vector, Geometry};
@@ -10,9 +16,12 @@ pub struct Curve {
     weighted_controls: Vec<Vec4>,
     bind_group_object: GeometryBindGroupObject,
     knots: Vec<f32>,
+    /// Overrides the samplers tolerance when set
+    sampling_tolerance: Option<SamplingTolerance>,
     // Samples
     vertex_buffer: wgpu::Buffer,
     vertex_count: u32,
+    sample_params: Vec<f32>,
 }
 
 impl Curve {
@@ -28,19 +37,142 @@ impl Curve {
             knots.to_vec()
         };
 
-        let vertex_buffer = curve_sampler.sample_curve(degree, &weighted_controls, &knots);
-        let vertex_count = SAMPLES_PER_SEGMENT * (weighted_controls.len() as u32 - 1) + 1;
+        let sample_params = create_curve_sample_params(
+            &NurbsCurve::new(degree, weighted_controls.clone(), knots.clone()),
+            &curve_sampler.get_sampling_tolerance(),
+        );
+        let vertex_buffer =
+            curve_sampler.sample_curve(degree, &weighted_controls, &knots, &sample_params);
+        let vertex_count = sample_params.len() as u32;
         let bind_group_object = GeometryBindGroupObject::new(curve_sampler.get_renderer());
         Curve {
             degree,
             weighted_controls,
             knots,
+            sampling_tolerance: None,
             vertex_buffer,
             vertex_count,
+            sample_params,
             bind_group_object,
         }
     }
 
+    pub fn from_nurbs(curve_sampler: &CurveSampler, nurbs: NurbsCurve) -> Curve {
+        Curve::new(
+            curve_sampler,
+            nurbs.degree,
+            nurbs.weighted_controls,
+            &nurbs.knots,
+        )
+    }
+
+    /// Replaces the definition and resamples
+    fn set_nurbs(&mut self, curve_sampler: &CurveSampler, nurbs: NurbsCurve) {
+        self.degree = nurbs.degree;
+        self.weighted_controls = nurbs.weighted_controls;
+        self.knots = nurbs.knots;
+        self.resample(curve_sampler);
+    }
+
+    /// Inserts t up to times times without changing the shape.
+    /// Returns how many knots were inserted.
+    pub fn insert_knot(&mut self, curve_sampler: &CurveSampler, t: f32, times: usize) -> usize {
+        let mut nurbs = self.to_nurbs();
+        let inserted = nurbs.insert_knot(t, times);
+        if inserted > 0 {
+            self.set_nurbs(curve_sampler, nurbs);
+        }
+        inserted
+    }
+
+    /// Inserts all of knots without changing the shape, knots must be sorted
+    pub fn refine_knots(&mut self, curve_sampler: &CurveSampler, knots: &[f32]) {
+        let mut nurbs = self.to_nurbs();
+        nurbs.refine_knots(knots);
+        self.set_nurbs(curve_sampler, nurbs);
+    }
+
+    /// Raises the degree without changing the shape
+    pub fn elevate_degree(&mut self, curve_sampler: &CurveSampler, times: u32) {
+        let mut nurbs = self.to_nurbs();
+        nurbs.elevate_degree(times);
+        self.set_nurbs(curve_sampler, nurbs);
+    }
+
+    /// Removes t up to times times, as long as the curve moves less than tolerance.
+    /// Returns how many times it was removed.
+    pub fn remove_knot(
+        &mut self,
+        curve_sampler: &CurveSampler,
+        t: f32,
+        times: usize,
+        tolerance: f32,
+    ) -> usize {
+        let mut nurbs = self.to_nurbs();
+        let removed = nurbs.remove_knot(t, times, tolerance);
+        if removed > 0 {
+            self.set_nurbs(curve_sampler, nurbs);
+        }
+        removed
+    }
+
+    /// Removes every knot that can go without moving the curve more than tolerance.
+    /// Returns the number of knots removed.
+    pub fn remove_knots(&mut self, curve_sampler: &CurveSampler, tolerance: f32) -> usize {
+        let mut nurbs = self.to_nurbs();
+        let removed = nurbs.remove_knots(tolerance);
+        if removed > 0 {
+            self.set_nurbs(curve_sampler, nurbs);
+        }
+        removed
+    }
+
+    /// Two new curves, before and after t.
+    /// They keep the transform and sampling tolerance of this curve.
+    pub fn split_at(&self, curve_sampler: &CurveSampler, t: f32) -> Option<(Curve, Curve)> {
+        let (left, right) = self.to_nurbs().split_at(t)?;
+        let mut left = Curve::from_nurbs(curve_sampler, left);
+        let mut right = Curve::from_nurbs(curve_sampler, right);
+        for curve in [&mut left, &mut right] {
+            curve
+                .bind_group_object
+                .set_model(*self.bind_group_object.get_model());
+            if self.sampling_tolerance.is_some() {
+                curve.set_sampling_tolerance(curve_sampler, self.sampling_tolerance);
+            }
+        }
+        Some((left, right))
+    }
+
+    /// Resamples with the curves own tolerance, or the samplers when it has none
+    pub fn resample(&mut self, curve_sampler: &CurveSampler) {
+        let tolerance = self
+            .sampling_tolerance
+            .unwrap_or(curve_sampler.get_sampling_tolerance());
+        self.sample_params = create_curve_sample_params(&self.to_nurbs(), &tolerance);
+        self.vertex_buffer = curve_sampler.sample_curve(
+            self.degree,
+            &self.weighted_controls,
+            &self.knots,
+            &self.sample_params,
+        );
+        self.vertex_count = self.sample_params.len() as u32;
+    }
+
+    /// None falls back to the samplers tolerance
+    pub fn set_sampling_tolerance(
+        &mut self,
+        curve_sampler: &CurveSampler,
+        sampling_tolerance: Option<SamplingTolerance>,
+    ) {
+        self.sampling_tolerance = sampling_tolerance;
+        self.resample(curve_sampler);
+    }
+
+    pub fn get_sampling_tolerance(&self) -> Option<SamplingTolerance> {
+        self.sampling_tolerance
+    }
+
     pub fn get_vertex_buffer(&self) -> &wgpu::Buffer {
         &self.vertex_buffer
     }
@@ -49,12 +181,49 @@ impl Curve {
         self.vertex_count
     }
 
+    pub fn get_knots(&self) -> &[f32] {
+        &self.knots
+    }
+
+    /// Parameter of each sample in the vertex buffer
+    pub fn get_sample_params(&self) -> &[f32] {
+        &self.sample_params
+    }
+
+    /// CPU copy for evaluation
+    pub fn to_nurbs(&self) -> NurbsCurve {
+        NurbsCurve::new(
+            self.degree,
+            self.weighted_controls.clone(),
+            self.knots.clone(),
+        )
+    }
+
+    /// CPU copy with the model transform applied
+    pub fn to_world_nurbs(&self) -> NurbsCurve {
+        let mut nurbs = self.to_nurbs();
+        nurbs.transform(self.bind_group_object.get_model());
+        nurbs
+    }
+
+    /// Parameter, point and distance of the closest point to a world space point.
+    /// Newton iteration starts from the nearest sample.
+    pub fn closest_point(&self, point: &Vec3) -> (f32, Vec3, f32) {
+        let (t, closest) = self
+            .to_world_nurbs()
+            .closest_point(point, &self.sample_params);
+        (t, closest, Vec3::distance(&closest, point))
+    }
+
     pub fn get_bind_group(&self) -> &wgpu::BindGroup {
         self.bind_group_object.get_bind_group()
     }
 }
 
 impl Geometry for Curve {
+    fn get_bind_group_object(&self) -> &GeometryBindGroupObject {
+        &self.bind_group_object
+    }
     fn get_bind_group_object_mut(&mut self) -> &mut GeometryBindGroupObject {
         &mut self.bind_group_object
     }
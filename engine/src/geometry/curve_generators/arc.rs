@@ -2,8 +2,9 @@ use crate::{
     geometry::{curve::Curve, GeometryId},
     gpu_samplers::curve_sampler::CurveSampler,
     math::{
-        geometry::{plane::Plane, ray::Ray},
+        geometry::ray::Ray,
         linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::curve::NurbsCurve,
     },
     scene::scene_interface::Scene,
     utils::get_instance_mut,
@@ -11,6 +12,8 @@ use crate::{
 
 use wasm_bindgen::prelude::*;
 
+use super::circle::circle_through_points;
+
 #[wasm_bindgen]
 impl Scene {
     #[wasm_bindgen]
@@ -24,38 +27,60 @@ impl Scene {
         let middle: Vec3 = middle.into();
         let end: Vec3 = end.into();
 
-        let ab = Vec3::subtract(&start, &middle);
-        let ac = Vec3::subtract(&end, &middle);
-        let normal = Vec3::to_normalized(&Vec3::cross(&ab, &ac));
-
-        let ro = Vec3::to_scaled(&Vec3::add(&middle, &start), 0.5);
-        let rd = Vec3::to_normalized(&Vec3::cross(&ab, &normal));
-        let r = Ray::new(ro, rd);
-
-        let po = Vec3::to_scaled(&Vec3::add(&middle, &end), 0.5);
-        let pn = Vec3::to_normalized(&ac);
-        let p = Plane::new(po, pn);
-
-        let t = r.intersect_plane(&p, true).unwrap();
-        let center = r.at(t);
-        let radius = Vec3::distance(&middle, &center);
+        // The normal is oriented so going counter clockwise from start passes middle before end
+        let Some((center, normal, radius)) = circle_through_points(&start, &middle, &end) else {
+            log::info!("create arc failed");
+            return 0;
+        };
 
-        let x_axis = Vec3::to_normalized(&Vec3::subtract(&end, &center));
+        let x_axis = Vec3::to_normalized(&Vec3::subtract(&start, &center));
         let y_axis = Vec3::cross(&normal, &x_axis);
+        let theta = angle_around(&x_axis, &y_axis, &Vec3::subtract(&end, &center));
 
-        let mut theta = Vec3::angle_between(
-            &Vec3::subtract(&start, &center),
-            &Vec3::subtract(&end, &center),
+        let curve = create_arc(
+            get_instance_mut!(&self.get_instance_handle()).get_curve_sampler(),
+            center,
+            x_axis,
+            y_axis,
+            radius,
+            0.0,
+            theta,
         );
 
-        if Vec3::angle_between(&ab, &ac) < std::f32::consts::PI / 2.0 {
-            theta = 2.0 * std::f32::consts::PI - theta;
-        }
+        get_instance_mut!(&self.get_instance_handle())
+            .get_scene_mut(self.get_handle())
+            .add_curve(curve)
+    }
+
+    /// Radius comes from start, end only sets where the arc stops.
+    /// The short way around is used unless over_180_degrees is set.
+    #[wasm_bindgen]
+    pub fn add_arc_center_start_end(
+        &self,
+        center: &[f32],
+        start: &[f32],
+        end: &[f32],
+        over_180_degrees: bool,
+    ) -> GeometryId {
+        let center: Vec3 = center.into();
+        let to_start = Vec3::subtract(&start.into(), &center);
+        let to_end = Vec3::subtract(&end.into(), &center);
+        let radius = to_start.len();
 
-        if f32::is_nan(theta) || theta == 0.0 {
+        let mut normal = Vec3::cross(&to_start, &to_end);
+        if radius == 0.0 || normal.len() <= radius * to_end.len() * 1e-6 {
             log::info!("create arc failed");
             return 0;
         }
+        let mut theta = Vec3::angle_between(&to_start, &to_end);
+        if over_180_degrees {
+            // Go around the other way
+            normal = Vec3::to_scaled(&normal, -1.0);
+            theta = 2.0 * std::f32::consts::PI - theta;
+        }
+
+        let x_axis = to_start.to_normalized();
+        let y_axis = Vec3::cross(&normal.to_normalized(), &x_axis);
 
         let curve = create_arc(
             get_instance_mut!(&self.get_instance_handle()).get_curve_sampler(),
@@ -71,15 +96,15 @@ impl Scene {
             .get_scene_mut(self.get_handle())
             .add_curve(curve)
     }
+}
 
-    #[wasm_bindgen]
-    pub fn create_arc_center_start_end(
-        center: &[f32],
-        start: &[f32],
-        end: &[f32],
-        over_180_degrees: bool,
-    ) -> GeometryId {
-        todo!()
+/// Angle of v in the plane of x_axis and y_axis, between 0 and 2 pi
+pub fn angle_around(x_axis: &Vec3, y_axis: &Vec3, v: &Vec3) -> f32 {
+    let theta = f32::atan2(Vec3::dot(v, y_axis), Vec3::dot(v, x_axis));
+    if theta < 0.0 {
+        theta + 2.0 * std::f32::consts::PI
+    } else {
+        theta
     }
 }
 
@@ -90,10 +115,26 @@ pub fn create_arc(
     y_axis: Vec3,
     radius: f32,
     theta_start: f32,
-    mut theta_end: f32,
+    theta_end: f32,
 ) -> Curve {
+    Curve::from_nurbs(
+        sampler,
+        create_arc_nurbs(origin, x_axis, y_axis, radius, theta_start, theta_end),
+    )
+}
+
+/// Exact rational quadratic arc, counter clockwise from theta_start to theta_end in radians.
+/// Axes do not need to be unit length, scaled axes give elliptical arcs.
+pub fn create_arc_nurbs(
+    origin: Vec3,
+    x_axis: Vec3,
+    y_axis: Vec3,
+    radius: f32,
+    theta_start: f32,
+    mut theta_end: f32,
+) -> NurbsCurve {
     if theta_end < theta_start {
-        theta_end += 360.0;
+        theta_end += 2.0 * std::f32::consts::PI;
     }
 
     let theta = theta_end - theta_start;
@@ -112,43 +153,34 @@ pub fn create_arc(
 
     let weight = f32::cos(d_theta / 2.0);
 
-    let mut point_0: Vec3 = Vec3::add(
-        &origin,
-        &Vec3::add(
-            &Vec3::to_scaled(&x_axis, radius * f32::cos(theta_start)),
-            &Vec3::to_scaled(&y_axis, radius * f32::sin(theta_start)),
-        ),
-    );
-
-    let mut tangent_0: Vec3 = Vec3::add(
-        &Vec3::to_scaled(&x_axis, f32::sin(theta_start)),
-        &Vec3::to_scaled(&y_axis, f32::cos(theta_start)),
-    );
-
-    let mut weighted_controls: Vec<Vec4> = vec![Vec4 {
-        x: point_0.x,
-        y: point_0.y,
-        z: point_0.z,
-        w: 1.0,
-    }];
-
-    let mut angle: f32 = theta_start;
-
-    for i in 1..=arc_count {
-        angle += d_theta;
-
-        let point_2: Vec3 = Vec3::add(
+    let point_at = |angle: f32| {
+        Vec3::add(
             &origin,
             &Vec3::add(
                 &Vec3::to_scaled(&x_axis, radius * f32::cos(angle)),
                 &Vec3::to_scaled(&y_axis, radius * f32::sin(angle)),
             ),
-        );
-
-        let tangent_2: Vec3 = Vec3::add(
+        )
+    };
+    let tangent_at = |angle: f32| {
+        Vec3::add(
             &Vec3::to_scaled(&x_axis, -f32::sin(angle)),
             &Vec3::to_scaled(&y_axis, f32::cos(angle)),
-        );
+        )
+    };
+
+    let mut point_0: Vec3 = point_at(theta_start);
+    let mut tangent_0: Vec3 = tangent_at(theta_start);
+
+    let mut weighted_controls: Vec<Vec4> = vec![point_0.append(1.0)];
+
+    let mut angle: f32 = theta_start;
+
+    for _ in 1..=arc_count {
+        angle += d_theta;
+
+        let point_2: Vec3 = point_at(angle);
+        let tangent_2: Vec3 = tangent_at(angle);
 
         let ray_0 = Ray::new(point_0, tangent_0);
         let ray_1 = Ray::new(point_2, tangent_2);
@@ -160,43 +192,22 @@ pub fn create_arc(
         weighted_controls.push(Vec3::to_scaled(&point_1, weight).append(weight));
         weighted_controls.push(point_2.append(1.0));
 
-        if i < arc_count {
-            point_0 = point_2;
-            tangent_0 = tangent_2;
-        }
+        point_0 = point_2;
+        tangent_0 = tangent_2;
     }
 
-    let knots: Vec<f32> = match arc_count {
-        1 => vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
-        2 => vec![0.0, 0.0, 0.0, 1.0 / 2.0, 1.0 / 2.0, 1.0, 1.0, 1.0],
-        3 => vec![
-            0.0,
-            0.0,
-            0.0,
-            1.0 / 3.0,
-            1.0 / 3.0,
-            2.0 / 3.0,
-            2.0 / 3.0,
-            1.0,
-            1.0,
-            1.0,
-        ],
-        4 => vec![
-            0.0,
-            0.0,
-            0.0,
-            1.0 / 4.0,
-            1.0 / 4.0,
-            2.0 / 4.0,
-            2.0 / 4.0,
-            3.0 / 4.0,
-            3.0 / 4.0,
-            1.0,
-            1.0,
-            1.0,
-        ],
-        _ => unreachable!(),
-    };
+    // Close full circles exactly
+    if theta >= 2.0 * std::f32::consts::PI {
+        weighted_controls[2 * arc_count] = weighted_controls[0];
+    }
+
+    let mut knots: Vec<f32> = vec![0.0; 3];
+    for i in 1..arc_count {
+        let knot = i as f32 / arc_count as f32;
+        knots.push(knot);
+        knots.push(knot);
+    }
+    knots.extend([1.0; 3]);
 
-    Curve::new(sampler, 2, weighted_controls, &knots[..])
+    NurbsCurve::new(2, weighted_controls, knots)
 }
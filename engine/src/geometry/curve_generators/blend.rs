@@ -0,0 +1,140 @@
+use crate::{
+    geometry::GeometryId,
+    math::{linear_algebra::vec3::Vec3, nurbs::curve::NurbsCurve},
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CurveEnd {
+    Start = 0,
+    End = 1,
+}
+
+/// How smoothly a blend joins the curves it connects
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Continuity {
+    /// Same tangent direction
+    G1 = 1,
+    /// Same tangent direction and curvature
+    G2 = 2,
+    /// Same tangent direction, curvature and rate of change of curvature
+    G3 = 3,
+}
+
+#[wasm_bindgen]
+impl Scene {
+    /// Curve from one end of curve_a to one end of curve_b.
+    /// Bulges scale how far the blend follows each curve before turning, 1 is a good default.
+    /// Returns 0 if a curve is missing or the ends touch.
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_blend_curve(
+        &self,
+        curve_a: GeometryId,
+        end_a: CurveEnd,
+        curve_b: GeometryId,
+        end_b: CurveEnd,
+        continuity: Continuity,
+        bulge_a: f32,
+        bulge_b: f32,
+    ) -> GeometryId {
+        let (Some(a), Some(b)) = (self.get_curve_nurbs(curve_a), self.get_curve_nurbs(curve_b))
+        else {
+            log::info!("blend failed, no curve");
+            return 0;
+        };
+        let Some(blend) = create_blend_nurbs(&a, end_a, &b, end_b, continuity, bulge_a, bulge_b)
+        else {
+            log::info!("blend failed");
+            return 0;
+        };
+        self.add_curve_from_nurbs(blend)
+    }
+}
+
+/// Point and derivatives at the end of the curve, turned around at the start so they point away from the curve
+fn outward_derivatives(curve: &NurbsCurve, end: CurveEnd, count: usize) -> Vec<Vec3> {
+    let (start, finish) = curve.domain();
+    match end {
+        CurveEnd::End => curve.derivatives(finish, count),
+        CurveEnd::Start => curve
+            .derivatives(start, count)
+            .into_iter()
+            .enumerate()
+            .map(|(k, d)| {
+                if k % 2 == 1 {
+                    Vec3::to_scaled(&d, -1.0)
+                } else {
+                    d
+                }
+            })
+            .collect(),
+    }
+}
+
+/// First controls of a degree n bezier whose derivatives at its start are the
+/// derivatives of the curve reparameterized by t = speed * s, so it joins with the continuity of the derivatives
+fn matching_controls(derivatives: &[Vec3], speed: f32, n: usize) -> Vec<Vec3> {
+    let mut controls = vec![derivatives[0]];
+    let mut falling = 1.0;
+    let mut scale = 1.0;
+    for k in 1..derivatives.len() {
+        falling *= (n + 1 - k) as f32;
+        scale *= speed;
+        // The kth derivative is falling * (forward difference of the first k + 1 controls)
+        let mut control = Vec3::to_scaled(&derivatives[k], scale / falling);
+        let mut binomial = 1.0;
+        for j in 1..=k {
+            binomial = binomial * (k + 1 - j) as f32 / j as f32;
+            let sign = if j % 2 == 1 { 1.0 } else { -1.0 };
+            control = Vec3::add(
+                &control,
+                &Vec3::to_scaled(&controls[k - j], sign * binomial),
+            );
+        }
+        controls.push(control);
+    }
+    controls
+}
+
+/// Bezier of degree 2 * continuity + 1 from the end of a to the end of b.
+/// Each half of the controls matches the derivatives at one end exactly.
+/// None if the ends touch or a curve has no tangent at its end.
+pub fn create_blend_nurbs(
+    a: &NurbsCurve,
+    end_a: CurveEnd,
+    b: &NurbsCurve,
+    end_b: CurveEnd,
+    continuity: Continuity,
+    bulge_a: f32,
+    bulge_b: f32,
+) -> Option<NurbsCurve> {
+    let k = continuity as usize;
+    let n = 2 * k + 1;
+    let derivatives_a = outward_derivatives(a, end_a, k);
+    let derivatives_b = outward_derivatives(b, end_b, k);
+
+    let chord = Vec3::subtract(&derivatives_b[0], &derivatives_a[0]).len();
+    let speed_a = derivatives_a[1].len();
+    let speed_b = derivatives_b[1].len();
+    if chord == 0.0 || speed_a == 0.0 || speed_b == 0.0 {
+        return None;
+    }
+
+    // The blend leaves each end at bulge * chord per unit of its parameter
+    let controls_a = matching_controls(&derivatives_a, bulge_a * chord / speed_a, n);
+    let controls_b = matching_controls(&derivatives_b, bulge_b * chord / speed_b, n);
+
+    let weighted_controls = controls_a
+        .iter()
+        .chain(controls_b.iter().rev())
+        .map(|control| control.append(1.0))
+        .collect();
+    let mut knots = vec![0.0; n + 1];
+    knots.extend(std::iter::repeat_n(1.0, n + 1));
+    Some(NurbsCurve::new(n as u32, weighted_controls, knots))
+}
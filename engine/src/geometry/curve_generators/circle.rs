@@ -0,0 +1,121 @@
+use crate::{
+    geometry::GeometryId,
+    math::{linear_algebra::vec3::Vec3, nurbs::curve::NurbsCurve},
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+use super::arc::create_arc_nurbs;
+
+#[wasm_bindgen]
+impl Scene {
+    #[wasm_bindgen]
+    pub fn add_circle(&self, center: &[f32], normal: &[f32], radius: f32) -> GeometryId {
+        if radius <= 0.0 {
+            log::info!("create circle failed");
+            return 0;
+        }
+        self.add_curve_from_nurbs(create_circle_nurbs(center.into(), normal.into(), radius))
+    }
+
+    /// Circle through all three points
+    #[wasm_bindgen]
+    pub fn add_circle_three_points(&self, a: &[f32], b: &[f32], c: &[f32]) -> GeometryId {
+        let Some((center, normal, radius)) = circle_through_points(&a.into(), &b.into(), &c.into())
+        else {
+            log::info!("create circle failed");
+            return 0;
+        };
+        self.add_curve_from_nurbs(create_circle_nurbs(center, normal, radius))
+    }
+
+    /// Circle with start and end on opposite sides.
+    /// It lies in the plane through start and end that is closest to having the given normal.
+    #[wasm_bindgen]
+    pub fn add_circle_diameter(&self, start: &[f32], end: &[f32], normal: &[f32]) -> GeometryId {
+        let start: Vec3 = start.into();
+        let end: Vec3 = end.into();
+        let diameter = Vec3::subtract(&end, &start);
+        if diameter.len() == 0.0 {
+            log::info!("create circle failed");
+            return 0;
+        }
+        let center = Vec3::to_scaled(&Vec3::add(&start, &end), 0.5);
+        // Start at start
+        let x_axis = Vec3::subtract(&start, &center).to_normalized();
+        let normal: Vec3 = normal.into();
+        // Drop the part of the normal along the diameter
+        let mut normal = Vec3::subtract(
+            &normal,
+            &Vec3::to_scaled(&x_axis, Vec3::dot(&normal, &x_axis)),
+        );
+        if normal.len() < 1e-6 {
+            normal = Vec3::any_perpendicular(&x_axis);
+        }
+        let y_axis = Vec3::cross(&normal.to_normalized(), &x_axis);
+        self.add_curve_from_nurbs(create_arc_nurbs(
+            center,
+            x_axis,
+            y_axis,
+            diameter.len() / 2.0,
+            0.0,
+            2.0 * std::f32::consts::PI,
+        ))
+    }
+
+    /// Axes are the half widths of the ellipse, and should be perpendicular
+    #[wasm_bindgen]
+    pub fn add_ellipse(&self, center: &[f32], axis_x: &[f32], axis_y: &[f32]) -> GeometryId {
+        let axis_x: Vec3 = axis_x.into();
+        let axis_y: Vec3 = axis_y.into();
+        if Vec3::cross(&axis_x, &axis_y).len() == 0.0 {
+            log::info!("create ellipse failed");
+            return 0;
+        }
+        self.add_curve_from_nurbs(create_ellipse_nurbs(center.into(), axis_x, axis_y))
+    }
+}
+
+/// Exact rational circle, counter clockwise around normal
+pub fn create_circle_nurbs(center: Vec3, normal: Vec3, radius: f32) -> NurbsCurve {
+    let normal = normal.to_normalized();
+    let x_axis = Vec3::any_perpendicular(&normal);
+    let y_axis = Vec3::cross(&normal, &x_axis);
+    create_arc_nurbs(
+        center,
+        x_axis,
+        y_axis,
+        radius,
+        0.0,
+        2.0 * std::f32::consts::PI,
+    )
+}
+
+/// Exact rational ellipse, starts at center + axis_x and heads toward center + axis_y
+pub fn create_ellipse_nurbs(center: Vec3, axis_x: Vec3, axis_y: Vec3) -> NurbsCurve {
+    create_arc_nurbs(center, axis_x, axis_y, 1.0, 0.0, 2.0 * std::f32::consts::PI)
+}
+
+/// Center, unit normal and radius of the circle through a, b and c.
+/// Going counter clockwise around the normal from a reaches b before c.
+/// None when the points are in a line.
+pub fn circle_through_points(a: &Vec3, b: &Vec3, c: &Vec3) -> Option<(Vec3, Vec3, f32)> {
+    let ab = Vec3::subtract(b, a);
+    let ac = Vec3::subtract(c, a);
+    let normal = Vec3::cross(&ab, &ac);
+    let normal_len_squared = Vec3::dot(&normal, &normal);
+    if normal_len_squared <= 1e-12 * Vec3::dot(&ab, &ab) * Vec3::dot(&ac, &ac) {
+        return None;
+    }
+
+    let to_center = Vec3::to_scaled(
+        &Vec3::add(
+            &Vec3::to_scaled(&Vec3::cross(&normal, &ab), Vec3::dot(&ac, &ac)),
+            &Vec3::to_scaled(&Vec3::cross(&ac, &normal), Vec3::dot(&ab, &ab)),
+        ),
+        0.5 / normal_len_squared,
+    );
+    let center = Vec3::add(a, &to_center);
+    Some((center, normal.to_normalized(), to_center.len()))
+}
@@ -0,0 +1,58 @@
+use crate::{
+    geometry::GeometryId,
+    math::{
+        linear_algebra::vec3::Vec3,
+        nurbs::fitting::{fit_curve, Deviation},
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+/// A fitted curve or surface and how far the points are from it
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone)]
+pub struct FitResult {
+    id: GeometryId,
+    max_deviation: f32,
+    average_deviation: f32,
+}
+
+impl FitResult {
+    pub(crate) fn new(id: GeometryId, deviation: Deviation) -> Self {
+        Self {
+            id,
+            max_deviation: deviation.max,
+            average_deviation: deviation.average,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl FitResult {
+    pub fn get_id(&self) -> GeometryId {
+        self.id
+    }
+    pub fn get_max_deviation(&self) -> f32 {
+        self.max_deviation
+    }
+    pub fn get_average_deviation(&self) -> f32 {
+        self.average_deviation
+    }
+}
+
+#[wasm_bindgen]
+impl Scene {
+    /// Least squares curve through ordered points, points are xyz triples.
+    /// The curve starts and ends exactly at the first and last point.
+    /// None if there are fewer points than controls or fewer controls than degree + 1.
+    #[wasm_bindgen]
+    pub fn fit_curve(&self, points: &[f32], degree: u32, control_count: u32) -> Option<FitResult> {
+        let points: Vec<Vec3> = points.chunks_exact(3).map(|point| point.into()).collect();
+        let Some((curve, deviation)) = fit_curve(&points, degree, control_count as usize) else {
+            log::info!("fit curve failed");
+            return None;
+        };
+        Some(FitResult::new(self.add_curve_from_nurbs(curve), deviation))
+    }
+}
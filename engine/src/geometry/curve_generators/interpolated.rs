@@ -0,0 +1,97 @@
+use crate::{
+    geometry::GeometryId,
+    math::{
+        linear_algebra::vec3::Vec3,
+        nurbs::{
+            curve::NurbsCurve,
+            interpolation::{
+                create_params, interpolate_closed, interpolate_with_derivatives, point_distances,
+                Parameterization,
+            },
+        },
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl Scene {
+    /// Curve through the points in order, points are xyz triples.
+    /// Tangents set the direction at the ends, leave them empty to let the curve choose.
+    /// Closed curves join smoothly back to the first point and ignore the tangents.
+    #[wasm_bindgen]
+    pub fn add_interpolated_curve(
+        &self,
+        points: &[f32],
+        degree: u32,
+        parameterization: Parameterization,
+        // Leave empty for a free start
+        start_tangent: &[f32],
+        // Leave empty for a free end
+        end_tangent: &[f32],
+        closed: bool,
+    ) -> GeometryId {
+        let points: Vec<Vec3> = points.chunks_exact(3).map(|point| point.into()).collect();
+        let tangent = |tangent: &[f32]| (tangent.len() == 3).then(|| tangent.into());
+        let Some(curve) = create_interpolated_nurbs(
+            &points,
+            degree,
+            parameterization,
+            tangent(start_tangent),
+            tangent(end_tangent),
+            closed,
+        ) else {
+            log::info!("create interpolated curve failed");
+            return 0;
+        };
+        self.add_curve_from_nurbs(curve)
+    }
+}
+
+/// Curve through the points in order, the degree is lowered if there are too few points.
+/// Tangents only give directions, their length is set from the size of the curve.
+/// Closed curves may repeat the first point at the end or not.
+/// None for degree 0, too few points or repeated points.
+pub fn create_interpolated_nurbs(
+    points: &[Vec3],
+    degree: u32,
+    parameterization: Parameterization,
+    start_tangent: Option<Vec3>,
+    end_tangent: Option<Vec3>,
+    closed: bool,
+) -> Option<NurbsCurve> {
+    if degree == 0 || points.len() < 2 {
+        return None;
+    }
+    let mut points = points.to_vec();
+    if closed && points.len() > 1 && points[0] == points[points.len() - 1] {
+        points.pop();
+    }
+
+    let distances = point_distances(&points, closed);
+    if distances.contains(&0.0) {
+        return None;
+    }
+    let params = create_params(&distances, parameterization);
+    let homogeneous: Vec<_> = points.iter().map(|point| point.append(1.0)).collect();
+
+    if closed {
+        return interpolate_closed(&homogeneous, &params, degree);
+    }
+
+    // The parameter runs from 0 to 1, so a derivative as long as the polyline moves at about the right speed
+    let length: f32 = distances.iter().sum();
+    let derivative = |tangent: Option<Vec3>| {
+        tangent
+            .filter(|tangent| tangent.len() > 0.0)
+            .map(|tangent| Vec3::to_scaled(&tangent.to_normalized(), length).append(0.0))
+    };
+    interpolate_with_derivatives(
+        &homogeneous,
+        &params,
+        degree,
+        derivative(start_tangent),
+        derivative(end_tangent),
+    )
+}
@@ -1,3 +1,6 @@
 pub mod arc;
 pub mod blend;
 pub mod circle;
+pub mod fit;
+pub mod interpolated;
+pub mod offset;
@@ -0,0 +1,340 @@
+use crate::{
+    geometry::GeometryId,
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{
+            compatibility::join_curves,
+            curve::NurbsCurve,
+            interpolation::{create_params, interpolate, point_distances, Parameterization},
+        },
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+use super::circle::circle_through_points;
+
+/// Samples per span to start with, and the most samples tried before giving up
+const FIRST_SAMPLES_PER_SPAN: usize = 8;
+const MAX_SAMPLES: usize = 1024;
+
+#[wasm_bindgen]
+impl Scene {
+    /// Curve at distance from a planar curve, positive distances go to the left looking down plane_normal.
+    /// Lines and arcs are offset exactly, other curves are fitted to within tolerance.
+    /// Loops where the offset crosses itself in tight concave turns are cut out.
+    /// Returns 0 if there is no such curve or the offset collapses.
+    #[wasm_bindgen]
+    pub fn offset_curve(
+        &self,
+        id: GeometryId,
+        distance: f32,
+        plane_normal: &[f32],
+        tolerance: f32,
+    ) -> GeometryId {
+        let Some(curve) = self.get_curve_nurbs(id) else {
+            log::info!("offset failed, no curve");
+            return 0;
+        };
+        let Some(offset) = create_offset_nurbs(&curve, distance, &plane_normal.into(), tolerance)
+        else {
+            log::info!("offset failed");
+            return 0;
+        };
+        self.add_curve_from_nurbs(offset)
+    }
+}
+
+/// Offset of a curve lying in a plane with the given normal.
+/// None if the normal has no length, tolerance is not positive, nothing is left after trimming
+/// or the fit of a freeform curve does not get within tolerance.
+pub fn create_offset_nurbs(
+    curve: &NurbsCurve,
+    distance: f32,
+    plane_normal: &Vec3,
+    tolerance: f32,
+) -> Option<NurbsCurve> {
+    if plane_normal.len() == 0.0 || tolerance <= 0.0 {
+        return None;
+    }
+    let normal = plane_normal.to_normalized();
+    if distance == 0.0 {
+        return Some(curve.clone());
+    }
+    if curve.degree == 1 {
+        return offset_polyline(curve, distance, &normal);
+    }
+    if let Some(arc) = offset_arc(curve, distance, &normal) {
+        return arc;
+    }
+    offset_freeform(curve, distance, &normal, tolerance)
+}
+
+/// Left of the tangent, looking down the normal
+fn offset_direction(tangent: &Vec3, normal: &Vec3) -> Vec3 {
+    Vec3::cross(normal, tangent).to_normalized()
+}
+
+/// Exact offset of a circular arc by scaling it about its center.
+/// None if the curve is not circular, Some(None) if the offset collapses.
+fn offset_arc(curve: &NurbsCurve, distance: f32, normal: &Vec3) -> Option<Option<NurbsCurve>> {
+    if curve.degree != 2 {
+        return None;
+    }
+    let (start, end) = curve.domain();
+    let at = |s: f32| curve.point(start + (end - start) * s);
+    let (center, _, radius) = circle_through_points(&at(0.0), &at(0.5), &at(1.0))
+        .or_else(|| circle_through_points(&at(0.0), &at(0.25), &at(0.5)))?;
+    let circular = (0..=16).all(|i| {
+        let point = at(i as f32 / 16.0);
+        (Vec3::distance(&point, &center) - radius).abs() <= 1e-5 * (radius + center.len())
+    });
+    if !circular {
+        return None;
+    }
+
+    // Which way the offset goes relative to the center
+    let t = start + (end - start) * 0.5;
+    let to_center = Vec3::subtract(&center, &curve.point(t));
+    let left = offset_direction(&curve.tangent(t), normal);
+    let new_radius = if Vec3::dot(&left, &to_center) > 0.0 {
+        radius - distance
+    } else {
+        radius + distance
+    };
+    if new_radius <= 0.0 {
+        return Some(None);
+    }
+
+    let scale = new_radius / radius;
+    let mut res = curve.clone();
+    for control in res.weighted_controls.iter_mut() {
+        let point = control.to_vec3_safe();
+        let scaled = Vec3::add(
+            &center,
+            &Vec3::to_scaled(&Vec3::subtract(&point, &center), scale),
+        );
+        *control = Vec3::to_scaled(&scaled, control.w).append(control.w);
+    }
+    Some(Some(res))
+}
+
+/// 2d coordinates in the plane
+struct PlaneProjection {
+    x_axis: Vec3,
+    y_axis: Vec3,
+}
+
+impl PlaneProjection {
+    fn new(normal: &Vec3) -> Self {
+        let x_axis = Vec3::any_perpendicular(normal).to_normalized();
+        Self {
+            y_axis: Vec3::cross(normal, &x_axis),
+            x_axis,
+        }
+    }
+
+    fn project(&self, point: &Vec3) -> (f32, f32) {
+        (
+            Vec3::dot(point, &self.x_axis),
+            Vec3::dot(point, &self.y_axis),
+        )
+    }
+}
+
+/// Where segments a0 a1 and b0 b1 cross as fractions along each, ends included
+fn segment_intersection(
+    projection: &PlaneProjection,
+    a0: &Vec3,
+    a1: &Vec3,
+    b0: &Vec3,
+    b1: &Vec3,
+) -> Option<(f32, f32)> {
+    let (ax, ay) = projection.project(a0);
+    let (adx, ady) = projection.project(&Vec3::subtract(a1, a0));
+    let (bx, by) = projection.project(b0);
+    let (bdx, bdy) = projection.project(&Vec3::subtract(b1, b0));
+    let denominator = adx * bdy - ady * bdx;
+    if denominator.abs() <= 1e-12 * (adx.abs() + ady.abs()) * (bdx.abs() + bdy.abs()) {
+        return None;
+    }
+    let (dx, dy) = (bx - ax, by - ay);
+    let s = (dx * bdy - dy * bdx) / denominator;
+    let t = (dx * ady - dy * adx) / denominator;
+    ((0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&t)).then_some((s, t))
+}
+
+/// Cuts out the loops of a polyline that crosses itself.
+/// Returns the pieces between cuts, each piece ends where the next starts.
+/// Every point keeps the index it had in the polyline, cut points have none.
+fn trim_loops(points: &[Vec3], projection: &PlaneProjection) -> Vec<Vec<(Vec3, Option<usize>)>> {
+    let mut pieces = vec![vec![(points[0], Some(0))]];
+    let mut i = 0;
+    while i + 1 < points.len() {
+        // The farthest later segment crossing this one closes the largest loop
+        let crossing = (i + 2..points.len() - 1).rev().find_map(|j| {
+            segment_intersection(
+                projection,
+                &points[i],
+                &points[i + 1],
+                &points[j],
+                &points[j + 1],
+            )
+            .map(|(s, _)| (j, s))
+        });
+        let piece = pieces.last_mut().unwrap();
+        match crossing {
+            Some((j, s)) => {
+                let cut = Vec3::add(
+                    &points[i],
+                    &Vec3::to_scaled(&Vec3::subtract(&points[i + 1], &points[i]), s),
+                );
+                piece.push((cut, None));
+                pieces.push(vec![(cut, None), (points[j + 1], Some(j + 1))]);
+                i = j + 1;
+            }
+            None => {
+                piece.push((points[i + 1], Some(i + 1)));
+                i += 1;
+            }
+        }
+    }
+    pieces
+}
+
+/// Exact offset of a degree 1 curve, each segment moves over and neighbours meet at a corner
+fn offset_polyline(curve: &NurbsCurve, distance: f32, normal: &Vec3) -> Option<NurbsCurve> {
+    let points: Vec<Vec3> = curve
+        .weighted_controls
+        .iter()
+        .map(|control| control.to_vec3_safe())
+        .collect();
+    let segment_offsets: Vec<Vec3> = points
+        .windows(2)
+        .map(|pair| {
+            Vec3::to_scaled(
+                &offset_direction(&Vec3::subtract(&pair[1], &pair[0]), normal),
+                distance,
+            )
+        })
+        .collect();
+
+    let mut offset_points = vec![Vec3::add(&points[0], &segment_offsets[0])];
+    for (i, pair) in segment_offsets.windows(2).enumerate() {
+        // Miter corner, the offset along the bisector that is distance from both segments
+        let bisector = Vec3::add(&pair[0], &pair[1]);
+        let cos_half = Vec3::dot(&bisector.to_normalized(), &pair[0].to_normalized());
+        let corner = if bisector.len() < 1e-6 * distance.abs() || cos_half.abs() < 1e-6 {
+            pair[0]
+        } else {
+            Vec3::to_scaled(&bisector.to_normalized(), distance.abs() / cos_half)
+        };
+        offset_points.push(Vec3::add(&points[i + 1], &corner));
+    }
+    offset_points.push(Vec3::add(
+        &points[points.len() - 1],
+        &segment_offsets[segment_offsets.len() - 1],
+    ));
+
+    let pieces = trim_loops(&offset_points, &PlaneProjection::new(normal));
+    let points: Vec<Vec3> = pieces
+        .iter()
+        .enumerate()
+        .flat_map(|(i, piece)| {
+            piece[if i == 0 { 0 } else { 1 }..]
+                .iter()
+                .map(|(point, _)| *point)
+        })
+        .collect();
+    if points.len() < 2 {
+        return None;
+    }
+    let weighted_controls: Vec<Vec4> = points.iter().map(|point| point.append(1.0)).collect();
+    let params = create_params(&point_distances(&points, false), Parameterization::Uniform);
+    let mut knots = vec![0.0];
+    knots.extend(params);
+    knots.push(1.0);
+    Some(NurbsCurve::new(1, weighted_controls, knots))
+}
+
+/// Offset of a smooth curve, sampled more densely until a fit through the samples is within tolerance.
+/// None if it is still not within tolerance at MAX_SAMPLES.
+fn offset_freeform(
+    curve: &NurbsCurve,
+    distance: f32,
+    normal: &Vec3,
+    tolerance: f32,
+) -> Option<NurbsCurve> {
+    let (start, end) = curve.domain();
+    let projection = PlaneProjection::new(normal);
+    let offset_at = |t: f32| {
+        let derivatives = curve.derivatives(t, 1);
+        Vec3::add(
+            &derivatives[0],
+            &Vec3::to_scaled(&offset_direction(&derivatives[1], normal), distance),
+        )
+    };
+    let span_count = curve
+        .knots
+        .windows(2)
+        .filter(|pair| pair[1] > pair[0])
+        .count();
+
+    let mut sample_count = FIRST_SAMPLES_PER_SPAN * span_count.max(1);
+    loop {
+        let params: Vec<f32> = (0..=sample_count)
+            .map(|i| start + (end - start) * i as f32 / sample_count as f32)
+            .collect();
+        let points: Vec<Vec3> = params.iter().map(|t| offset_at(*t)).collect();
+        let pieces = trim_loops(&points, &projection);
+
+        let fitted = pieces
+            .iter()
+            .map(|piece| fit_piece(piece))
+            .collect::<Option<Vec<_>>>()?;
+        let res = join_curves(&fitted)?;
+
+        // Compare halfway between neighbouring samples, where the fit is least constrained
+        let mut max_error: f32 = 0.0;
+        for (piece, fitted) in pieces.iter().zip(fitted.iter()) {
+            let (piece_start, piece_end) = fitted.domain();
+            let piece_params = piece_params(piece);
+            for (k, pair) in piece.windows(2).enumerate() {
+                let (Some(a), Some(b)) = (pair[0].1, pair[1].1) else {
+                    continue;
+                };
+                let expected = offset_at((params[a] + params[b]) / 2.0);
+                let s = (piece_params[k] + piece_params[k + 1]) / 2.0;
+                let actual = fitted.point(piece_start + (piece_end - piece_start) * s);
+                max_error = max_error.max(Vec3::distance(&actual, &expected));
+            }
+        }
+        if max_error <= tolerance {
+            return Some(res);
+        }
+        if sample_count >= MAX_SAMPLES {
+            log::warn!(
+                "offset fit is {} from the offset, over the tolerance {}",
+                max_error,
+                tolerance
+            );
+            return None;
+        }
+        sample_count = (sample_count * 2).min(MAX_SAMPLES);
+    }
+}
+
+fn piece_params(piece: &[(Vec3, Option<usize>)]) -> Vec<f32> {
+    let points: Vec<Vec3> = piece.iter().map(|(point, _)| *point).collect();
+    create_params(
+        &point_distances(&points, false),
+        Parameterization::ChordLength,
+    )
+}
+
+/// Cubic through the points of one trimmed piece
+fn fit_piece(piece: &[(Vec3, Option<usize>)]) -> Option<NurbsCurve> {
+    let homogeneous: Vec<Vec4> = piece.iter().map(|(point, _)| point.append(1.0)).collect();
+    interpolate(&homogeneous, &piece_params(piece), 3)
+}
@@ -39,7 +39,7 @@ impl Lines {
                 .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                     label: Some("Vertex Buffer"),
                     contents: bytemuck::cast_slice(verts),
-                    usage: wgpu::BufferUsages::VERTEX,
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE,
                 });
         let index_buffer =
             renderer
@@ -47,7 +47,7 @@ impl Lines {
                 .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                     label: Some("index buffer"),
                     contents: bytemuck::cast_slice(indices),
-                    usage: wgpu::BufferUsages::INDEX,
+                    usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::STORAGE,
                 });
         let bind_group_object = GeometryBindGroupObject::new(renderer);
         Lines {
@@ -90,6 +90,9 @@ impl Lines {
 }
 
 impl Geometry for Lines {
+    fn get_bind_group_object(&self) -> &GeometryBindGroupObject {
+        &self.bind_group_object
+    }
     fn get_bind_group_object_mut(&mut self) -> &mut GeometryBindGroupObject {
         &mut self.bind_group_object
     }
@@ -48,14 +48,18 @@ impl Mesh {
                 .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                     label: Some("Vertex Buffer"),
                     contents: bytemuck::cast_slice(verts),
-                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_SRC,
+                    usage: wgpu::BufferUsages::VERTEX
+                        | wgpu::BufferUsages::STORAGE
+                        | wgpu::BufferUsages::COPY_SRC,
                 });
         let index_buffer =
             renderer
                 .get_device()
                 .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                     label: Some("Index Buffer"),
-                    usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_SRC,
+                    usage: wgpu::BufferUsages::INDEX
+                        | wgpu::BufferUsages::STORAGE
+                        | wgpu::BufferUsages::COPY_SRC,
                     contents: bytemuck::cast_slice(indices),
                 });
         let bind_group = GeometryBindGroupObject::new(renderer);
@@ -91,9 +95,15 @@ impl Mesh {
     pub fn get_vertex_count(&self) -> u32 {
         self.vertex_count
     }
+    pub fn get_bbh(&self) -> Option<&MeshBBH> {
+        self.bbh.as_ref()
+    }
 }
 
 impl Geometry for Mesh {
+    fn get_bind_group_object(&self) -> &GeometryBindGroupObject {
+        &self.bind_group_object
+    }
     fn get_bind_group_object_mut(&mut self) -> &mut GeometryBindGroupObject {
         &mut self.bind_group_object
     }
@@ -27,6 +27,7 @@ pub fn new_geometry_id() -> GeometryId {
 }
 
 pub trait Geometry {
+    fn get_bind_group_object(&self) -> &GeometryBindGroupObject;
     fn get_bind_group_object_mut(&mut self) -> &mut GeometryBindGroupObject;
 
     fn rotate(&mut self, center: Vec3, axis: Vec3, radians: f32) {
@@ -38,7 +38,7 @@ impl Polyline {
                 .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                     label: Some("Vertex Buffer"),
                     contents: bytemuck::cast_slice(verts),
-                    usage: wgpu::BufferUsages::VERTEX,
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE,
                 });
         let bind_group_object = GeometryBindGroupObject::new(renderer);
         Polyline {
@@ -61,6 +61,9 @@ impl Polyline {
 }
 
 impl Geometry for Polyline {
+    fn get_bind_group_object(&self) -> &GeometryBindGroupObject {
+        &self.bind_group_object
+    }
     fn get_bind_group_object_mut(&mut self) -> &mut GeometryBindGroupObject {
         &mut self.bind_group_object
     }
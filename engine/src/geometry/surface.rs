@@ -1,11 +1,26 @@
 use crate::{
     gpu_acceleration_structures::mesh_bbh::{mesh_bbh_generator::MeshBBHGenerator, MeshBBH},
-    gpu_samplers::{params::SAMPLES_PER_SEGMENT, surface_sampler::SurfaceSampler},
-    math::linear_algebra::{vec3::Vec3, vec4::Vec4},
+    gpu_samplers::{
+        adaptive::create_surface_sample_params,
+        curve_sampler::CurveSampler,
+        params::SamplingTolerance,
+        surface_sampler::{SurfaceDirectionInput, SurfaceSampler},
+        trim::{add_crossing_params, sample_trims, tessellate_trimmed_grid, TrimmedTessellation},
+    },
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{
+            curve::NurbsCurve,
+            surface::{NurbsSurface, SurfaceDirection},
+            trim::SurfaceTrims,
+        },
+    },
 };
 use std::rc::Rc;
 
-use super::{bind_group::GeometryBindGroupObject, utils::default_knot_vector, Geometry};
+use super::{
+    bind_group::GeometryBindGroupObject, curve::Curve, utils::default_knot_vector, Geometry,
+};
 
 pub struct Surface {
     surface_sampler: Rc<SurfaceSampler>,
@@ -25,8 +40,22 @@ pub struct Surface {
     vertex_buffer: wgpu::Buffer,
     index_count: u32,
     index_buffer: wgpu::Buffer,
+    /// (u, v) of each vertex
+    uv_buffer: wgpu::Buffer,
+    sample_params_u: Vec<f32>,
+    sample_params_v: Vec<f32>,
+    /// Overrides the samplers tolerance when set
+    sampling_tolerance: Option<SamplingTolerance>,
+    /// Kept region in (u, v), the whole domain when None
+    trims: Option<SurfaceTrims>,
+    /// Triangles of the kept region when trimmed, the index buffer holds the same indices
+    tessellation: Option<TrimmedTessellation>,
     bind_group_object: GeometryBindGroupObject,
     bbh: Option<MeshBBH>,
+    /// Drawn over the surface, clipped to the kept region
+    isocurves: Vec<Curve>,
+    /// Density the isocurves were built for, None when they are out of date
+    isocurve_density: Option<u32>,
 }
 
 impl Surface {
@@ -69,18 +98,30 @@ impl Surface {
                 w: *weight,
             })
             .collect();
-        let (index_buffer, vertex_buffer) = surface_sampler.sample_surface(
+        let nurbs = NurbsSurface::new(
             degree_u,
             degree_v,
-            &weighted_controls[..],
             control_count_u,
             control_count_v,
-            &knots_u[..],
-            &knots_v[..],
+            weighted_controls,
+            knots_u,
+            knots_v,
+        );
+        let (sample_params_u, sample_params_v) =
+            create_surface_sample_params(&nurbs, &surface_sampler.get_sampling_tolerance());
+        let (index_buffer, vertex_buffer, uv_buffer) = Self::sample(
+            &surface_sampler,
+            &nurbs,
+            &sample_params_u,
+            &sample_params_v,
+            None,
         );
-        let sample_count_u = SAMPLES_PER_SEGMENT * (control_count_u - 1) + 1;
+        let NurbsSurface {
+            knots_u, knots_v, ..
+        } = nurbs;
 
-        let sample_count_v = SAMPLES_PER_SEGMENT * (control_count_v - 1) + 1;
+        let sample_count_u = sample_params_u.len() as u32;
+        let sample_count_v = sample_params_v.len() as u32;
         let index_count = (sample_count_u - 1) * (sample_count_v - 1) * 6;
         let bind_group_object = GeometryBindGroupObject::new(surface_sampler.get_renderer());
         let bbh = if with_bbh {
@@ -108,11 +149,176 @@ impl Surface {
             vertex_buffer,
             index_count,
             index_buffer,
+            uv_buffer,
+            sample_params_u,
+            sample_params_v,
+            sampling_tolerance: None,
+            trims: None,
+            tessellation: None,
             bind_group_object,
             bbh,
+            isocurves: Vec::new(),
+            isocurve_density: None,
+        }
+    }
+
+    pub async fn from_nurbs(
+        surface_sampler: Rc<SurfaceSampler>,
+        bbh_generator: Rc<MeshBBHGenerator>,
+        nurbs: NurbsSurface,
+        with_bbh: bool,
+    ) -> Self {
+        let controls = nurbs
+            .weighted_controls
+            .iter()
+            .map(|control| control.to_vec3_safe())
+            .collect();
+        let weights: Vec<f32> = nurbs
+            .weighted_controls
+            .iter()
+            .map(|control| control.w)
+            .collect();
+        Self::new(
+            surface_sampler,
+            bbh_generator,
+            nurbs.control_count_u,
+            nurbs.control_count_v,
+            nurbs.degree_u,
+            nurbs.degree_v,
+            controls,
+            &weights,
+            &nurbs.knots_u,
+            &nurbs.knots_v,
+            with_bbh,
+        )
+        .await
+    }
+
+    /// Samples the grid, then cuts it down to the kept region when there is a trimmed tessellation
+    fn sample(
+        surface_sampler: &SurfaceSampler,
+        nurbs: &NurbsSurface,
+        sample_params_u: &[f32],
+        sample_params_v: &[f32],
+        tessellation: Option<&TrimmedTessellation>,
+    ) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer) {
+        let (index_buffer, vertex_buffer, uv_buffer) = surface_sampler.sample_surface(
+            &nurbs.weighted_controls,
+            &SurfaceDirectionInput::u(nurbs, sample_params_u),
+            &SurfaceDirectionInput::v(nurbs, sample_params_v),
+        );
+        match tessellation {
+            Some(tessellation) => surface_sampler.trim_samples(
+                nurbs,
+                &vertex_buffer,
+                &uv_buffer,
+                (sample_params_u.len() * sample_params_v.len()) as u64,
+                tessellation,
+            ),
+            None => (index_buffer, vertex_buffer, uv_buffer),
         }
     }
 
+    /// Sample params for the tolerance, and the triangles of the kept region over them when trimmed.
+    /// Trims add u params so every loop crosses a grid line.
+    fn create_sample_params(
+        nurbs: &NurbsSurface,
+        tolerance: &SamplingTolerance,
+        trims: Option<&SurfaceTrims>,
+    ) -> (Vec<f32>, Vec<f32>, Option<TrimmedTessellation>) {
+        let (mut sample_params_u, sample_params_v) = create_surface_sample_params(nurbs, tolerance);
+        let tessellation = trims.map(|trims| {
+            let (start_u, end_u) = nurbs.domain_u();
+            let (start_v, end_v) = nurbs.domain_v();
+            let loops = sample_trims(trims, (end_u - start_u).hypot(end_v - start_v));
+            add_crossing_params(&mut sample_params_u, &sample_params_v, &loops);
+            tessellate_trimmed_grid(&sample_params_u, &sample_params_v, trims, &loops)
+        });
+        (sample_params_u, sample_params_v, tessellation)
+    }
+
+    fn get_tolerance(&self) -> SamplingTolerance {
+        self.sampling_tolerance
+            .unwrap_or(self.surface_sampler.get_sampling_tolerance())
+    }
+
+    /// Resamples with the surfaces own tolerance, or the samplers when it has none.
+    /// An existing bbh is rebuilt.
+    pub fn resample(&mut self) {
+        let nurbs = self.to_nurbs();
+        let (sample_params_u, sample_params_v, tessellation) =
+            Self::create_sample_params(&nurbs, &self.get_tolerance(), self.trims.as_ref());
+        self.apply_samples(&nurbs, sample_params_u, sample_params_v, tessellation);
+    }
+
+    fn apply_samples(
+        &mut self,
+        nurbs: &NurbsSurface,
+        sample_params_u: Vec<f32>,
+        sample_params_v: Vec<f32>,
+        tessellation: Option<TrimmedTessellation>,
+    ) {
+        let (index_buffer, vertex_buffer, uv_buffer) = Self::sample(
+            &self.surface_sampler,
+            nurbs,
+            &sample_params_u,
+            &sample_params_v,
+            tessellation.as_ref(),
+        );
+        self.index_buffer = index_buffer;
+        self.vertex_buffer = vertex_buffer;
+        self.uv_buffer = uv_buffer;
+        self.sample_params_u = sample_params_u;
+        self.sample_params_v = sample_params_v;
+        self.index_count = match &tessellation {
+            Some(tessellation) => tessellation.indices.len() as u32,
+            None => (self.get_sample_count_u() - 1) * (self.get_sample_count_v() - 1) * 6,
+        };
+        self.tessellation = tessellation;
+        self.isocurve_density = None;
+
+        if self.bbh.is_some() {
+            self.bbh = Some(self.bbh_generator.generate_mesh_bbh_fast_build_2(
+                &self.vertex_buffer,
+                self.get_vertex_count(),
+                &self.index_buffer,
+                self.index_count,
+            ));
+        }
+    }
+
+    /// Keeps only the region inside the outer loop and outside the inner loops, None removes the trims.
+    /// Resamples, so drawing, picking and the bbh only see the kept region.
+    /// Returns false and leaves the surface as it was when the trims keep nothing.
+    pub fn set_trims(&mut self, trims: Option<SurfaceTrims>) -> bool {
+        let nurbs = self.to_nurbs();
+        let (sample_params_u, sample_params_v, tessellation) =
+            Self::create_sample_params(&nurbs, &self.get_tolerance(), trims.as_ref());
+        if tessellation
+            .as_ref()
+            .is_some_and(|tessellation| tessellation.indices.is_empty())
+        {
+            return false;
+        }
+        self.trims = trims;
+        self.apply_samples(&nurbs, sample_params_u, sample_params_v, tessellation);
+        true
+    }
+
+    pub fn get_trims(&self) -> Option<&SurfaceTrims> {
+        self.trims.as_ref()
+    }
+
+    /// None falls back to the samplers tolerance
+    pub fn set_sampling_tolerance(&mut self, sampling_tolerance: Option<SamplingTolerance>) {
+        self.sampling_tolerance = sampling_tolerance;
+        self.resample();
+    }
+
+    pub fn get_sampling_tolerance(&self) -> Option<SamplingTolerance> {
+        self.sampling_tolerance
+    }
+
     /// Control count u and v must not change
     /// TODO: update to be able to change these
     pub async fn update_params(
@@ -140,30 +346,9 @@ impl Surface {
             self.knots_v = knots_v.to_vec();
         }
 
-        let weighted_controls: Vec<Vec4> = self
-            .controls
-            .iter()
-            .zip(self.weights.iter())
-            .map(|(control, weight)| Vec4 {
-                x: control.x * weight,
-                y: control.y * weight,
-                z: control.z * weight,
-                w: *weight,
-            })
-            .collect();
-        let (_, vertex_buffer) = self.surface_sampler.sample_surface(
-            self.degree_u,
-            self.degree_v,
-            &weighted_controls[..],
-            self.control_count_u,
-            self.control_count_v,
-            &self.knots_u[..],
-            &self.knots_v[..],
-        );
-        self.vertex_buffer = vertex_buffer;
-
-        let sample_count_u = SAMPLES_PER_SEGMENT * (self.control_count_u - 1) + 1;
-        let sample_count_v = SAMPLES_PER_SEGMENT * (self.control_count_v - 1) + 1;
+        // Sample counts can change, so the index buffer is rebuilt too
+        self.bbh = None;
+        self.resample();
 
         self.bbh = if with_bbh {
             Some(
@@ -171,7 +356,7 @@ impl Surface {
                     // TODO: which one do I build?
                     .generate_mesh_bbh_fast_trace(
                         &self.vertex_buffer,
-                        sample_count_u * sample_count_v,
+                        self.get_vertex_count(),
                         &self.index_buffer,
                         self.index_count,
                     )
@@ -187,6 +372,10 @@ impl Surface {
     pub fn get_vertex_buffer(&self) -> &wgpu::Buffer {
         &self.vertex_buffer
     }
+    /// Two f32s per vertex, in vertex buffer order
+    pub fn get_uv_buffer(&self) -> &wgpu::Buffer {
+        &self.uv_buffer
+    }
     pub fn get_index_count(&self) -> u32 {
         self.index_count
     }
@@ -194,6 +383,231 @@ impl Surface {
         self.bind_group_object.get_bind_group()
     }
 
+    /// Grid samples followed by the extra vertices of a trimmed tessellation
+    pub fn get_vertex_count(&self) -> u32 {
+        let extra_count = self
+            .tessellation
+            .as_ref()
+            .map_or(0, |tessellation| tessellation.extra_uvs.len() as u32);
+        self.get_sample_count_u() * self.get_sample_count_v() + extra_count
+    }
+
+    pub fn get_sample_count_u(&self) -> u32 {
+        self.sample_params_u.len() as u32
+    }
+    pub fn get_sample_count_v(&self) -> u32 {
+        self.sample_params_v.len() as u32
+    }
+    /// Parameter of each column of samples
+    pub fn get_sample_params_u(&self) -> &[f32] {
+        &self.sample_params_u
+    }
+    /// Parameter of each row of samples
+    pub fn get_sample_params_v(&self) -> &[f32] {
+        &self.sample_params_v
+    }
+    pub fn get_weighted_controls(&self) -> Vec<Vec4> {
+        self.controls
+            .iter()
+            .zip(self.weights.iter())
+            .map(|(control, weight)| Vec4 {
+                x: control.x * weight,
+                y: control.y * weight,
+                z: control.z * weight,
+                w: *weight,
+            })
+            .collect()
+    }
+
+    /// CPU copy for evaluation
+    pub fn to_nurbs(&self) -> NurbsSurface {
+        NurbsSurface::new(
+            self.degree_u,
+            self.degree_v,
+            self.control_count_u,
+            self.control_count_v,
+            self.get_weighted_controls(),
+            self.knots_u.clone(),
+            self.knots_v.clone(),
+        )
+    }
+
+    /// Replaces the definition and resamples
+    fn set_nurbs(&mut self, nurbs: NurbsSurface) {
+        self.degree_u = nurbs.degree_u;
+        self.degree_v = nurbs.degree_v;
+        self.control_count_u = nurbs.control_count_u;
+        self.control_count_v = nurbs.control_count_v;
+        self.controls = nurbs
+            .weighted_controls
+            .iter()
+            .map(|control| control.to_vec3_safe())
+            .collect();
+        self.weights = nurbs
+            .weighted_controls
+            .iter()
+            .map(|control| control.w)
+            .collect();
+        self.knots_u = nurbs.knots_u;
+        self.knots_v = nurbs.knots_v;
+        self.resample();
+    }
+
+    /// Raises the degree in each direction without changing the shape
+    pub fn elevate_degree(&mut self, times_u: u32, times_v: u32) {
+        let mut nurbs = self.to_nurbs();
+        nurbs.elevate_degree_u(times_u);
+        nurbs.elevate_degree_v(times_v);
+        self.set_nurbs(nurbs);
+    }
+
+    /// Removes every knot in either direction that can go without moving the surface more than tolerance.
+    /// Returns the number of knots removed.
+    pub fn remove_knots(&mut self, tolerance: f32) -> usize {
+        let mut nurbs = self.to_nurbs();
+        let removed = nurbs.remove_knots(tolerance);
+        if removed > 0 {
+            self.set_nurbs(nurbs);
+        }
+        removed
+    }
+
+    /// Exact curve running in direction with the other parameter at param, in the surfaces local space.
+    /// Trims are ignored. None if param is outside the domain.
+    pub fn isocurve(&self, direction: SurfaceDirection, param: f32) -> Option<NurbsCurve> {
+        let nurbs = self.to_nurbs();
+        let (start, end) = match direction {
+            SurfaceDirection::U => nurbs.domain_v(),
+            SurfaceDirection::V => nurbs.domain_u(),
+        };
+        if !(start..=end).contains(&param) {
+            return None;
+        }
+        Some(nurbs.isocurve(direction, param))
+    }
+
+    /// Rebuilds the drawn isocurves if the surface or the density changed since they were built.
+    /// Density isocurves run in each direction, evenly spaced inside the domain.
+    pub fn build_isocurves(&mut self, curve_sampler: &CurveSampler, density: u32) {
+        if self.isocurve_density == Some(density) {
+            return;
+        }
+        let nurbs = self.to_nurbs();
+        let (domain_u, domain_v) = (nurbs.domain_u(), nurbs.domain_v());
+        let loops = self.trims.as_ref().map(|trims| {
+            let size = (domain_u.1 - domain_u.0).hypot(domain_v.1 - domain_v.0);
+            sample_trims(trims, size)
+        });
+
+        let mut isocurves = Vec::new();
+        for (direction, (along, (start, end))) in [
+            (SurfaceDirection::U, (domain_u, domain_v)),
+            (SurfaceDirection::V, (domain_v, domain_u)),
+        ] {
+            for i in 1..=density {
+                let param = start + (end - start) * i as f32 / (density + 1) as f32;
+                let isocurve = nurbs.isocurve(direction, param);
+                let intervals = match (&self.trims, &loops) {
+                    (Some(trims), Some(loops)) => {
+                        trims.kept_intervals(loops, direction, param, along)
+                    }
+                    _ => vec![along],
+                };
+                for (interval_start, interval_end) in intervals {
+                    let Some(part) = isocurve.sub_curve(interval_start, interval_end) else {
+                        continue;
+                    };
+                    isocurves.push(Curve::from_nurbs(curve_sampler, part));
+                }
+            }
+        }
+        self.isocurves = isocurves;
+        self.isocurve_density = Some(density);
+    }
+
+    /// Isocurves from the last build_isocurves.
+    /// They are drawn with the surfaces bind group, so they follow its transform.
+    pub fn get_isocurves(&self) -> &[Curve] {
+        &self.isocurves
+    }
+
+    /// CPU copy with the model transform applied
+    pub fn to_world_nurbs(&self) -> NurbsSurface {
+        let mut nurbs = self.to_nurbs();
+        nurbs.transform(self.bind_group_object.get_model());
+        nurbs
+    }
+
+    /// u, v, point, unit normal and distance of the closest point to a world space point.
+    /// Newton iteration starts from the nearest sample.
+    pub fn closest_point(&self, point: &Vec3) -> (f32, f32, Vec3, Vec3, f32) {
+        let nurbs = self.to_world_nurbs();
+        let (u, v, closest) =
+            nurbs.closest_point(point, &self.sample_params_u, &self.sample_params_v);
+        (
+            u,
+            v,
+            closest,
+            nurbs.normal(u, v),
+            Vec3::distance(&closest, point),
+        )
+    }
+
+    pub fn set_bbh(&mut self, bbh: MeshBBH) {
+        self.bbh = Some(bbh);
+    }
+
+    /// (u, v) of a vertex in the vertex buffer
+    fn get_vertex_uv(&self, index: u32) -> [f32; 2] {
+        let count_u = self.get_sample_count_u();
+        let grid_count = count_u * self.get_sample_count_v();
+        match &self.tessellation {
+            Some(tessellation) if index >= grid_count => {
+                tessellation.extra_uvs[(index - grid_count) as usize]
+            }
+            _ => [
+                self.sample_params_u[(index % count_u) as usize],
+                self.sample_params_v[(index / count_u) as usize],
+            ],
+        }
+    }
+
+    /// Maps a point on a triangle of the sampled mesh back to surface parameters.
+    /// Barycentrics are the weights of the triangles vertices in index buffer order.
+    pub fn get_triangle_uv(&self, triangle: u32, barycentric: &Vec3) -> (f32, f32) {
+        if let Some(tessellation) = &self.tessellation {
+            let first = triangle as usize * 3;
+            let weights = [barycentric.x, barycentric.y, barycentric.z];
+            return tessellation.indices[first..first + 3]
+                .iter()
+                .zip(weights)
+                .fold((0.0, 0.0), |(u, v), (index, weight)| {
+                    let [vertex_u, vertex_v] = self.get_vertex_uv(*index);
+                    (u + vertex_u * weight, v + vertex_v * weight)
+                });
+        }
+
+        let quads_per_row = self.get_sample_count_u() - 1;
+        let row = triangle / (quads_per_row * 2);
+        let column = (triangle % (quads_per_row * 2)) / 2;
+
+        // See index_buffer_generator.wgsl for the winding
+        let (x, y) = if triangle.is_multiple_of(2) {
+            // x1y1, x2y1, x2y2
+            (barycentric.y + barycentric.z, barycentric.z)
+        } else {
+            // x1y1, x2y2, x1y2
+            (barycentric.y, barycentric.y + barycentric.z)
+        };
+
+        let (column, row) = (column as usize, row as usize);
+        let params_u = &self.sample_params_u;
+        let params_v = &self.sample_params_v;
+        let u = params_u[column] + (params_u[column + 1] - params_u[column]) * x;
+        let v = params_v[row] + (params_v[row + 1] - params_v[row]) * y;
+        (u, v)
+    }
+
     /// Unsafe
     pub fn get_bbh(&self) -> Option<&MeshBBH> {
         if self.bbh.is_some() {
@@ -205,6 +619,9 @@ impl Surface {
 }
 
 impl Geometry for Surface {
+    fn get_bind_group_object(&self) -> &GeometryBindGroupObject {
+        &self.bind_group_object
+    }
     fn get_bind_group_object_mut(&mut self) -> &mut GeometryBindGroupObject {
         &mut self.bind_group_object
     }
@@ -0,0 +1,94 @@
+use crate::{
+    geometry::{utils::placement, GeometryId},
+    math::{
+        linear_algebra::{mat4::Mat4, vec3::Vec3},
+        nurbs::{curve::NurbsCurve, surface::NurbsSurface},
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+use super::revolve::create_revolve_nurbs;
+
+#[wasm_bindgen]
+impl Scene {
+    /// Cylinder standing on origin and going height along axis_z, the seam is on the side axis_x points to
+    #[wasm_bindgen]
+    pub async fn add_cylinder(
+        &self,
+        origin: &[f32],
+        axis_x: &[f32],
+        axis_z: &[f32],
+        radius: f32,
+        height: f32,
+        with_bbh: bool,
+    ) -> GeometryId {
+        self.add_cone(origin, axis_x, axis_z, radius, radius, height, with_bbh)
+            .await
+    }
+
+    /// Cone standing on origin and going height along axis_z.
+    /// A top radius of 0 comes to a point, otherwise the top is cut off.
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_cone(
+        &self,
+        origin: &[f32],
+        axis_x: &[f32],
+        axis_z: &[f32],
+        base_radius: f32,
+        top_radius: f32,
+        height: f32,
+        with_bbh: bool,
+    ) -> GeometryId {
+        let Some(placement) = placement(&origin.into(), &axis_x.into(), &axis_z.into()) else {
+            log::info!("create cone failed");
+            return 0;
+        };
+        if base_radius < 0.0 || top_radius < 0.0 || base_radius + top_radius == 0.0 || height == 0.0
+        {
+            log::info!("create cone failed");
+            return 0;
+        }
+        self.add_surface_from_nurbs(
+            create_cone_nurbs(&placement, base_radius, top_radius, height),
+            with_bbh,
+        )
+        .await
+    }
+}
+
+/// Exact cylinder, u goes up from the base and v goes around.
+/// Local coordinates are mapped by placement.
+pub fn create_cylinder_nurbs(placement: &Mat4, radius: f32, height: f32) -> NurbsSurface {
+    create_cone_nurbs(placement, radius, radius, height)
+}
+
+/// Exact cone, u goes up from the base and v goes around.
+/// Local coordinates are mapped by placement.
+pub fn create_cone_nurbs(
+    placement: &Mat4,
+    base_radius: f32,
+    top_radius: f32,
+    height: f32,
+) -> NurbsSurface {
+    let origin = Vec3::new(0.0, 0.0, 0.0);
+    let profile = NurbsCurve::new(
+        1,
+        vec![
+            Vec3::new(base_radius, 0.0, 0.0).append(1.0),
+            Vec3::new(top_radius, 0.0, height).append(1.0),
+        ],
+        vec![0.0, 0.0, 1.0, 1.0],
+    );
+    let mut surface = create_revolve_nurbs(
+        &profile,
+        origin,
+        Vec3::new(0.0, 0.0, 1.0),
+        0.0,
+        2.0 * std::f32::consts::PI,
+    );
+    surface.transform(placement);
+    surface
+}
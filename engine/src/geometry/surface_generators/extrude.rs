@@ -0,0 +1,139 @@
+use crate::{
+    geometry::GeometryId,
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{curve::NurbsCurve, surface::NurbsSurface},
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl Scene {
+    /// Moves the curve along direction, u runs along the curve and v along direction.
+    /// With cap, closed curves also get a surface over each open end.
+    /// Returns the side first and then the caps, empty if there is no such curve.
+    #[wasm_bindgen]
+    pub async fn add_extrusion(
+        &self,
+        curve_id: GeometryId,
+        direction: &[f32],
+        cap: bool,
+        with_bbh: bool,
+    ) -> Vec<GeometryId> {
+        let direction: Vec3 = direction.into();
+        let Some(curve) = self.get_curve_nurbs(curve_id) else {
+            log::info!("extrusion failed, no curve");
+            return vec![];
+        };
+        if direction.len() == 0.0 {
+            log::info!("extrusion failed");
+            return vec![];
+        }
+
+        let mut surfaces = vec![create_extrusion_nurbs(&curve, &direction)];
+        if cap {
+            // The v = 1 row of the side is the moved curve
+            let top = surfaces[0].curves_u().remove(1);
+            surfaces.extend(create_cap_nurbs(&curve));
+            surfaces.extend(create_cap_nurbs(&top));
+        }
+
+        let mut ids = Vec::with_capacity(surfaces.len());
+        for surface in surfaces {
+            ids.push(self.add_surface_from_nurbs(surface, with_bbh).await);
+        }
+        ids
+    }
+
+    /// Narrows the curve down to apex, u runs along the curve and v toward apex.
+    /// With cap, closed curves also get a surface over the base.
+    /// Returns the side first and then the cap, empty if there is no such curve.
+    #[wasm_bindgen]
+    pub async fn add_extrusion_to_point(
+        &self,
+        curve_id: GeometryId,
+        apex: &[f32],
+        cap: bool,
+        with_bbh: bool,
+    ) -> Vec<GeometryId> {
+        let Some(curve) = self.get_curve_nurbs(curve_id) else {
+            log::info!("extrusion failed, no curve");
+            return vec![];
+        };
+
+        let mut surfaces = vec![create_extrusion_to_point_nurbs(&curve, &apex.into())];
+        if cap {
+            surfaces.extend(create_cap_nurbs(&curve));
+        }
+
+        let mut ids = Vec::with_capacity(surfaces.len());
+        for surface in surfaces {
+            ids.push(self.add_surface_from_nurbs(surface, with_bbh).await);
+        }
+        ids
+    }
+}
+
+/// Ruled surface between the curve and other, which has the same weights.
+/// Keeping the weights makes every v line straight.
+fn ruled_nurbs(curve: &NurbsCurve, other: Vec<Vec4>) -> NurbsSurface {
+    let mut weighted_controls = curve.weighted_controls.clone();
+    weighted_controls.extend(other);
+    NurbsSurface::new(
+        curve.degree,
+        1,
+        curve.weighted_controls.len() as u32,
+        2,
+        weighted_controls,
+        curve.knots.clone(),
+        vec![0.0, 0.0, 1.0, 1.0],
+    )
+}
+
+/// Exact straight extrusion
+pub fn create_extrusion_nurbs(curve: &NurbsCurve, direction: &Vec3) -> NurbsSurface {
+    let moved = curve
+        .weighted_controls
+        .iter()
+        .map(|control| Vec4::add(control, &Vec3::to_scaled(direction, control.w).append(0.0)))
+        .collect();
+    ruled_nurbs(curve, moved)
+}
+
+/// Exact cone like surface from the curve to apex, the v = 1 edge collapses to apex
+pub fn create_extrusion_to_point_nurbs(curve: &NurbsCurve, apex: &Vec3) -> NurbsSurface {
+    let collapsed = curve
+        .weighted_controls
+        .iter()
+        .map(|control| Vec3::to_scaled(apex, control.w).append(control.w))
+        .collect();
+    ruled_nurbs(curve, collapsed)
+}
+
+/// Surface filling a closed planar curve by collapsing it to its center.
+/// Only fills the inside correctly when every point of the curve can see the center.
+/// None when the curve is not closed.
+pub fn create_cap_nurbs(curve: &NurbsCurve) -> Option<NurbsSurface> {
+    let (start, end) = curve.domain();
+    let polygon_length: f32 = curve
+        .weighted_controls
+        .windows(2)
+        .map(|pair| Vec3::subtract(&pair[1].to_vec3_safe(), &pair[0].to_vec3_safe()).len())
+        .sum();
+    let gap = Vec3::subtract(&curve.point(end), &curve.point(start)).len();
+    if gap > 1e-5 * polygon_length {
+        return None;
+    }
+
+    // Average of evenly spaced points, the last is skipped since it repeats the first
+    let sample_count = 64;
+    let mut center = Vec3::default();
+    for i in 0..sample_count {
+        let t = start + (end - start) * i as f32 / sample_count as f32;
+        center = Vec3::add(&center, &curve.point(t));
+    }
+    let center = Vec3::to_scaled(&center, 1.0 / sample_count as f32);
+    Some(create_extrusion_to_point_nurbs(curve, &center))
+}
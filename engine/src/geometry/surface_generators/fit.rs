@@ -0,0 +1,43 @@
+use crate::{
+    geometry::curve_generators::fit::FitResult,
+    math::{linear_algebra::vec3::Vec3, nurbs::fitting::fit_surface},
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl Scene {
+    /// Least squares surface over a grid of points, points are xyz triples with u changing fastest.
+    /// The corners of the surface are the corner points.
+    /// None if either direction has fewer points than controls or fewer controls than degree + 1.
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fit_surface(
+        &self,
+        points: &[f32],
+        point_count_u: u32,
+        point_count_v: u32,
+        degree_u: u32,
+        degree_v: u32,
+        control_count_u: u32,
+        control_count_v: u32,
+        with_bbh: bool,
+    ) -> Option<FitResult> {
+        let points: Vec<Vec3> = points.chunks_exact(3).map(|point| point.into()).collect();
+        let Some((surface, deviation)) = fit_surface(
+            &points,
+            point_count_u as usize,
+            point_count_v as usize,
+            degree_u,
+            degree_v,
+            control_count_u as usize,
+            control_count_v as usize,
+        ) else {
+            log::info!("fit surface failed");
+            return None;
+        };
+        let id = self.add_surface_from_nurbs(surface, with_bbh).await;
+        Some(FitResult::new(id, deviation))
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    geometry::{utils::default_knot_vector, GeometryId},
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{
+            compatibility::make_compatible,
+            curve::NurbsCurve,
+            interpolation::{create_params, interpolate, interpolate_closed, Parameterization},
+            surface::NurbsSurface,
+        },
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LoftStyle {
+    /// Passes through the sections, spaced by the distance between them
+    Normal = 0,
+    /// The section controls become the surface controls, the surface only passes through the first and last section
+    Loose = 1,
+    /// Passes through the sections and hugs them more closely where they bend sharply
+    Tight = 2,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LoftOptions {
+    pub style: LoftStyle,
+    /// Continues from the last section back to the first
+    pub closed: bool,
+    /// When at least 2 every section is replaced by a cubic through this many points along it, 0 keeps the sections as they are
+    pub rebuild_point_count: u32,
+}
+
+#[wasm_bindgen]
+impl LoftOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Default for LoftOptions {
+    fn default() -> Self {
+        Self {
+            style: LoftStyle::Normal,
+            closed: false,
+            rebuild_point_count: 0,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Scene {
+    /// Surface through the curves in order, u runs along the curves and v across them.
+    /// Curves should run in the same direction. Returns 0 if a curve is missing or there are too few.
+    #[wasm_bindgen]
+    pub async fn add_loft(
+        &self,
+        curve_ids: &[GeometryId],
+        options: &LoftOptions,
+        with_bbh: bool,
+    ) -> GeometryId {
+        let mut sections = Vec::with_capacity(curve_ids.len());
+        for id in curve_ids {
+            let Some(section) = self.get_curve_nurbs(*id) else {
+                log::info!("loft failed, no curve {}", id);
+                return 0;
+            };
+            sections.push(section);
+        }
+        let Some(surface) = create_loft_nurbs(&sections, options) else {
+            log::info!("loft failed");
+            return 0;
+        };
+        self.add_surface_from_nurbs(surface, with_bbh).await
+    }
+}
+
+/// Lofted surface with a row of controls per compatible section.
+/// None with fewer than 2 sections, or 3 when closed.
+pub fn create_loft_nurbs(sections: &[NurbsCurve], options: &LoftOptions) -> Option<NurbsSurface> {
+    let section_count = sections.len();
+    if section_count < 2 || (options.closed && section_count < 3) {
+        return None;
+    }
+
+    let sections = if options.rebuild_point_count >= 2 {
+        rebuild_sections(sections, options.rebuild_point_count as usize)?
+    } else {
+        let mut sections = sections.to_vec();
+        make_compatible(&mut sections);
+        sections
+    };
+
+    let degree_u = sections[0].degree;
+    let knots_u = sections[0].knots.clone();
+    let degree_v = 3.min(section_count as u32 - 1);
+
+    // Controls at the same index across all sections
+    let columns: Vec<Vec<Vec4>> = (0..sections[0].weighted_controls.len())
+        .map(|i| {
+            sections
+                .iter()
+                .map(|section| section.weighted_controls[i])
+                .collect()
+        })
+        .collect();
+
+    let curves_v: Vec<NurbsCurve> = match options.style {
+        LoftStyle::Loose => columns
+            .into_iter()
+            .map(|column| loose_curve(column, degree_v, options.closed))
+            .collect(),
+        LoftStyle::Normal | LoftStyle::Tight => {
+            let parameterization = if options.style == LoftStyle::Tight {
+                Parameterization::Centripetal
+            } else {
+                Parameterization::ChordLength
+            };
+            let params = create_params(
+                &section_distances(&sections, options.closed),
+                parameterization,
+            );
+            columns
+                .iter()
+                .map(|column| {
+                    if options.closed {
+                        interpolate_closed(column, &params, degree_v)
+                    } else {
+                        interpolate(column, &params, degree_v)
+                    }
+                })
+                .collect::<Option<_>>()?
+        }
+    };
+
+    Some(NurbsSurface::from_curves_v(curves_v, degree_u, knots_u))
+}
+
+/// Cubics through point_count evenly spaced parameters of each section, they all share one knot vector
+fn rebuild_sections(sections: &[NurbsCurve], point_count: usize) -> Option<Vec<NurbsCurve>> {
+    let params: Vec<f32> = (0..point_count)
+        .map(|i| i as f32 / (point_count - 1) as f32)
+        .collect();
+    sections
+        .iter()
+        .map(|section| {
+            let (start, end) = section.domain();
+            let points: Vec<Vec4> = params
+                .iter()
+                .map(|t| section.point(start + (end - start) * t).append(1.0))
+                .collect();
+            interpolate(&points, &params, 3)
+        })
+        .collect()
+}
+
+/// Average distance between matching controls of neighbouring sections
+fn section_distances(sections: &[NurbsCurve], closed: bool) -> Vec<f32> {
+    let distance = |a: &NurbsCurve, b: &NurbsCurve| {
+        let sum: f32 = a
+            .weighted_controls
+            .iter()
+            .zip(b.weighted_controls.iter())
+            .map(|(a, b)| Vec3::subtract(&a.to_vec3_safe(), &b.to_vec3_safe()).len())
+            .sum();
+        sum / a.weighted_controls.len() as f32
+    };
+    let mut distances: Vec<f32> = sections
+        .windows(2)
+        .map(|pair| distance(&pair[0], &pair[1]))
+        .collect();
+    if closed {
+        distances.push(distance(&sections[sections.len() - 1], &sections[0]));
+    }
+    distances
+}
+
+/// Uniform b-spline with the column as its controls, wrapped around when closed
+fn loose_curve(mut column: Vec<Vec4>, degree: u32, closed: bool) -> NurbsCurve {
+    if !closed {
+        let knots = default_knot_vector(column.len(), degree);
+        return NurbsCurve::new(degree, column, knots);
+    }
+    let p = degree as usize;
+    let count = column.len();
+    column.extend_from_within(..p);
+    let knots = (0..count + 2 * p + 1)
+        .map(|j| j as f32 - p as f32)
+        .collect();
+    let mut curve = NurbsCurve::new(degree, column, knots);
+    curve.clamp();
+    curve
+}
@@ -1,4 +1,11 @@
+pub mod cylinder;
+pub mod extrude;
+pub mod fit;
 pub mod loft;
+pub mod offset;
+pub mod rectangle;
 pub mod revolve;
 pub mod sphere;
+pub mod sweep1;
 pub mod sweep2;
+pub mod torus;
@@ -0,0 +1,170 @@
+use crate::{
+    geometry::{Geometry, GeometryId},
+    gpu_samplers::surface_sampler::SurfaceDirectionInput,
+    math::{
+        linear_algebra::vec3::Vec3,
+        nurbs::{
+            fitting::{fit_surface, Deviation},
+            offset::OffsetSelfIntersection,
+            surface::NurbsSurface,
+        },
+    },
+    scene::scene_interface::Scene,
+    utils::get_instance_mut,
+};
+
+use wasm_bindgen::prelude::*;
+
+/// Degree of the fitted offset in both directions
+const FIT_DEGREE: u32 = 3;
+/// Samples in each direction to start with, and the most samples tried before giving up
+const FIRST_SAMPLE_COUNT: usize = 17;
+const MAX_SAMPLE_COUNT: usize = 129;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct OffsetSurfaceResult {
+    id: GeometryId,
+    max_deviation: f32,
+    average_deviation: f32,
+    self_intersections: Vec<OffsetSelfIntersection>,
+}
+
+#[wasm_bindgen]
+impl OffsetSurfaceResult {
+    pub fn get_id(&self) -> GeometryId {
+        self.id
+    }
+    /// Distance of the offset surface from the exact offset at the samples it was fitted to
+    pub fn get_max_deviation(&self) -> f32 {
+        self.max_deviation
+    }
+    pub fn get_average_deviation(&self) -> f32 {
+        self.average_deviation
+    }
+    /// Regions in (u, v) of the original surface where the offset folds over itself,
+    /// the offset surface has a loop there that should be trimmed away
+    pub fn get_self_intersections(&self) -> Vec<OffsetSelfIntersection> {
+        self.self_intersections.clone()
+    }
+}
+
+#[wasm_bindgen]
+impl Scene {
+    /// Surface at distance from a surface, positive distances go the way its normals point.
+    /// The exact offset is sampled on the GPU and fitted with more controls and denser samples
+    /// until the fit is within tolerance of the samples, or the most samples are reached.
+    /// Trims are not carried over.
+    /// None if there is no such surface or tolerance is not positive.
+    #[wasm_bindgen]
+    pub async fn offset_surface(
+        &self,
+        id: GeometryId,
+        distance: f32,
+        tolerance: f32,
+        with_bbh: bool,
+    ) -> Option<OffsetSurfaceResult> {
+        let Some((nurbs, world_nurbs, model)) = get_instance_mut!(&self.get_instance_handle())
+            .get_scene(self.get_handle())
+            .get_surfaces()
+            .get(&id)
+            .map(|surface| {
+                (
+                    surface.to_nurbs(),
+                    surface.to_world_nurbs(),
+                    *surface.get_bind_group_object().get_model(),
+                )
+            })
+        else {
+            log::info!("offset surface failed, no surface");
+            return None;
+        };
+        if tolerance <= 0.0 {
+            log::info!("offset surface failed");
+            return None;
+        }
+        let surface_sampler = get_instance_mut!(&self.get_instance_handle()).get_surface_sampler();
+        let offset_sampler = get_instance_mut!(&self.get_instance_handle()).get_offset_sampler();
+
+        let mut sample_count = FIRST_SAMPLE_COUNT;
+        let mut control_count = FIT_DEGREE as usize + 1;
+        let (offset, deviation, params_u, params_v) = loop {
+            let params_u = uniform_params(nurbs.domain_u(), sample_count);
+            let params_v = uniform_params(nurbs.domain_v(), sample_count);
+            let (_, vertex_buffer, _) = surface_sampler.sample_surface(
+                &nurbs.weighted_controls,
+                &SurfaceDirectionInput::u(&nurbs, &params_u),
+                &SurfaceDirectionInput::v(&nurbs, &params_v),
+            );
+            let points = offset_sampler
+                .sample_offset(
+                    &vertex_buffer,
+                    sample_count as u32,
+                    sample_count as u32,
+                    &model,
+                    distance,
+                )
+                .await;
+
+            let Some((offset, deviation)) =
+                fit_offset_samples(&points, sample_count, &mut control_count, tolerance)
+            else {
+                log::info!("offset surface failed");
+                return None;
+            };
+            if deviation.max <= tolerance || sample_count >= MAX_SAMPLE_COUNT {
+                break (offset, deviation, params_u, params_v);
+            }
+            sample_count = (sample_count * 2 - 1).min(MAX_SAMPLE_COUNT);
+        };
+        if deviation.max > tolerance {
+            log::warn!(
+                "offset surface is {} from the samples, more than the tolerance",
+                deviation.max
+            );
+        }
+
+        let self_intersections =
+            world_nurbs.offset_self_intersections(&params_u, &params_v, distance);
+        let id = self.add_surface_from_nurbs(offset, with_bbh).await;
+        Some(OffsetSurfaceResult {
+            id,
+            max_deviation: deviation.max,
+            average_deviation: deviation.average,
+            self_intersections,
+        })
+    }
+}
+
+fn uniform_params((start, end): (f32, f32), count: usize) -> Vec<f32> {
+    (0..count)
+        .map(|i| start + (end - start) * i as f32 / (count - 1) as f32)
+        .collect()
+}
+
+/// Least squares fits to a square grid of samples with more and more controls, starting from control count,
+/// until one is within tolerance. Control count is left at the count of the fit returned.
+/// Stops at half as many controls as samples, past that the fit is free to wander between the samples.
+fn fit_offset_samples(
+    points: &[Vec3],
+    sample_count: usize,
+    control_count: &mut usize,
+    tolerance: f32,
+) -> Option<(NurbsSurface, Deviation)> {
+    let max_control_count = sample_count.div_ceil(2);
+    loop {
+        let (surface, deviation) = fit_surface(
+            points,
+            sample_count,
+            sample_count,
+            FIT_DEGREE,
+            FIT_DEGREE,
+            *control_count,
+            *control_count,
+        )?;
+        if deviation.max <= tolerance || *control_count >= max_control_count {
+            return Some((surface, deviation));
+        }
+        *control_count = (*control_count * 2).min(max_control_count);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    geometry::{utils::placement, GeometryId},
+    math::{
+        linear_algebra::{mat4::Mat4, vec3::Vec3},
+        nurbs::surface::NurbsSurface,
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl Scene {
+    /// Rectangle with a corner at origin, width along axis_x and height along axis_y.
+    /// axis_y only needs to be in the plane, it is made perpendicular to axis_x.
+    #[wasm_bindgen]
+    pub async fn add_rectangle(
+        &self,
+        origin: &[f32],
+        axis_x: &[f32],
+        axis_y: &[f32],
+        width: f32,
+        height: f32,
+        with_bbh: bool,
+    ) -> GeometryId {
+        let axis_x: Vec3 = axis_x.into();
+        let normal = Vec3::cross(&axis_x, &axis_y.into());
+        let Some(placement) = placement(&origin.into(), &axis_x, &normal) else {
+            log::info!("create rectangle failed");
+            return 0;
+        };
+        if width == 0.0 || height == 0.0 {
+            log::info!("create rectangle failed");
+            return 0;
+        }
+        self.add_surface_from_nurbs(create_rectangle_nurbs(&placement, width, height), with_bbh)
+            .await
+    }
+}
+
+/// Flat bilinear patch, u along the width and v along the height.
+/// Local coordinates are mapped by placement.
+pub fn create_rectangle_nurbs(placement: &Mat4, width: f32, height: f32) -> NurbsSurface {
+    let weighted_controls = [
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(width, 0.0, 0.0),
+        Vec3::new(0.0, height, 0.0),
+        Vec3::new(width, height, 0.0),
+    ]
+    .iter()
+    .map(|corner| placement.transform_point(corner).append(1.0))
+    .collect();
+    NurbsSurface::new(
+        1,
+        1,
+        2,
+        2,
+        weighted_controls,
+        vec![0.0, 0.0, 1.0, 1.0],
+        vec![0.0, 0.0, 1.0, 1.0],
+    )
+}
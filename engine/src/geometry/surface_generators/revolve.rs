@@ -0,0 +1,109 @@
+use crate::{
+    geometry::{curve_generators::arc::create_arc_nurbs, GeometryId},
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{curve::NurbsCurve, surface::NurbsSurface},
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl Scene {
+    /// Revolves a curve around an axis, counter clockwise looking down axis_dir.
+    /// Angles are in radians. Returns 0 if there is no such curve.
+    #[wasm_bindgen]
+    pub async fn add_revolve(
+        &self,
+        profile_curve_id: GeometryId,
+        axis_origin: &[f32],
+        axis_dir: &[f32],
+        start_angle: f32,
+        end_angle: f32,
+        with_bbh: bool,
+    ) -> GeometryId {
+        let Some(profile) = self.get_curve_nurbs(profile_curve_id) else {
+            log::info!("revolve failed, no profile curve");
+            return 0;
+        };
+        let axis_dir: Vec3 = axis_dir.into();
+        if axis_dir.len() == 0.0 || start_angle == end_angle {
+            log::info!("revolve failed");
+            return 0;
+        }
+        let surface = create_revolve_nurbs(
+            &profile,
+            axis_origin.into(),
+            axis_dir,
+            start_angle,
+            end_angle,
+        );
+        self.add_surface_from_nurbs(surface, with_bbh).await
+    }
+}
+
+/// Exact surface of revolution, A8.1.
+/// u follows the profile and v goes around the axis.
+pub fn create_revolve_nurbs(
+    profile: &NurbsCurve,
+    axis_origin: Vec3,
+    axis_dir: Vec3,
+    start_angle: f32,
+    end_angle: f32,
+) -> NurbsSurface {
+    let axis_dir = axis_dir.to_normalized();
+    let x_axis = Vec3::any_perpendicular(&axis_dir);
+    let y_axis = Vec3::cross(&axis_dir, &x_axis);
+    // Unit arc in the xy plane, every profile control follows a scaled copy of it
+    let arc = create_arc_nurbs(
+        Vec3::new(0.0, 0.0, 0.0),
+        x_axis,
+        y_axis,
+        1.0,
+        start_angle,
+        end_angle,
+    );
+
+    let mut weighted_controls = Vec::new();
+    for arc_control in arc.weighted_controls.iter() {
+        let arc_point = arc_control.to_vec3_safe();
+        let cos = Vec3::dot(&arc_point, &x_axis);
+        let sin = Vec3::dot(&arc_point, &y_axis);
+        for profile_control in profile.weighted_controls.iter() {
+            let point = profile_control.to_vec3_safe();
+            let to_point = Vec3::subtract(&point, &axis_origin);
+            let center = Vec3::add(
+                &axis_origin,
+                &Vec3::to_scaled(&axis_dir, Vec3::dot(&to_point, &axis_dir)),
+            );
+            // Radius vector and the same rotated a quarter turn
+            let radial = Vec3::subtract(&point, &center);
+            let tangential = Vec3::cross(&axis_dir, &radial);
+            let rotated = Vec3::add(
+                &center,
+                &Vec3::add(
+                    &Vec3::to_scaled(&radial, cos),
+                    &Vec3::to_scaled(&tangential, sin),
+                ),
+            );
+            let weight = profile_control.w * arc_control.w;
+            weighted_controls.push(Vec4::new(
+                rotated.x * weight,
+                rotated.y * weight,
+                rotated.z * weight,
+                weight,
+            ));
+        }
+    }
+
+    NurbsSurface::new(
+        profile.degree,
+        arc.degree,
+        profile.weighted_controls.len() as u32,
+        arc.weighted_controls.len() as u32,
+        weighted_controls,
+        profile.knots.clone(),
+        arc.knots,
+    )
+}
@@ -0,0 +1,57 @@
+use crate::{
+    geometry::{curve_generators::arc::create_arc_nurbs, utils::placement, GeometryId},
+    math::{
+        linear_algebra::{mat4::Mat4, vec3::Vec3},
+        nurbs::surface::NurbsSurface,
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+use super::revolve::create_revolve_nurbs;
+
+#[wasm_bindgen]
+impl Scene {
+    /// Sphere around origin with its poles on axis_z, the seam is on the side axis_x points to
+    #[wasm_bindgen]
+    pub async fn add_sphere(
+        &self,
+        origin: &[f32],
+        axis_x: &[f32],
+        axis_z: &[f32],
+        radius: f32,
+        with_bbh: bool,
+    ) -> GeometryId {
+        let Some(placement) = placement(&origin.into(), &axis_x.into(), &axis_z.into()) else {
+            log::info!("create sphere failed");
+            return 0;
+        };
+        if radius <= 0.0 {
+            log::info!("create sphere failed");
+            return 0;
+        }
+        self.add_surface_from_nurbs(create_sphere_nurbs(&placement, radius), with_bbh)
+            .await
+    }
+}
+
+/// Exact sphere, u goes from the bottom pole to the top and v goes around.
+/// Local coordinates are mapped by placement.
+pub fn create_sphere_nurbs(placement: &Mat4, radius: f32) -> NurbsSurface {
+    let origin = Vec3::new(0.0, 0.0, 0.0);
+    let z_axis = Vec3::new(0.0, 0.0, 1.0);
+    // Half circle from the bottom pole through +x to the top pole
+    let profile = create_arc_nurbs(
+        origin,
+        Vec3::new(0.0, 0.0, -1.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        radius,
+        0.0,
+        std::f32::consts::PI,
+    );
+    let mut surface =
+        create_revolve_nurbs(&profile, origin, z_axis, 0.0, 2.0 * std::f32::consts::PI);
+    surface.transform(placement);
+    surface
+}
@@ -0,0 +1,167 @@
+use crate::{
+    geometry::GeometryId,
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{curve::NurbsCurve, interpolation::interpolate, surface::NurbsSurface},
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+/// How the section turns as it moves along the rail
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SweepFrame {
+    /// Follows the curvature of the rail, flips where the rail changes which way it bends
+    Frenet = 0,
+    /// Twists as little as possible, from the double reflection method
+    RotationMinimizing = 1,
+}
+
+#[wasm_bindgen]
+impl Scene {
+    /// Moves the section along the rail, u runs along the section and v along the rail.
+    /// The section is positioned relative to the start of the rail.
+    /// Returns 0 if a curve is missing.
+    #[wasm_bindgen]
+    pub async fn add_sweep1(
+        &self,
+        rail_id: GeometryId,
+        section_id: GeometryId,
+        frame: SweepFrame,
+        with_bbh: bool,
+    ) -> GeometryId {
+        let (Some(rail), Some(section)) = (
+            self.get_curve_nurbs(rail_id),
+            self.get_curve_nurbs(section_id),
+        ) else {
+            log::info!("sweep1 failed, no curve");
+            return 0;
+        };
+        let surface = create_sweep1_nurbs(&rail, &section, frame);
+        self.add_surface_from_nurbs(surface, with_bbh).await
+    }
+}
+
+struct Frame {
+    origin: Vec3,
+    normal: Vec3,
+    binormal: Vec3,
+    tangent: Vec3,
+}
+
+impl Frame {
+    fn new(origin: Vec3, tangent: Vec3, normal: Vec3) -> Self {
+        // Keep the normal exactly perpendicular to the tangent
+        let normal = Vec3::subtract(
+            &normal,
+            &Vec3::to_scaled(&tangent, Vec3::dot(&normal, &tangent)),
+        );
+        let normal = normal.to_normalized();
+        Self {
+            origin,
+            binormal: Vec3::cross(&tangent, &normal),
+            normal,
+            tangent,
+        }
+    }
+
+    fn to_local(&self, point: &Vec3) -> Vec3 {
+        let d = Vec3::subtract(point, &self.origin);
+        Vec3::new(
+            Vec3::dot(&d, &self.normal),
+            Vec3::dot(&d, &self.binormal),
+            Vec3::dot(&d, &self.tangent),
+        )
+    }
+
+    fn to_world(&self, local: &Vec3) -> Vec3 {
+        let mut res = Vec3::add(&self.origin, &Vec3::to_scaled(&self.normal, local.x));
+        res = Vec3::add(&res, &Vec3::to_scaled(&self.binormal, local.y));
+        Vec3::add(&res, &Vec3::to_scaled(&self.tangent, local.z))
+    }
+}
+
+/// Frames at params along the rail.
+/// Where the rail is straight the Frenet normal is undefined, the previous normal is carried over.
+fn rail_frames(rail: &NurbsCurve, params: &[f32], frame_type: SweepFrame) -> Vec<Frame> {
+    let curvature_normal = |t: f32| {
+        let curvature = rail.curvature(t);
+        (curvature.len() > 1e-6).then(|| curvature.to_normalized())
+    };
+
+    let tangent = rail.tangent(params[0]);
+    let normal = curvature_normal(params[0]).unwrap_or_else(|| Vec3::any_perpendicular(&tangent));
+    let mut frames = vec![Frame::new(rail.point(params[0]), tangent, normal)];
+
+    for t in params[1..].iter() {
+        let previous = &frames[frames.len() - 1];
+        let origin = rail.point(*t);
+        let tangent = rail.tangent(*t);
+        let normal = match frame_type {
+            SweepFrame::Frenet => curvature_normal(*t).unwrap_or(previous.normal),
+            SweepFrame::RotationMinimizing => {
+                let reflect = |v: &Vec3, axis: &Vec3| {
+                    let c = Vec3::dot(axis, axis);
+                    if c == 0.0 {
+                        *v
+                    } else {
+                        Vec3::subtract(v, &Vec3::to_scaled(axis, 2.0 * Vec3::dot(axis, v) / c))
+                    }
+                };
+                // Reflect across the plane between the two origins, then across the one between the tangents
+                let v1 = Vec3::subtract(&origin, &previous.origin);
+                let normal_l = reflect(&previous.normal, &v1);
+                let tangent_l = reflect(&previous.tangent, &v1);
+                reflect(&normal_l, &Vec3::subtract(&tangent, &tangent_l))
+            }
+        };
+        frames.push(Frame::new(origin, tangent, normal));
+    }
+    frames
+}
+
+/// One rail sweep.
+/// The section is placed at stations along the rail by the chosen frame, then the stations are interpolated in v.
+pub fn create_sweep1_nurbs(
+    rail: &NurbsCurve,
+    section: &NurbsCurve,
+    frame_type: SweepFrame,
+) -> NurbsSurface {
+    let (start, end) = rail.domain();
+    let station_count = (4 * rail.weighted_controls.len()).clamp(16, 64);
+    let params: Vec<f32> = (0..station_count)
+        .map(|i| i as f32 / (station_count - 1) as f32)
+        .collect();
+    let rail_params: Vec<f32> = params.iter().map(|t| start + (end - start) * t).collect();
+    let frames = rail_frames(rail, &rail_params, frame_type);
+
+    // Section controls relative to the first frame, weights stay in w
+    let local: Vec<Vec4> = section
+        .weighted_controls
+        .iter()
+        .map(|control| {
+            frames[0]
+                .to_local(&control.to_vec3_safe())
+                .append(control.w)
+        })
+        .collect();
+
+    let curves_v: Vec<NurbsCurve> = local
+        .iter()
+        .map(|local| {
+            let column: Vec<Vec4> = frames
+                .iter()
+                .map(|frame| {
+                    let point = frame.to_world(&Vec3::new(local.x, local.y, local.z));
+                    Vec3::to_scaled(&point, local.w).append(local.w)
+                })
+                .collect();
+            // Distinct parameters and at least two points, so the system always has a solution
+            interpolate(&column, &params, 3).unwrap()
+        })
+        .collect();
+
+    NurbsSurface::from_curves_v(curves_v, section.degree, section.knots.clone())
+}
@@ -0,0 +1,290 @@
+use crate::{
+    geometry::GeometryId,
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{
+            compatibility::make_compatible,
+            curve::NurbsCurve,
+            interpolation::{interpolate, interpolate_closed},
+            surface::NurbsSurface,
+        },
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Sweep2Options {
+    /// Sections stretch to reach both rails but keep their height instead of scaling evenly
+    pub keep_height: bool,
+    /// The rails are closed and the surface continues from their end back to their start
+    pub closed: bool,
+}
+
+#[wasm_bindgen]
+impl Sweep2Options {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[wasm_bindgen]
+impl Scene {
+    /// Moves the sections along both rails, u runs along the sections and v along the rails.
+    /// Sections should start on rail_a and end on rail_b. Returns 0 if a curve is missing.
+    #[wasm_bindgen]
+    pub async fn add_sweep2(
+        &self,
+        rail_a_id: GeometryId,
+        rail_b_id: GeometryId,
+        section_ids: &[GeometryId],
+        options: &Sweep2Options,
+        with_bbh: bool,
+    ) -> GeometryId {
+        let (Some(rail_a), Some(rail_b)) = (
+            self.get_curve_nurbs(rail_a_id),
+            self.get_curve_nurbs(rail_b_id),
+        ) else {
+            log::info!("sweep2 failed, no rail curve");
+            return 0;
+        };
+        let mut sections = Vec::with_capacity(section_ids.len());
+        for id in section_ids {
+            let Some(section) = self.get_curve_nurbs(*id) else {
+                log::info!("sweep2 failed, no section curve {}", id);
+                return 0;
+            };
+            sections.push(section);
+        }
+        let Some(surface) = create_sweep2_nurbs(&rail_a, &rail_b, &sections, options) else {
+            log::info!("sweep2 failed");
+            return 0;
+        };
+        self.add_surface_from_nurbs(surface, with_bbh).await
+    }
+}
+
+/// Rail distance relative to the coordinates below which the rails meet
+const WIDTH_EPSILON: f32 = 1e-6;
+
+/// Frame between the two rails, x runs from a to b and z follows the rails
+struct RailFrame {
+    origin: Vec3,
+    x_axis: Vec3,
+    y_axis: Vec3,
+    z_axis: Vec3,
+    width: f32,
+}
+
+impl RailFrame {
+    /// Frame at the same parameter on both rails, None where they meet
+    fn at(rail_a: &NurbsCurve, rail_b: &NurbsCurve, t: f32) -> Option<Self> {
+        let a = rail_a.point(t);
+        let chord = Vec3::subtract(&rail_b.point(t), &a);
+        let width = chord.len();
+        if width <= WIDTH_EPSILON * (1.0 + a.len()) {
+            return None;
+        }
+        let x_axis = Vec3::to_scaled(&chord, 1.0 / width);
+        let along = Vec3::add(&rail_a.tangent(t), &rail_b.tangent(t));
+        let mut z_axis = Vec3::subtract(
+            &along,
+            &Vec3::to_scaled(&x_axis, Vec3::dot(&along, &x_axis)),
+        );
+        if z_axis.len() < 1e-6 {
+            z_axis = Vec3::any_perpendicular(&x_axis);
+        }
+        let z_axis = z_axis.to_normalized();
+        let y_axis = Vec3::cross(&z_axis, &x_axis);
+        Some(Self {
+            origin: a,
+            x_axis,
+            y_axis,
+            z_axis,
+            width,
+        })
+    }
+
+    /// Coordinates in the frame, the x coordinate is a fraction of the width
+    fn to_local(&self, point: &Vec3, keep_height: bool) -> Vec3 {
+        let d = Vec3::subtract(point, &self.origin);
+        let height_scale = if keep_height { 1.0 } else { self.width };
+        Vec3::new(
+            Vec3::dot(&d, &self.x_axis) / self.width,
+            Vec3::dot(&d, &self.y_axis) / height_scale,
+            Vec3::dot(&d, &self.z_axis) / height_scale,
+        )
+    }
+
+    fn to_world(&self, local: &Vec3, keep_height: bool) -> Vec3 {
+        let height_scale = if keep_height { 1.0 } else { self.width };
+        let mut res = Vec3::add(
+            &self.origin,
+            &Vec3::to_scaled(&self.x_axis, local.x * self.width),
+        );
+        res = Vec3::add(&res, &Vec3::to_scaled(&self.y_axis, local.y * height_scale));
+        Vec3::add(&res, &Vec3::to_scaled(&self.z_axis, local.z * height_scale))
+    }
+}
+
+/// Parameter on curve closest to point, found by sampling then narrowing in
+fn closest_param(curve: &NurbsCurve, point: &Vec3) -> f32 {
+    let (start, end) = curve.domain();
+    let distance = |t: f32| Vec3::subtract(&curve.point(t), point).len();
+    let sample_count = 200;
+    let step = (end - start) / sample_count as f32;
+    let mut best = (0..=sample_count)
+        .map(|i| start + step * i as f32)
+        .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+        .unwrap();
+    let (mut low, mut high) = ((best - step).max(start), (best + step).min(end));
+    for _ in 0..30 {
+        let third = (high - low) / 3.0;
+        if distance(low + third) < distance(high - third) {
+            high -= third;
+        } else {
+            low += third;
+        }
+        best = (low + high) / 2.0;
+    }
+    best
+}
+
+/// Two rail sweep.
+/// Every section is stored relative to the rails where it starts, the sections are blended between
+/// and placed at stations along the rails, then the stations are interpolated in v.
+/// Where the rails meet between sections the section collapses to the point they meet at.
+/// None without sections or if a section starts where the rails meet.
+pub fn create_sweep2_nurbs(
+    rail_a: &NurbsCurve,
+    rail_b: &NurbsCurve,
+    sections: &[NurbsCurve],
+    options: &Sweep2Options,
+) -> Option<NurbsSurface> {
+    if sections.is_empty() {
+        return None;
+    }
+    let mut rail_a = rail_a.clone();
+    let mut rail_b = rail_b.clone();
+    rail_a.reparameterize(0.0, 1.0);
+    rail_b.reparameterize(0.0, 1.0);
+    let keep_height = options.keep_height;
+
+    let mut compatible = sections.to_vec();
+    make_compatible(&mut compatible);
+
+    // Sections in local coordinates ordered along the rails, weights stay in w
+    let mut placed: Vec<(f32, Vec<Vec4>)> = Vec::with_capacity(sections.len());
+    for (section, compatible) in sections.iter().zip(compatible.iter()) {
+        let (start, _) = section.domain();
+        let t = closest_param(&rail_a, &section.point(start));
+        let frame = RailFrame::at(&rail_a, &rail_b, t)?;
+        let local = compatible
+            .weighted_controls
+            .iter()
+            .map(|control| {
+                frame
+                    .to_local(&control.to_vec3_safe(), keep_height)
+                    .append(control.w)
+            })
+            .collect();
+        placed.push((t, local));
+    }
+    placed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let degree_u = compatible[0].degree;
+    let knots_u = compatible[0].knots.clone();
+
+    let control_count = rail_a
+        .weighted_controls
+        .len()
+        .max(rail_b.weighted_controls.len());
+    let station_count = (4 * control_count).clamp(8, 64);
+    let station_params: Vec<f32> = if options.closed {
+        (0..station_count)
+            .map(|i| i as f32 / station_count as f32)
+            .collect()
+    } else {
+        (0..station_count)
+            .map(|i| i as f32 / (station_count - 1) as f32)
+            .collect()
+    };
+
+    // One row of weighted controls per station
+    let mut stations: Vec<Vec<Vec4>> = Vec::with_capacity(station_count);
+    for t in station_params.iter() {
+        let frame = RailFrame::at(&rail_a, &rail_b, *t);
+        let local = blend_sections(&placed, *t, options.closed);
+        stations.push(
+            local
+                .iter()
+                .map(|local| {
+                    let point = match &frame {
+                        Some(frame) => {
+                            frame.to_world(&Vec3::new(local.x, local.y, local.z), keep_height)
+                        }
+                        None => rail_a.point(*t),
+                    };
+                    Vec3::to_scaled(&point, local.w).append(local.w)
+                })
+                .collect(),
+        );
+    }
+
+    let curves_v = (0..stations[0].len())
+        .map(|i| {
+            let column: Vec<Vec4> = stations.iter().map(|row| row[i]).collect();
+            if options.closed {
+                let mut params = station_params.clone();
+                params.push(1.0);
+                interpolate_closed(&column, &params, 3)
+            } else {
+                interpolate(&column, &station_params, 3)
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(NurbsSurface::from_curves_v(curves_v, degree_u, knots_u))
+}
+
+/// Local section at rail parameter t, linear between the placed sections on either side.
+/// Open rails hold the first and last section past their ends, closed rails blend across the seam.
+fn blend_sections(placed: &[(f32, Vec<Vec4>)], t: f32, closed: bool) -> Vec<Vec4> {
+    let lerp = |a: &[Vec4], b: &[Vec4], s: f32| -> Vec<Vec4> {
+        a.iter()
+            .zip(b.iter())
+            .map(|(a, b)| Vec4::lerp(a, b, s))
+            .collect()
+    };
+
+    let (first_t, first) = &placed[0];
+    let (last_t, last) = &placed[placed.len() - 1];
+    if let Some(i) = placed
+        .windows(2)
+        .position(|pair| pair[0].0 <= t && t <= pair[1].0)
+    {
+        let (t0, a) = &placed[i];
+        let (t1, b) = &placed[i + 1];
+        let s = if t1 > t0 { (t - t0) / (t1 - t0) } else { 0.0 };
+        return lerp(a, b, s);
+    }
+    if !closed || placed.len() == 1 {
+        return if t < *first_t {
+            first.clone()
+        } else {
+            last.clone()
+        };
+    }
+
+    // Across the seam from the last section to the first
+    let gap = first_t + 1.0 - last_t;
+    let past_last = if t > *last_t {
+        t - last_t
+    } else {
+        t + 1.0 - last_t
+    };
+    let s = if gap > 0.0 { past_last / gap } else { 0.0 };
+    lerp(last, first, s)
+}
@@ -0,0 +1,66 @@
+use crate::{
+    geometry::{curve_generators::arc::create_arc_nurbs, utils::placement, GeometryId},
+    math::{
+        linear_algebra::{mat4::Mat4, vec3::Vec3},
+        nurbs::surface::NurbsSurface,
+    },
+    scene::scene_interface::Scene,
+};
+
+use wasm_bindgen::prelude::*;
+
+use super::revolve::create_revolve_nurbs;
+
+#[wasm_bindgen]
+impl Scene {
+    /// Torus around origin with axis_z through its hole, the seam is on the side axis_x points to.
+    /// major_radius is from origin to the middle of the tube, minor_radius is the radius of the tube.
+    #[wasm_bindgen]
+    pub async fn add_torus(
+        &self,
+        origin: &[f32],
+        axis_x: &[f32],
+        axis_z: &[f32],
+        major_radius: f32,
+        minor_radius: f32,
+        with_bbh: bool,
+    ) -> GeometryId {
+        let Some(placement) = placement(&origin.into(), &axis_x.into(), &axis_z.into()) else {
+            log::info!("create torus failed");
+            return 0;
+        };
+        if minor_radius <= 0.0 || major_radius <= 0.0 {
+            log::info!("create torus failed");
+            return 0;
+        }
+        self.add_surface_from_nurbs(
+            create_torus_nurbs(&placement, major_radius, minor_radius),
+            with_bbh,
+        )
+        .await
+    }
+}
+
+/// Exact torus, u goes around the tube and v goes around the axis.
+/// Local coordinates are mapped by placement.
+pub fn create_torus_nurbs(placement: &Mat4, major_radius: f32, minor_radius: f32) -> NurbsSurface {
+    let origin = Vec3::new(0.0, 0.0, 0.0);
+    // Tube circle starting on the outside of the torus
+    let profile = create_arc_nurbs(
+        Vec3::new(major_radius, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        minor_radius,
+        0.0,
+        2.0 * std::f32::consts::PI,
+    );
+    let mut surface = create_revolve_nurbs(
+        &profile,
+        origin,
+        Vec3::new(0.0, 0.0, 1.0),
+        0.0,
+        2.0 * std::f32::consts::PI,
+    );
+    surface.transform(placement);
+    surface
+}
@@ -1,3 +1,5 @@
+use crate::math::linear_algebra::{mat4::Mat4, vec3::Vec3};
+
 pub fn default_knot_vector(control_count: usize, degree: u32) -> Vec<f32> {
     let mut res = vec![0.0; degree as usize + 1];
     for i in 1..control_count - degree as usize {
@@ -8,3 +10,22 @@ pub fn default_knot_vector(control_count: usize, degree: u32) -> Vec<f32> {
     }
     res
 }
+
+/// Moves local coordinates to origin with z along axis_z and x as close to axis_x as possible.
+/// None when axis_z has no length.
+pub fn placement(origin: &Vec3, axis_x: &Vec3, axis_z: &Vec3) -> Option<Mat4> {
+    if axis_z.len() == 0.0 {
+        return None;
+    }
+    let z = axis_z.to_normalized();
+    let mut x = Vec3::subtract(axis_x, &Vec3::to_scaled(&z, Vec3::dot(axis_x, &z)));
+    if x.len() < 1e-6 * axis_x.len().max(1.0) {
+        x = Vec3::any_perpendicular(&z);
+    }
+    let x = x.to_normalized();
+    let y = Vec3::cross(&z, &x);
+    Some(Mat4::new(&[
+        x.x, x.y, x.z, 0.0, y.x, y.y, y.z, 0.0, z.x, z.y, z.z, 0.0, origin.x, origin.y, origin.z,
+        1.0,
+    ]))
+}
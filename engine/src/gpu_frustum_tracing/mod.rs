@@ -0,0 +1,47 @@
+pub mod select;
+pub mod select_lines;
+pub mod select_mesh;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{math::geometry::frustum::Frustum, utils::PendingReadback};
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SelectionMode {
+    /// Selects geometry that is fully inside the rectangle
+    Window = 0,
+    /// Selects geometry that touches the rectangle
+    Crossing = 1,
+}
+
+/// Frustum layout expected by the selection shaders
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GpuFrustum {
+    origin: [f32; 4],
+    normals: [[f32; 4]; 4],
+    edges: [[f32; 4]; 4],
+}
+
+impl From<&Frustum> for GpuFrustum {
+    fn from(frustum: &Frustum) -> Self {
+        let o = frustum.get_origin();
+        let normals = frustum.get_normals();
+        let edges = frustum.get_edges();
+        GpuFrustum {
+            origin: [o.x, o.y, o.z, 1.0],
+            normals: normals.map(|n| [n.x, n.y, n.z, 0.0]),
+            edges: edges.map(|e| [e.x, e.y, e.z, 0.0]),
+        }
+    }
+}
+
+/// Reads the result of submit_select_mesh or submit_select_lines.
+pub async fn read_selected(readback: PendingReadback) -> bool {
+    readback
+        .read::<u32>()
+        .await
+        .first()
+        .is_some_and(|selected| *selected != 0)
+}
@@ -0,0 +1,143 @@
+//! Rectangle selection.
+//!
+//! Surfaces and meshes are traced through their bbh, which gets built on first use if missing.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    geometry::{Geometry, GeometryId},
+    instance::INSTANCES,
+    math::geometry::frustum::Frustum,
+    scene::scene_interface::Scene,
+    utils::PendingReadback,
+    viewport::viewport_interface::Viewport,
+};
+
+use super::{read_selected, SelectionMode};
+
+#[wasm_bindgen]
+impl Scene {
+    /// x and y are in pixels from the top left corner of the viewports canvas.
+    /// Returns every geometry selected by the rectangle.
+    pub async fn select(
+        &self,
+        viewport: &Viewport,
+        x0: f32,
+        y0: f32,
+        x1: f32,
+        y1: f32,
+        mode: SelectionMode,
+    ) -> Vec<GeometryId> {
+        // The instance is only locked while submitting, the readbacks are awaited without it
+        let pending = {
+            let mut instances = INSTANCES.lock().unwrap();
+            let instance = instances.get_mut(&self.get_instance_handle()).unwrap();
+
+            let frustum = instance
+                .get_viewport(viewport.get_handle())
+                .pixel_rect_to_frustum(x0, y0, x1, y1);
+
+            let mesh_selector = instance.get_mesh_selector();
+            let lines_selector = instance.get_lines_selector();
+            let bbh_generator = instance.get_mesh_bbh_generator();
+
+            let scene = instance.get_scene_mut(self.get_handle());
+            scene.build_missing_bbhs(&bbh_generator);
+
+            let mut pending: Vec<(GeometryId, PendingReadback)> = Vec::new();
+
+            for (id, surface) in scene.get_surfaces().iter() {
+                let Some(local_frustum) = to_local(surface, &frustum) else {
+                    continue;
+                };
+                let readback = mesh_selector.submit_select_mesh(
+                    &local_frustum,
+                    mode,
+                    surface.get_bbh().unwrap(),
+                    surface.get_vertex_buffer(),
+                    surface.get_index_buffer(),
+                );
+                pending.push((*id, readback));
+            }
+
+            for (id, mesh) in scene.get_meshes().iter() {
+                let Some(local_frustum) = to_local(mesh, &frustum) else {
+                    continue;
+                };
+                let readback = mesh_selector.submit_select_mesh(
+                    &local_frustum,
+                    mode,
+                    mesh.get_bbh().unwrap(),
+                    mesh.get_vertex_buffer(),
+                    mesh.get_index_buffer(),
+                );
+                pending.push((*id, readback));
+            }
+
+            for (id, curve) in scene.get_curves().iter() {
+                let Some(local_frustum) = to_local(curve, &frustum) else {
+                    continue;
+                };
+                let readback = lines_selector.submit_select_lines(
+                    &local_frustum,
+                    mode,
+                    curve.get_vertex_buffer(),
+                    None,
+                    curve.get_vertex_count().saturating_sub(1),
+                );
+                pending.push((*id, readback));
+            }
+
+            for (id, polyline) in scene.get_polylines().iter() {
+                let Some(local_frustum) = to_local(polyline, &frustum) else {
+                    continue;
+                };
+                let readback = lines_selector.submit_select_lines(
+                    &local_frustum,
+                    mode,
+                    polyline.get_vertex_buffer(),
+                    None,
+                    polyline.get_vertex_count().saturating_sub(1),
+                );
+                pending.push((*id, readback));
+            }
+
+            for (id, lines) in scene.get_lines().iter() {
+                let Some(local_frustum) = to_local(lines, &frustum) else {
+                    continue;
+                };
+                let readback = lines_selector.submit_select_lines(
+                    &local_frustum,
+                    mode,
+                    lines.get_vertex_buffer(),
+                    Some(lines.get_index_buffer()),
+                    lines.get_index_count() / 2,
+                );
+                pending.push((*id, readback));
+            }
+
+            pending
+        };
+
+        let mut selected: Vec<GeometryId> = Vec::new();
+        for (id, readback) in pending {
+            if read_selected(readback).await {
+                selected.push(id);
+            }
+        }
+
+        selected
+    }
+}
+
+/// Moves a world space frustum into the geometries model space.
+/// None, with a warning, if the model can not be inverted.
+fn to_local(geometry: &dyn Geometry, frustum: &Frustum) -> Option<Frustum> {
+    let Some(inverse) = geometry.get_bind_group_object().get_model().inverse() else {
+        log::warn!("skipping geometry with a model matrix that is not invertible");
+        return None;
+    };
+    let mut local = *frustum;
+    local.transform(inverse);
+    Some(local)
+}
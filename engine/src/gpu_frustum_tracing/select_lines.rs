@@ -0,0 +1,185 @@
+//! Window and crossing selection of line strips and line lists.
+//!
+//! One invocation per segment, any segment can decide the result.
+
+use std::rc::Rc;
+
+use wgpu::util::DeviceExt;
+
+use crate::{
+    math::geometry::frustum::Frustum,
+    render::renderer::Renderer,
+    utils::{create_compute_pipeline, dispatch_size_3d, PendingReadback},
+};
+
+use super::{read_selected, GpuFrustum, SelectionMode};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct SelectLinesUniforms {
+    frustum: GpuFrustum,
+    mode: u32,
+    segment_count: u32,
+    indexed: u32,
+    _padding: u32,
+}
+
+pub struct LinesSelector {
+    renderer: Rc<Renderer>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+    // Bound in place of the index buffer for line strips
+    empty_index_buffer: wgpu::Buffer,
+}
+
+impl LinesSelector {
+    pub fn new(renderer: Rc<Renderer>) -> Self {
+        let device = renderer.get_device();
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("select lines bind group layout"),
+            entries: &[
+                // Params
+                crate::utils::compute_uniform_bind_group_layout_entry(0),
+                // Vertices
+                crate::utils::compute_buffer_bind_group_layout_entry(1, true),
+                // Indices
+                crate::utils::compute_buffer_bind_group_layout_entry(2, true),
+                // Result
+                crate::utils::compute_buffer_bind_group_layout_entry(3, false),
+            ],
+        });
+
+        let pipeline = create_compute_pipeline(
+            device,
+            "select lines",
+            include_str!("select_lines.wgsl"),
+            &bind_group_layout,
+            "main",
+        );
+
+        let empty_index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("select lines empty index buffer"),
+            contents: bytemuck::cast_slice(&[0u32; 2]),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        Self {
+            renderer,
+            bind_group_layout,
+            pipeline,
+            empty_index_buffer,
+        }
+    }
+
+    /// Frustum must be in the geometries model space.
+    /// Vertices are vec4s and are divided by w, so curve samples can be used directly.
+    /// Pass an index buffer for line lists, or None for a line strip.
+    /// Buffers must have been created with the STORAGE usage.
+    pub async fn select_lines(
+        &self,
+        frustum: &Frustum,
+        mode: SelectionMode,
+        vertex_buffer: &wgpu::Buffer,
+        index_buffer: Option<&wgpu::Buffer>,
+        segment_count: u32,
+    ) -> bool {
+        read_selected(self.submit_select_lines(
+            frustum,
+            mode,
+            vertex_buffer,
+            index_buffer,
+            segment_count,
+        ))
+        .await
+    }
+
+    /// Submits select_lines, read the result with read_selected.
+    pub fn submit_select_lines(
+        &self,
+        frustum: &Frustum,
+        mode: SelectionMode,
+        vertex_buffer: &wgpu::Buffer,
+        index_buffer: Option<&wgpu::Buffer>,
+        segment_count: u32,
+    ) -> PendingReadback {
+        if segment_count == 0 {
+            return PendingReadback::empty();
+        }
+
+        let device = self.renderer.get_device();
+        let result_size = std::mem::size_of::<u32>() as u64;
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("select lines params"),
+            contents: bytemuck::cast_slice(&[SelectLinesUniforms {
+                frustum: GpuFrustum::from(frustum),
+                mode: mode as u32,
+                segment_count,
+                indexed: index_buffer.is_some() as u32,
+                _padding: 0,
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        // Window selection holds until a segment leaves, crossing until one enters
+        let initial = (mode == SelectionMode::Window) as u32;
+        let result = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("select lines result"),
+            contents: bytemuck::cast_slice(&[initial]),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("select lines"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: vertex_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: index_buffer
+                        .unwrap_or(&self.empty_index_buffer)
+                        .as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: result.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("select lines"),
+        });
+
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("select lines"),
+                timestamp_writes: None,
+            });
+
+            compute_pass.set_pipeline(&self.pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[]);
+            let size = dispatch_size_3d(segment_count);
+            compute_pass.dispatch_workgroups(size, size, size);
+        }
+
+        PendingReadback::submit(
+            device,
+            self.renderer.get_queue(),
+            encoder,
+            &result,
+            result_size,
+        )
+    }
+
+    pub fn get_renderer(&self) -> Rc<Renderer> {
+        self.renderer.clone()
+    }
+}
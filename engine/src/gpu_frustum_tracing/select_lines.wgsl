@@ -0,0 +1,89 @@
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> vertices: array<vec4<f32>>;
+@group(0) @binding(2) var<storage, read> indices: array<u32>;
+// Starts at 1 for window and 0 for crossing
+@group(0) @binding(3) var<storage, read_write> result: atomic<u32>;
+
+struct Frustum {
+  origin: vec4<f32>,
+  // Inward facing
+  normals: array<vec4<f32>, 4>,
+  edges: array<vec4<f32>, 4>,
+}
+
+struct Params {
+  frustum: Frustum,
+  // 0 for window, 1 for crossing
+  mode: u32,
+  segment_count: u32,
+  // 0 for a line strip, 1 for a line list
+  indexed: u32,
+}
+
+const WINDOW = 0u;
+
+fn contains_point(p: vec3<f32>) -> bool {
+  let v = p - params.frustum.origin.xyz;
+  for (var i = 0u; i < 4u; i++) {
+    if (dot(params.frustum.normals[i].xyz, v) < 0.0) {
+      return false;
+    }
+  }
+  return true;
+}
+
+fn contains_segment_partially(a: vec3<f32>, b: vec3<f32>) -> bool {
+  let va = a - params.frustum.origin.xyz;
+  let vb = b - params.frustum.origin.xyz;
+  var t_enter = 0.0;
+  var t_exit = 1.0;
+  for (var i = 0u; i < 4u; i++) {
+    let da = dot(params.frustum.normals[i].xyz, va);
+    let db = dot(params.frustum.normals[i].xyz, vb);
+    if (da < 0.0 && db < 0.0) {
+      return false;
+    }
+    if (da < 0.0) {
+      t_enter = max(t_enter, da / (da - db));
+    } else if (db < 0.0) {
+      t_exit = min(t_exit, da / (da - db));
+    }
+  }
+  return t_enter <= t_exit;
+}
+
+fn get_segment_index(segment: u32, end: u32) -> u32 {
+  if (params.indexed == 0u) {
+    return segment + end;
+  }
+  return indices[segment * 2u + end];
+}
+
+// Vertices can be weighted, like curve samples.
+fn get_vertex(index: u32) -> vec3<f32> {
+  let v = vertices[index];
+  return v.xyz / v.w;
+}
+
+@compute @workgroup_size(1,1,1)
+fn main(
+  @builtin(global_invocation_id) id: vec3<u32>,
+  @builtin(num_workgroups) size: vec3<u32>,
+  ) {
+
+  let index = id.x + id.y * size.x + id.z * size.x * size.y;
+  if (index >= params.segment_count) {
+    return;
+  }
+
+  let a = get_vertex(get_segment_index(index, 0u));
+  let b = get_vertex(get_segment_index(index, 1u));
+
+  if (params.mode == WINDOW) {
+    if (!contains_point(a) || !contains_point(b)) {
+      atomicStore(&result, 0u);
+    }
+  } else if (contains_segment_partially(a, b)) {
+    atomicStore(&result, 1u);
+  }
+}
@@ -0,0 +1,170 @@
+//! Window and crossing selection of a mesh through its MeshBBH.
+//!
+//! The tree is walked on the GPU so whole subtrees that are fully inside
+//! or fully outside of the frustum are never looked at triangle by triangle.
+
+use std::rc::Rc;
+
+use wgpu::util::DeviceExt;
+
+use crate::{
+    gpu_acceleration_structures::mesh_bbh::MeshBBH,
+    math::geometry::frustum::Frustum,
+    render::renderer::Renderer,
+    utils::{create_compute_pipeline, PendingReadback},
+};
+
+use super::{read_selected, GpuFrustum, SelectionMode};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct SelectMeshUniforms {
+    frustum: GpuFrustum,
+    mode: u32,
+    _padding: [u32; 3],
+}
+
+pub struct MeshSelector {
+    renderer: Rc<Renderer>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl MeshSelector {
+    pub fn new(renderer: Rc<Renderer>) -> Self {
+        let device = renderer.get_device();
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("select mesh bind group layout"),
+            entries: &[
+                // Params
+                crate::utils::compute_uniform_bind_group_layout_entry(0),
+                // Tree
+                crate::utils::compute_buffer_bind_group_layout_entry(1, true),
+                // BBH indices
+                crate::utils::compute_buffer_bind_group_layout_entry(2, true),
+                // Vertex buffer
+                crate::utils::compute_buffer_bind_group_layout_entry(3, true),
+                // Index buffer
+                crate::utils::compute_buffer_bind_group_layout_entry(4, true),
+                // Result
+                crate::utils::compute_buffer_bind_group_layout_entry(5, false),
+            ],
+        });
+
+        let pipeline = create_compute_pipeline(
+            device,
+            "select mesh",
+            include_str!("select_mesh.wgsl"),
+            &bind_group_layout,
+            "main",
+        );
+
+        Self {
+            renderer,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    /// Frustum must be in the meshes model space.
+    /// Vertex and index buffers must have been created with the STORAGE usage.
+    pub async fn select_mesh(
+        &self,
+        frustum: &Frustum,
+        mode: SelectionMode,
+        bbh: &MeshBBH,
+        vertex_buffer: &wgpu::Buffer,
+        index_buffer: &wgpu::Buffer,
+    ) -> bool {
+        read_selected(self.submit_select_mesh(frustum, mode, bbh, vertex_buffer, index_buffer))
+            .await
+    }
+
+    /// Submits select_mesh, read the result with read_selected.
+    pub fn submit_select_mesh(
+        &self,
+        frustum: &Frustum,
+        mode: SelectionMode,
+        bbh: &MeshBBH,
+        vertex_buffer: &wgpu::Buffer,
+        index_buffer: &wgpu::Buffer,
+    ) -> PendingReadback {
+        let device = self.renderer.get_device();
+        let result_size = std::mem::size_of::<u32>() as u64;
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("select mesh params"),
+            contents: bytemuck::cast_slice(&[SelectMeshUniforms {
+                frustum: GpuFrustum::from(frustum),
+                mode: mode as u32,
+                _padding: [0; 3],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let result = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("select mesh result"),
+            size: result_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("select mesh"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: bbh.get_tree().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: bbh.get_indices().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: vertex_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: index_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: result.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("select mesh"),
+        });
+
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("select mesh"),
+                timestamp_writes: None,
+            });
+
+            compute_pass.set_pipeline(&self.pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[]);
+            compute_pass.dispatch_workgroups(1, 1, 1);
+        }
+
+        PendingReadback::submit(
+            device,
+            self.renderer.get_queue(),
+            encoder,
+            &result,
+            result_size,
+        )
+    }
+
+    pub fn get_renderer(&self) -> Rc<Renderer> {
+        self.renderer.clone()
+    }
+}
@@ -0,0 +1,205 @@
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> bbh: array<Node>;
+@group(0) @binding(2) var<storage, read> bbh_indices: array<u32>;
+@group(0) @binding(3) var<storage, read> vertex_buffer: array<Vertex>;
+@group(0) @binding(4) var<storage, read> index_buffer: array<u32>;
+@group(0) @binding(5) var<storage, read_write> result: array<u32>;
+
+struct Frustum {
+  origin: vec4<f32>,
+  // Inward facing
+  normals: array<vec4<f32>, 4>,
+  edges: array<vec4<f32>, 4>,
+}
+
+struct Params {
+  frustum: Frustum,
+  // 0 for window, 1 for crossing
+  mode: u32,
+}
+
+struct Vertex {
+  position: vec4<f32>,
+  normal: vec4<f32>,
+}
+
+// Same layout for all of the mesh bbh generators.
+// A node is a leaf when it has no children.
+struct Node {
+  min_corner: vec3<f32>,
+  max_corner: vec3<f32>,
+  l: u32,
+  r: u32,
+  left_child: u32,
+}
+
+const WINDOW = 0u;
+const EPSILON = 0.0000001;
+// Deep enough for any tree the generators can build (they stop at 100 levels).
+const STACK_SIZE = 128u;
+
+fn contains_point(p: vec3<f32>) -> bool {
+  let v = p - params.frustum.origin.xyz;
+  for (var i = 0u; i < 4u; i++) {
+    if (dot(params.frustum.normals[i].xyz, v) < 0.0) {
+      return false;
+    }
+  }
+  return true;
+}
+
+fn contains_segment_partially(a: vec3<f32>, b: vec3<f32>) -> bool {
+  let va = a - params.frustum.origin.xyz;
+  let vb = b - params.frustum.origin.xyz;
+  var t_enter = 0.0;
+  var t_exit = 1.0;
+  for (var i = 0u; i < 4u; i++) {
+    let da = dot(params.frustum.normals[i].xyz, va);
+    let db = dot(params.frustum.normals[i].xyz, vb);
+    if (da < 0.0 && db < 0.0) {
+      return false;
+    }
+    if (da < 0.0) {
+      t_enter = max(t_enter, da / (da - db));
+    } else if (db < 0.0) {
+      t_exit = min(t_exit, da / (da - db));
+    }
+  }
+  return t_enter <= t_exit;
+}
+
+fn get_corner(min_corner: vec3<f32>, max_corner: vec3<f32>, i: u32) -> vec3<f32> {
+  return select(min_corner, max_corner, vec3<bool>((i & 1u) != 0u, (i & 2u) != 0u, (i & 4u) != 0u));
+}
+
+fn contains_box_fully(min_corner: vec3<f32>, max_corner: vec3<f32>) -> bool {
+  for (var i = 0u; i < 8u; i++) {
+    if (!contains_point(get_corner(min_corner, max_corner, i))) {
+      return false;
+    }
+  }
+  return true;
+}
+
+// Conservative, true means the box is definitely outside.
+fn box_outside(min_corner: vec3<f32>, max_corner: vec3<f32>) -> bool {
+  for (var i = 0u; i < 4u; i++) {
+    let normal = params.frustum.normals[i].xyz;
+    var all_outside = true;
+    for (var j = 0u; j < 8u; j++) {
+      if (dot(normal, get_corner(min_corner, max_corner, j) - params.frustum.origin.xyz) >= 0.0) {
+        all_outside = false;
+        break;
+      }
+    }
+    if (all_outside) {
+      return true;
+    }
+  }
+  return false;
+}
+
+// Moller Trumbore, only hits in front of the origin count.
+fn edge_hits_triangle(dir: vec3<f32>, a: vec3<f32>, b: vec3<f32>, c: vec3<f32>) -> bool {
+  let ab = b - a;
+  let ac = c - a;
+  let p = cross(dir, ac);
+  let det = dot(ab, p);
+  if (abs(det) < EPSILON) {
+    return false;
+  }
+  let inv_det = 1.0 / det;
+  let s = params.frustum.origin.xyz - a;
+  let u = dot(s, p) * inv_det;
+  if (u < 0.0 || u > 1.0) {
+    return false;
+  }
+  let q = cross(s, ab);
+  let v = dot(dir, q) * inv_det;
+  if (v < 0.0 || u + v > 1.0) {
+    return false;
+  }
+  return dot(ac, q) * inv_det >= 0.0;
+}
+
+fn contains_triangle_partially(a: vec3<f32>, b: vec3<f32>, c: vec3<f32>) -> bool {
+  if (contains_segment_partially(a, b) || contains_segment_partially(b, c) || contains_segment_partially(c, a)) {
+    return true;
+  }
+  // Triangle can cover the whole frustum
+  for (var i = 0u; i < 4u; i++) {
+    if (edge_hits_triangle(params.frustum.edges[i].xyz, a, b, c)) {
+      return true;
+    }
+  }
+  return false;
+}
+
+// Single invocation, walks the tree and stops as soon as the answer is known.
+// Writes 1 to result when the mesh is selected.
+@compute @workgroup_size(1,1,1)
+fn main() {
+
+  let window = params.mode == WINDOW;
+
+  var stack: array<u32, STACK_SIZE>;
+  var stack_size = 1u;
+  stack[0] = 0u;
+
+  while (stack_size > 0u) {
+    stack_size--;
+    let node = bbh[stack[stack_size]];
+    let is_leaf = node.left_child == 0u;
+
+    if (is_leaf && node.l == node.r) {
+      continue;
+    }
+
+    if (contains_box_fully(node.min_corner, node.max_corner)) {
+      if (window) {
+        // Everything below is inside
+        continue;
+      }
+      result[0] = 1u;
+      return;
+    }
+
+    if (box_outside(node.min_corner, node.max_corner)) {
+      if (window) {
+        result[0] = 0u;
+        return;
+      }
+      continue;
+    }
+
+    if (is_leaf) {
+      for (var i = node.l; i < node.r; i++) {
+        let triangle = bbh_indices[i];
+        let a = vertex_buffer[index_buffer[triangle * 3]].position.xyz;
+        let b = vertex_buffer[index_buffer[triangle * 3 + 1]].position.xyz;
+        let c = vertex_buffer[index_buffer[triangle * 3 + 2]].position.xyz;
+        if (window) {
+          if (!contains_point(a) || !contains_point(b) || !contains_point(c)) {
+            result[0] = 0u;
+            return;
+          }
+        } else if (contains_triangle_partially(a, b, c)) {
+          result[0] = 1u;
+          return;
+        }
+      }
+      continue;
+    }
+
+    if (stack_size + 2u > STACK_SIZE) {
+      // Tree is deeper than the stack, drop the subtree rather than overflow.
+      continue;
+    }
+
+    stack[stack_size] = node.left_child;
+    stack[stack_size + 1u] = node.left_child + 1u;
+    stack_size += 2u;
+  }
+
+  result[0] = select(0u, 1u, window);
+}
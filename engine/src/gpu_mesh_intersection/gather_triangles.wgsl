@@ -0,0 +1,48 @@
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> bbh_indices: array<u32>;
+@group(0) @binding(2) var<storage, read> vertex_buffer: array<Vertex>;
+@group(0) @binding(3) var<storage, read> index_buffer: array<u32>;
+@group(0) @binding(4) var<storage, read_write> triangles: array<Triangle>;
+
+struct Params {
+  model: mat4x4<f32>,
+  triangle_count: u32,
+}
+
+struct Vertex {
+  position: vec4<f32>,
+  normal: vec4<f32>,
+}
+
+// World space corners, stored in the order of the bbh indices
+// so a leaf covers triangles[l..r] like it covers bbh_indices[l..r].
+struct Triangle {
+  a: vec3<f32>,
+  triangle: u32,
+  b: vec3<f32>,
+  c: vec3<f32>,
+}
+
+fn to_world(vertex: u32) -> vec3<f32> {
+  return (params.model * vec4<f32>(vertex_buffer[vertex].position.xyz, 1.0)).xyz;
+}
+
+@compute @workgroup_size(1,1,1)
+fn main(
+  @builtin(global_invocation_id) id: vec3<u32>,
+  @builtin(num_workgroups) size: vec3<u32>,
+  ) {
+
+  let index = id.x + id.y * size.x + id.z * size.x * size.y;
+  if (index >= params.triangle_count) {
+    return;
+  }
+
+  let triangle = bbh_indices[index];
+  triangles[index] = Triangle(
+    to_world(index_buffer[triangle * 3]),
+    triangle,
+    to_world(index_buffer[triangle * 3 + 1]),
+    to_world(index_buffer[triangle * 3 + 2]),
+  );
+}
@@ -0,0 +1,15 @@
+//! Finding where meshes and sampled surfaces cross other geometry, through their MeshBBHs.
+
+use crate::{gpu_acceleration_structures::mesh_bbh::MeshBBH, math::linear_algebra::mat4::Mat4};
+
+pub mod overlap;
+pub mod section;
+
+/// A mesh or sampled surface with its tree and model matrix.
+/// Vertex and index buffers must have been created with the STORAGE usage.
+pub struct WorldMesh<'a> {
+    pub bbh: &'a MeshBBH,
+    pub vertex_buffer: &'a wgpu::Buffer,
+    pub index_buffer: &'a wgpu::Buffer,
+    pub model: &'a Mat4,
+}
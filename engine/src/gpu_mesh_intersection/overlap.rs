@@ -0,0 +1,286 @@
+//! Pairs of crossing triangles between two meshes.
+//!
+//! Runs in two passes so no pass binds more storage buffers than the default limits allow.
+//! The first gathers each meshes triangles in world space, in the order of its bbh indices.
+//! The second walks the tree of the second mesh from every leaf of the first
+//! and tests the triangles of the leaves whose world space boxes touch.
+
+use std::rc::Rc;
+
+use wgpu::util::DeviceExt;
+
+use crate::{
+    math::linear_algebra::mat4::Mat4,
+    render::renderer::Renderer,
+    utils::{create_compute_pipeline, dispatch_size_3d, PendingReadback},
+};
+
+use super::WorldMesh;
+
+/// Pairs past this are dropped, plenty for seeding
+const MAX_PAIRS: u32 = 1 << 16;
+/// World space corners and triangle id, matches Triangle in the shaders
+const TRIANGLE_SIZE: u64 = 48;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct GatherTrianglesUniforms {
+    model: Mat4,
+    triangle_count: u32,
+    _padding: [u32; 3],
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct OverlapUniforms {
+    model_a: Mat4,
+    model_b: Mat4,
+    node_count_a: u32,
+    max_pairs: u32,
+    _padding: [u32; 2],
+}
+
+pub struct MeshOverlapper {
+    renderer: Rc<Renderer>,
+    gather_bind_group_layout: wgpu::BindGroupLayout,
+    gather_pipeline: wgpu::ComputePipeline,
+    overlap_bind_group_layout: wgpu::BindGroupLayout,
+    overlap_pipeline: wgpu::ComputePipeline,
+}
+
+impl MeshOverlapper {
+    pub fn new(renderer: Rc<Renderer>) -> Self {
+        let device = renderer.get_device();
+        let gather_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("gather triangles bind group layout"),
+                entries: &[
+                    // Params
+                    crate::utils::compute_uniform_bind_group_layout_entry(0),
+                    // BBH indices
+                    crate::utils::compute_buffer_bind_group_layout_entry(1, true),
+                    // Vertex buffer
+                    crate::utils::compute_buffer_bind_group_layout_entry(2, true),
+                    // Index buffer
+                    crate::utils::compute_buffer_bind_group_layout_entry(3, true),
+                    // Triangles
+                    crate::utils::compute_buffer_bind_group_layout_entry(4, false),
+                ],
+            });
+        let overlap_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("overlap meshes bind group layout"),
+                entries: &[
+                    // Params
+                    crate::utils::compute_uniform_bind_group_layout_entry(0),
+                    // Tree a
+                    crate::utils::compute_buffer_bind_group_layout_entry(1, true),
+                    // Triangles a
+                    crate::utils::compute_buffer_bind_group_layout_entry(2, true),
+                    // Tree b
+                    crate::utils::compute_buffer_bind_group_layout_entry(3, true),
+                    // Triangles b
+                    crate::utils::compute_buffer_bind_group_layout_entry(4, true),
+                    // Pairs
+                    crate::utils::compute_buffer_bind_group_layout_entry(5, false),
+                ],
+            });
+
+        let gather_pipeline = create_compute_pipeline(
+            device,
+            "gather triangles",
+            include_str!("gather_triangles.wgsl"),
+            &gather_bind_group_layout,
+            "main",
+        );
+        let overlap_pipeline = create_compute_pipeline(
+            device,
+            "overlap meshes",
+            include_str!("overlap.wgsl"),
+            &overlap_bind_group_layout,
+            "main",
+        );
+
+        Self {
+            renderer,
+            gather_bind_group_layout,
+            gather_pipeline,
+            overlap_bind_group_layout,
+            overlap_pipeline,
+        }
+    }
+
+    /// Every pair of crossing triangles, triangle of a first.
+    /// Triangles touching in a plane are not reported.
+    pub async fn overlap_meshes(&self, a: &WorldMesh<'_>, b: &WorldMesh<'_>) -> Vec<(u32, u32)> {
+        read_overlap_pairs(self.submit_overlap_meshes(a, b)).await
+    }
+
+    /// Records and submits overlap_meshes, read the result with read_overlap_pairs.
+    pub fn submit_overlap_meshes(&self, a: &WorldMesh<'_>, b: &WorldMesh<'_>) -> PendingReadback {
+        let device = self.renderer.get_device();
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("overlap meshes"),
+        });
+
+        let triangles_a = self.gather_triangles(&mut encoder, a);
+        let triangles_b = self.gather_triangles(&mut encoder, b);
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("overlap meshes params"),
+            contents: bytemuck::cast_slice(&[OverlapUniforms {
+                model_a: *a.model,
+                model_b: *b.model,
+                node_count_a: a.bbh.get_node_count(),
+                max_pairs: MAX_PAIRS,
+                _padding: [0; 2],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        // Count, padding to the alignment of the pairs, then the pairs
+        let pairs_size = 8 + MAX_PAIRS as u64 * 8;
+        let pairs = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("overlap meshes pairs"),
+            size: pairs_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("overlap meshes"),
+            layout: &self.overlap_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: a.bbh.get_tree().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: triangles_a.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: b.bbh.get_tree().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: triangles_b.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: pairs.as_entire_binding(),
+                },
+            ],
+        });
+
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("overlap meshes"),
+                timestamp_writes: None,
+            });
+
+            compute_pass.set_pipeline(&self.overlap_pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[]);
+            let size = dispatch_size_3d(a.bbh.get_node_count());
+            compute_pass.dispatch_workgroups(size, size, size);
+        }
+
+        PendingReadback::submit(
+            device,
+            self.renderer.get_queue(),
+            encoder,
+            &pairs,
+            pairs_size,
+        )
+    }
+
+    /// Records the pass writing the meshes triangles in world space, in bbh index order
+    fn gather_triangles(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        mesh: &WorldMesh,
+    ) -> wgpu::Buffer {
+        let device = self.renderer.get_device();
+        let triangle_count = (mesh.bbh.get_indices().size() / 4) as u32;
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("gather triangles params"),
+            contents: bytemuck::cast_slice(&[GatherTrianglesUniforms {
+                model: *mesh.model,
+                triangle_count,
+                _padding: [0; 3],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let triangles = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gather triangles"),
+            size: (triangle_count as u64 * TRIANGLE_SIZE).max(TRIANGLE_SIZE),
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("gather triangles"),
+            layout: &self.gather_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: mesh.bbh.get_indices().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: mesh.vertex_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: mesh.index_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: triangles.as_entire_binding(),
+                },
+            ],
+        });
+
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("gather triangles"),
+                timestamp_writes: None,
+            });
+
+            compute_pass.set_pipeline(&self.gather_pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[]);
+            let size = dispatch_size_3d(triangle_count);
+            compute_pass.dispatch_workgroups(size, size, size);
+        }
+
+        triangles
+    }
+
+    pub fn get_renderer(&self) -> Rc<Renderer> {
+        self.renderer.clone()
+    }
+}
+
+/// Reads the result of submit_overlap_meshes.
+pub async fn read_overlap_pairs(readback: PendingReadback) -> Vec<(u32, u32)> {
+    let data = readback.read::<u32>().await;
+    let Some(count) = data.first() else {
+        return Vec::new();
+    };
+    let count = (*count).min(MAX_PAIRS) as usize;
+    data[2..2 + 2 * count]
+        .chunks(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect()
+}
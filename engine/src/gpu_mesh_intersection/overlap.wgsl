@@ -0,0 +1,153 @@
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> bbh_a: array<Node>;
+@group(0) @binding(2) var<storage, read> triangles_a: array<Triangle>;
+@group(0) @binding(3) var<storage, read> bbh_b: array<Node>;
+@group(0) @binding(4) var<storage, read> triangles_b: array<Triangle>;
+@group(0) @binding(5) var<storage, read_write> result: Pairs;
+
+struct Params {
+  model_a: mat4x4<f32>,
+  model_b: mat4x4<f32>,
+  node_count_a: u32,
+  max_pairs: u32,
+}
+
+// Same layout for all of the mesh bbh generators.
+// A node is a leaf when it has no children.
+struct Node {
+  min_corner: vec3<f32>,
+  max_corner: vec3<f32>,
+  l: u32,
+  r: u32,
+  left_child: u32,
+}
+
+struct Triangle {
+  a: vec3<f32>,
+  triangle: u32,
+  b: vec3<f32>,
+  c: vec3<f32>,
+}
+
+// Pairs past max_pairs are counted but not written.
+struct Pairs {
+  count: atomic<u32>,
+  pairs: array<vec2<u32>>,
+}
+
+struct Box {
+  min_corner: vec3<f32>,
+  max_corner: vec3<f32>,
+}
+
+const EPSILON = 0.0000001;
+// Deep enough for any tree the generators can build (they stop at 100 levels).
+const STACK_SIZE = 128u;
+
+// World space box around a model space box
+fn to_world(model: mat4x4<f32>, node: Node) -> Box {
+  let center = (model * vec4<f32>((node.min_corner + node.max_corner) * 0.5, 1.0)).xyz;
+  let half_size = (node.max_corner - node.min_corner) * 0.5;
+  let extent = abs(model[0].xyz) * half_size.x
+    + abs(model[1].xyz) * half_size.y
+    + abs(model[2].xyz) * half_size.z;
+  return Box(center - extent, center + extent);
+}
+
+fn boxes_overlap(a: Box, b: Box) -> bool {
+  return all(a.min_corner <= b.max_corner) && all(b.min_corner <= a.max_corner);
+}
+
+// Moller Trumbore on the segment from p to q
+fn segment_hits_triangle(p: vec3<f32>, q: vec3<f32>, triangle: Triangle) -> bool {
+  let direction = q - p;
+  let ab = triangle.b - triangle.a;
+  let ac = triangle.c - triangle.a;
+  let h = cross(direction, ac);
+  let det = dot(ab, h);
+  if (abs(det) < EPSILON) {
+    return false;
+  }
+  let inv_det = 1.0 / det;
+  let s = p - triangle.a;
+  let u = dot(s, h) * inv_det;
+  if (u < 0.0 || u > 1.0) {
+    return false;
+  }
+  let r = cross(s, ab);
+  let v = dot(direction, r) * inv_det;
+  if (v < 0.0 || u + v > 1.0) {
+    return false;
+  }
+  let t = dot(ac, r) * inv_det;
+  return t >= 0.0 && t <= 1.0;
+}
+
+// Two triangles cross when an edge of one goes through the other.
+// Coplanar triangles are not reported.
+fn triangles_overlap(a: Triangle, b: Triangle) -> bool {
+  return segment_hits_triangle(a.a, a.b, b)
+    || segment_hits_triangle(a.b, a.c, b)
+    || segment_hits_triangle(a.c, a.a, b)
+    || segment_hits_triangle(b.a, b.b, a)
+    || segment_hits_triangle(b.b, b.c, a)
+    || segment_hits_triangle(b.c, b.a, a);
+}
+
+// One invocation per node of a, only leaves do any work.
+// Each leaf walks the tree of b and tests its triangles against the leaves it touches.
+@compute @workgroup_size(1,1,1)
+fn main(
+  @builtin(global_invocation_id) id: vec3<u32>,
+  @builtin(num_workgroups) size: vec3<u32>,
+  ) {
+
+  let leaf_index = id.x + id.y * size.x + id.z * size.x * size.y;
+  if (leaf_index >= params.node_count_a) {
+    return;
+  }
+
+  let leaf = bbh_a[leaf_index];
+  if (leaf.left_child != 0u) {
+    return;
+  }
+  let leaf_box = to_world(params.model_a, leaf);
+
+  var stack: array<u32, STACK_SIZE>;
+  var stack_size = 1u;
+  stack[0] = 0u;
+
+  while (stack_size > 0u) {
+    stack_size--;
+    let node = bbh_b[stack[stack_size]];
+
+    if (!boxes_overlap(leaf_box, to_world(params.model_b, node))) {
+      continue;
+    }
+
+    if (node.left_child == 0u) {
+      for (var i = leaf.l; i < leaf.r; i++) {
+        let a = triangles_a[i];
+        for (var j = node.l; j < node.r; j++) {
+          let b = triangles_b[j];
+          if (triangles_overlap(a, b)) {
+            let index = atomicAdd(&result.count, 1u);
+            if (index < params.max_pairs) {
+              result.pairs[index] = vec2<u32>(a.triangle, b.triangle);
+            }
+          }
+        }
+      }
+      continue;
+    }
+
+    if (stack_size + 2u > STACK_SIZE) {
+      // Tree is deeper than the stack, drop the subtree rather than overflow.
+      continue;
+    }
+
+    stack[stack_size] = node.left_child;
+    stack[stack_size + 1u] = node.left_child + 1u;
+    stack_size += 2u;
+  }
+}
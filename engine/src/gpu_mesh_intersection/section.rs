@@ -0,0 +1,335 @@
+//! Plane sections and contours of meshes and sampled surfaces.
+//!
+//! Every triangle crossing one of a set of parallel planes is cut on the GPU,
+//! skipping leaves of the MeshBBH whose boxes lie between two planes.
+//! The segments are joined into chains on the CPU by their end points,
+//! which neighbouring triangles compute bit for bit the same.
+
+use std::{collections::HashMap, rc::Rc};
+
+use wgpu::util::DeviceExt;
+
+use crate::{
+    math::linear_algebra::{mat4::Mat4, vec3::Vec3},
+    render::renderer::Renderer,
+    utils::{create_compute_pipeline, dispatch_size_3d, PendingReadback},
+};
+
+use super::WorldMesh;
+
+/// Segments past this are dropped
+const MAX_SEGMENTS: u32 = 1 << 18;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct SectionUniforms {
+    model: Mat4,
+    origin: [f32; 3],
+    spacing: f32,
+    direction: [f32; 3],
+    node_count: u32,
+    level_min: i32,
+    level_max: i32,
+    max_segments: u32,
+    _padding: u32,
+}
+
+/// Parallel planes, level k lies where dot(p - origin, direction) == k * spacing
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SectionLevels {
+    origin: Vec3,
+    direction: Vec3,
+    spacing: f32,
+    min: i32,
+    max: i32,
+}
+
+impl SectionLevels {
+    /// Just the plane through origin, at level 0.
+    /// None if the normal has no length.
+    pub fn plane(origin: Vec3, normal: Vec3) -> Option<Self> {
+        (normal.len() > 0.0).then(|| Self {
+            origin,
+            direction: normal.to_normalized(),
+            spacing: 1.0,
+            min: 0,
+            max: 0,
+        })
+    }
+
+    /// Planes spacing apart along direction, through base point and on both sides of it.
+    /// None if the direction has no length or spacing is not positive.
+    pub fn contours(base_point: Vec3, direction: Vec3, spacing: f32) -> Option<Self> {
+        (direction.len() > 0.0 && spacing > 0.0).then(|| Self {
+            origin: base_point,
+            direction: direction.to_normalized(),
+            spacing,
+            min: i32::MIN,
+            max: i32::MAX,
+        })
+    }
+
+    /// A point on the plane of the level
+    pub fn get_origin(&self, level: i32) -> Vec3 {
+        Vec3::add(
+            &self.origin,
+            &Vec3::to_scaled(&self.direction, level as f32 * self.spacing),
+        )
+    }
+    /// Unit normal shared by the planes
+    pub fn get_direction(&self) -> &Vec3 {
+        &self.direction
+    }
+}
+
+/// World space cut through one triangle, matches Segment in section.wgsl
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SectionSegment {
+    pub a: [f32; 3],
+    pub level: i32,
+    pub b: [f32; 3],
+    pub triangle: u32,
+}
+
+/// Joined segments of one level.
+/// The last point of a closed chain is not a repeat of the first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionChain {
+    pub level: i32,
+    pub points: Vec<Vec3>,
+    pub closed: bool,
+}
+
+pub struct MeshSectioner {
+    renderer: Rc<Renderer>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl MeshSectioner {
+    pub fn new(renderer: Rc<Renderer>) -> Self {
+        let device = renderer.get_device();
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("section mesh bind group layout"),
+            entries: &[
+                // Params
+                crate::utils::compute_uniform_bind_group_layout_entry(0),
+                // Tree
+                crate::utils::compute_buffer_bind_group_layout_entry(1, true),
+                // BBH indices
+                crate::utils::compute_buffer_bind_group_layout_entry(2, true),
+                // Vertex buffer
+                crate::utils::compute_buffer_bind_group_layout_entry(3, true),
+                // Index buffer
+                crate::utils::compute_buffer_bind_group_layout_entry(4, true),
+                // Segments
+                crate::utils::compute_buffer_bind_group_layout_entry(5, false),
+            ],
+        });
+
+        let pipeline = create_compute_pipeline(
+            device,
+            "section mesh",
+            include_str!("section.wgsl"),
+            &bind_group_layout,
+            "main",
+        );
+
+        Self {
+            renderer,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    /// Cuts of every triangle crossing one of the levels, in no particular order.
+    /// Triangles lying in a plane are not cut.
+    pub async fn section_mesh(
+        &self,
+        mesh: &WorldMesh<'_>,
+        levels: &SectionLevels,
+    ) -> Vec<SectionSegment> {
+        read_section_segments(self.submit_section_mesh(mesh, levels)).await
+    }
+
+    /// Records and submits section_mesh, read the result with read_section_segments.
+    pub fn submit_section_mesh(
+        &self,
+        mesh: &WorldMesh<'_>,
+        levels: &SectionLevels,
+    ) -> PendingReadback {
+        let device = self.renderer.get_device();
+        let segment_size = std::mem::size_of::<SectionSegment>() as u64;
+        // Count, padding to the alignment of the segments, then the segments
+        let segments_size = 16 + MAX_SEGMENTS as u64 * segment_size;
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("section mesh params"),
+            contents: bytemuck::cast_slice(&[SectionUniforms {
+                model: *mesh.model,
+                origin: [levels.origin.x, levels.origin.y, levels.origin.z],
+                spacing: levels.spacing,
+                direction: [levels.direction.x, levels.direction.y, levels.direction.z],
+                node_count: mesh.bbh.get_node_count(),
+                level_min: levels.min,
+                level_max: levels.max,
+                max_segments: MAX_SEGMENTS,
+                _padding: 0,
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let segments = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("section mesh segments"),
+            size: segments_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("section mesh"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: mesh.bbh.get_tree().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: mesh.bbh.get_indices().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: mesh.vertex_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: mesh.index_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: segments.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("section mesh"),
+        });
+
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("section mesh"),
+                timestamp_writes: None,
+            });
+
+            compute_pass.set_pipeline(&self.pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[]);
+            let size = dispatch_size_3d(mesh.bbh.get_node_count());
+            compute_pass.dispatch_workgroups(size, size, size);
+        }
+
+        PendingReadback::submit(
+            device,
+            self.renderer.get_queue(),
+            encoder,
+            &segments,
+            segments_size,
+        )
+    }
+
+    pub fn get_renderer(&self) -> Rc<Renderer> {
+        self.renderer.clone()
+    }
+}
+
+/// Reads the result of submit_section_mesh.
+pub async fn read_section_segments(readback: PendingReadback) -> Vec<SectionSegment> {
+    let data = readback.read::<u8>().await;
+    if data.is_empty() {
+        return Vec::new();
+    }
+    let count: u32 = bytemuck::pod_read_unaligned(&data[0..4]);
+    if count > MAX_SEGMENTS {
+        log::warn!("section has {} segments, keeping {}", count, MAX_SEGMENTS);
+    }
+    let segment_size = std::mem::size_of::<SectionSegment>();
+    data[16..16 + count.min(MAX_SEGMENTS) as usize * segment_size]
+        .chunks_exact(segment_size)
+        .map(bytemuck::pod_read_unaligned)
+        .collect()
+}
+
+/// Joins segments sharing end points into chains, level by level.
+/// Where more than two segments meet, chains pass through in whatever order the segments come.
+pub fn chain_segments(segments: &[SectionSegment]) -> Vec<SectionChain> {
+    type Key = (i32, [u32; 3]);
+    let key = |level: i32, point: &[f32; 3]| -> Key { (level, point.map(f32::to_bits)) };
+
+    // Segments touching each end point
+    let mut touching: HashMap<Key, Vec<usize>> = HashMap::new();
+    for (i, segment) in segments.iter().enumerate() {
+        if segment.a == segment.b {
+            continue;
+        }
+        for point in [&segment.a, &segment.b] {
+            touching
+                .entry(key(segment.level, point))
+                .or_default()
+                .push(i);
+        }
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut res = Vec::new();
+    for (first, segment) in segments.iter().enumerate() {
+        if used[first] || segment.a == segment.b {
+            continue;
+        }
+        used[first] = true;
+        let level = segment.level;
+
+        // Walks on from a point through unused segments, returning the points passed
+        let walk = |start: [f32; 3], used: &mut Vec<bool>| -> Vec<[f32; 3]> {
+            let mut points = Vec::new();
+            let mut point = start;
+            while let Some(next) = touching[&key(level, &point)]
+                .iter()
+                .copied()
+                .find(|i| !used[*i])
+            {
+                used[next] = true;
+                let next = &segments[next];
+                point = if next.a == point { next.b } else { next.a };
+                points.push(point);
+            }
+            points
+        };
+
+        let forward = walk(segment.b, &mut used);
+        let closed = forward.last() == Some(&segment.a);
+        let mut points: Vec<[f32; 3]> = if closed {
+            Vec::new()
+        } else {
+            walk(segment.a, &mut used).into_iter().rev().collect()
+        };
+        points.push(segment.a);
+        points.push(segment.b);
+        points.extend(forward);
+        if closed {
+            points.pop();
+        }
+
+        res.push(SectionChain {
+            level,
+            points: points.iter().map(|point| point.as_slice().into()).collect(),
+            closed,
+        });
+    }
+    res
+}
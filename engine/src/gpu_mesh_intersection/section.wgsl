@@ -0,0 +1,157 @@
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> bbh: array<Node>;
+@group(0) @binding(2) var<storage, read> bbh_indices: array<u32>;
+@group(0) @binding(3) var<storage, read> vertex_buffer: array<Vertex>;
+@group(0) @binding(4) var<storage, read> index_buffer: array<u32>;
+@group(0) @binding(5) var<storage, read_write> result: Segments;
+
+// Level k cuts where dot(p - origin, direction) == k * spacing, for k in level_min..=level_max
+struct Params {
+  model: mat4x4<f32>,
+  origin: vec3<f32>,
+  spacing: f32,
+  direction: vec3<f32>,
+  node_count: u32,
+  level_min: i32,
+  level_max: i32,
+  max_segments: u32,
+}
+
+struct Vertex {
+  position: vec4<f32>,
+  normal: vec4<f32>,
+}
+
+// Same layout for all of the mesh bbh generators.
+// A node is a leaf when it has no children.
+struct Node {
+  min_corner: vec3<f32>,
+  max_corner: vec3<f32>,
+  l: u32,
+  r: u32,
+  left_child: u32,
+}
+
+// World space cut through one triangle
+struct Segment {
+  a: vec3<f32>,
+  level: i32,
+  b: vec3<f32>,
+  triangle: u32,
+}
+
+// Segments past max_segments are counted but not written.
+struct Segments {
+  count: atomic<u32>,
+  segments: array<Segment>,
+}
+
+// Levels whose planes lie between the heights, empty when the first is past the second
+fn crossed_levels(low: f32, high: f32) -> vec2<i32> {
+  let first = max(ceil(low / params.spacing), f32(params.level_min));
+  let last = min(floor(high / params.spacing), f32(params.level_max));
+  return vec2<i32>(i32(first), i32(last));
+}
+
+fn height(point: vec3<f32>) -> f32 {
+  return dot(point - params.origin, params.direction);
+}
+
+// Where the plane crosses the edge between two vertices.
+// Always interpolates from the lower vertex index so both triangles on an edge
+// give bit for bit the same point, which is what joins the segments into chains.
+fn edge_point(
+  index_a: u32, point_a: vec3<f32>, height_a: f32,
+  index_b: u32, point_b: vec3<f32>, height_b: f32,
+) -> vec3<f32> {
+  var start = point_a;
+  var end = point_b;
+  var height_start = height_a;
+  var height_end = height_b;
+  if (index_b < index_a) {
+    start = point_b;
+    end = point_a;
+    height_start = height_b;
+    height_end = height_a;
+  }
+  if (height_end == 0.0) {
+    return end;
+  }
+  return start + (end - start) * (height_start / (height_start - height_end));
+}
+
+// One invocation per node, only leaves do any work.
+// Leaves whose world space box lies between two levels are skipped without looking at their triangles.
+@compute @workgroup_size(1,1,1)
+fn main(
+  @builtin(global_invocation_id) id: vec3<u32>,
+  @builtin(num_workgroups) size: vec3<u32>,
+  ) {
+
+  let leaf_index = id.x + id.y * size.x + id.z * size.x * size.y;
+  if (leaf_index >= params.node_count) {
+    return;
+  }
+
+  let leaf = bbh[leaf_index];
+  if (leaf.left_child != 0u) {
+    return;
+  }
+
+  // Height range of the world space box around the leaf
+  let center = (params.model * vec4<f32>((leaf.min_corner + leaf.max_corner) * 0.5, 1.0)).xyz;
+  let half_size = (leaf.max_corner - leaf.min_corner) * 0.5;
+  let local_direction = transpose(mat3x3<f32>(params.model[0].xyz, params.model[1].xyz, params.model[2].xyz)) * params.direction;
+  let reach = dot(abs(local_direction), half_size);
+  let leaf_levels = crossed_levels(height(center) - reach, height(center) + reach);
+  if (leaf_levels.x > leaf_levels.y) {
+    return;
+  }
+
+  for (var i = leaf.l; i < leaf.r; i++) {
+    let triangle = bbh_indices[i];
+    let indices = vec3<u32>(
+      index_buffer[triangle * 3],
+      index_buffer[triangle * 3 + 1],
+      index_buffer[triangle * 3 + 2],
+    );
+    let a = (params.model * vec4<f32>(vertex_buffer[indices.x].position.xyz, 1.0)).xyz;
+    let b = (params.model * vec4<f32>(vertex_buffer[indices.y].position.xyz, 1.0)).xyz;
+    let c = (params.model * vec4<f32>(vertex_buffer[indices.z].position.xyz, 1.0)).xyz;
+    let heights = vec3<f32>(height(a), height(b), height(c));
+    let levels = crossed_levels(
+      min(heights.x, min(heights.y, heights.z)),
+      max(heights.x, max(heights.y, heights.z)),
+    );
+
+    for (var level = levels.x; level <= levels.y; level++) {
+      // Vertices on the plane count as above it, so a plane through a vertex
+      // cuts the triangles on either side of it exactly once.
+      let offset = heights - vec3<f32>(f32(level) * params.spacing);
+      let above = offset >= vec3<f32>(0.0);
+      if (all(above) || !any(above)) {
+        continue;
+      }
+
+      var points: array<vec3<f32>, 2>;
+      var count = 0u;
+      if (above.x != above.y) {
+        points[count] = edge_point(indices.x, a, offset.x, indices.y, b, offset.y);
+        count++;
+      }
+      if (above.y != above.z) {
+        points[count] = edge_point(indices.y, b, offset.y, indices.z, c, offset.z);
+        count++;
+      }
+      if (above.z != above.x) {
+        points[count] = edge_point(indices.z, c, offset.z, indices.x, a, offset.x);
+        count++;
+      }
+
+      let index = atomicAdd(&result.count, 1u);
+      if (index < params.max_segments) {
+        result.segments[index] = Segment(points[0], level, points[1], triangle);
+      }
+    }
+  }
+}
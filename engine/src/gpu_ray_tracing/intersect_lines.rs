@@ -0,0 +1,203 @@
+//! Ray casting against line strips and line lists.
+//!
+//! Lines have no area, so a segment counts as hit when it passes
+//! within a tolerance that grows with distance from the ray origin.
+//! With the tolerance set from the pixel size this is a fixed pixel radius on screen.
+
+use std::rc::Rc;
+
+use wgpu::util::DeviceExt;
+
+use crate::{
+    math::{geometry::ray::Ray, linear_algebra::mat4::Mat4},
+    render::renderer::Renderer,
+    utils::{create_compute_pipeline, dispatch_size_3d, PendingReadback},
+};
+
+use super::intersection::LineIntersection;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct IntersectLinesUniforms {
+    model: Mat4,
+    ray_origin: [f32; 3],
+    segment_count: u32,
+    ray_direction: [f32; 3],
+    tolerance: f32,
+    indexed: u32,
+    _padding: [u32; 3],
+}
+
+pub struct LinesIntersector {
+    renderer: Rc<Renderer>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+    // Bound in place of the index buffer for line strips
+    empty_index_buffer: wgpu::Buffer,
+}
+
+impl LinesIntersector {
+    pub fn new(renderer: Rc<Renderer>) -> Self {
+        let device = renderer.get_device();
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("intersect lines bind group layout"),
+            entries: &[
+                // Params
+                crate::utils::compute_uniform_bind_group_layout_entry(0),
+                // Vertices
+                crate::utils::compute_buffer_bind_group_layout_entry(1, true),
+                // Indices
+                crate::utils::compute_buffer_bind_group_layout_entry(2, true),
+                // Hits
+                crate::utils::compute_buffer_bind_group_layout_entry(3, false),
+            ],
+        });
+
+        let pipeline = create_compute_pipeline(
+            device,
+            "intersect lines",
+            include_str!("intersect_lines.wgsl"),
+            &bind_group_layout,
+            "main",
+        );
+
+        let empty_index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("intersect lines empty index buffer"),
+            contents: bytemuck::cast_slice(&[0u32; 2]),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        Self {
+            renderer,
+            bind_group_layout,
+            pipeline,
+            empty_index_buffer,
+        }
+    }
+
+    /// Finds the closest segment within tolerance of the world space ray.
+    /// Tolerance is the allowed distance from the ray per unit of distance along it.
+    /// The vertices are moved by model first, so the hit point is in world space too.
+    ///
+    /// Vertices are vec4s and are divided by w, so curve samples can be used directly.
+    /// Pass an index buffer for line lists, or None for a line strip.
+    /// Buffers must have been created with the STORAGE usage.
+    pub async fn intersect_lines(
+        &self,
+        ray: &Ray,
+        model: &Mat4,
+        tolerance: f32,
+        vertex_buffer: &wgpu::Buffer,
+        index_buffer: Option<&wgpu::Buffer>,
+        segment_count: u32,
+    ) -> Option<LineIntersection> {
+        let hits = self
+            .submit_intersect_lines(
+                ray,
+                model,
+                tolerance,
+                vertex_buffer,
+                index_buffer,
+                segment_count,
+            )
+            .read()
+            .await;
+        closest_line_hit(&hits)
+    }
+
+    /// Submits intersect_lines, read the result as LineIntersections and pass it to closest_line_hit.
+    pub fn submit_intersect_lines(
+        &self,
+        ray: &Ray,
+        model: &Mat4,
+        tolerance: f32,
+        vertex_buffer: &wgpu::Buffer,
+        index_buffer: Option<&wgpu::Buffer>,
+        segment_count: u32,
+    ) -> PendingReadback {
+        if segment_count == 0 {
+            return PendingReadback::empty();
+        }
+
+        let device = self.renderer.get_device();
+        let hits_size = segment_count as u64 * std::mem::size_of::<LineIntersection>() as u64;
+
+        let origin = ray.get_origin();
+        let direction = ray.get_direction();
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("intersect lines params"),
+            contents: bytemuck::cast_slice(&[IntersectLinesUniforms {
+                model: *model,
+                ray_origin: [origin.x, origin.y, origin.z],
+                segment_count,
+                ray_direction: [direction.x, direction.y, direction.z],
+                tolerance,
+                indexed: index_buffer.is_some() as u32,
+                _padding: [0; 3],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let hits = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("intersect lines hits"),
+            size: hits_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("intersect lines"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: vertex_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: index_buffer
+                        .unwrap_or(&self.empty_index_buffer)
+                        .as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: hits.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("intersect lines"),
+        });
+
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("intersect lines"),
+                timestamp_writes: None,
+            });
+
+            compute_pass.set_pipeline(&self.pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[]);
+            let size = dispatch_size_3d(segment_count);
+            compute_pass.dispatch_workgroups(size, size, size);
+        }
+
+        PendingReadback::submit(device, self.renderer.get_queue(), encoder, &hits, hits_size)
+    }
+
+    pub fn get_renderer(&self) -> Rc<Renderer> {
+        self.renderer.clone()
+    }
+}
+
+/// The hit closest to the ray origin among the segments of one intersect_lines call.
+pub fn closest_line_hit(hits: &[LineIntersection]) -> Option<LineIntersection> {
+    hits.iter()
+        .filter(|hit| hit.is_hit())
+        .min_by(|a, b| a.get_t().total_cmp(&b.get_t()))
+        .copied()
+}
@@ -0,0 +1,87 @@
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> vertices: array<vec4<f32>>;
+@group(0) @binding(2) var<storage, read> indices: array<u32>;
+@group(0) @binding(3) var<storage, read_write> hits: array<Hit>;
+
+struct Params {
+  // Segments are tested in world space, so the tolerance holds whatever the model scales
+  model: mat4x4<f32>,
+  ray_origin: vec3<f32>,
+  segment_count: u32,
+  ray_direction: vec3<f32>,
+  // Allowed distance from the ray per unit of distance along it.
+  tolerance: f32,
+  // 0 for a line strip, 1 for a line list
+  indexed: u32,
+}
+
+struct Hit {
+  point: vec3<f32>,
+  t: f32,
+  // Position along the segment in [0, 1]
+  s: f32,
+  segment: u32,
+}
+
+const FLOAT_MAX = 3.40282346638528859812e+38f;
+const EPSILON = 0.0000001;
+
+fn get_segment_index(segment: u32, end: u32) -> u32 {
+  if (params.indexed == 0u) {
+    return segment + end;
+  }
+  return indices[segment * 2u + end];
+}
+
+// Vertices can be weighted, like curve samples. Returned in world space.
+fn get_vertex(index: u32) -> vec3<f32> {
+  let v = vertices[index];
+  return (params.model * vec4<f32>(v.xyz / v.w, 1.0)).xyz;
+}
+
+// Writes the closest world space point on each segment,
+// t is FLOAT_MAX when the segment is out of tolerance.
+@compute @workgroup_size(1,1,1)
+fn main(
+  @builtin(global_invocation_id) id: vec3<u32>,
+  @builtin(num_workgroups) size: vec3<u32>,
+  ) {
+
+  let index = id.x + id.y * size.x + id.z * size.x * size.y;
+  if (index >= params.segment_count) {
+    return;
+  }
+
+  let a = get_vertex(get_segment_index(index, 0u));
+  let b = get_vertex(get_segment_index(index, 1u));
+  let o = params.ray_origin;
+  let d = params.ray_direction;
+
+  // Closest points between the ray and the segment.
+  // Ray direction is normalized.
+  let e = b - a;
+  let w = o - a;
+  let de = dot(d, e);
+  let ee = dot(e, e);
+  let dw = dot(d, w);
+  let ew = dot(e, w);
+  let denominator = ee - de * de;
+  var s = 0.0;
+  if (denominator > EPSILON) {
+    s = clamp((ew - de * dw) / denominator, 0.0, 1.0);
+  }
+  let t = max(dot(a + e * s - o, d), 0.0);
+  if (ee > EPSILON) {
+    s = clamp(dot(o + d * t - a, e) / ee, 0.0, 1.0);
+  }
+
+  let on_segment = a + e * s;
+  let on_ray = o + d * t;
+
+  if (distance(on_segment, on_ray) > params.tolerance * t) {
+    hits[index] = Hit(on_segment, FLOAT_MAX, s, index);
+    return;
+  }
+
+  hits[index] = Hit(on_segment, t, s, index);
+}
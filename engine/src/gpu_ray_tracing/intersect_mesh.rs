@@ -0,0 +1,197 @@
+//! Batched ray casting against a MeshBBH.
+//!
+//! Works with the trees from all of the mesh bbh generators,
+//! they share a node layout and mark leaves with a left child of 0.
+
+use std::rc::Rc;
+
+use wgpu::util::DeviceExt;
+
+use crate::{
+    gpu_acceleration_structures::mesh_bbh::MeshBBH,
+    math::geometry::ray::Ray,
+    render::renderer::Renderer,
+    utils::{create_compute_pipeline, dispatch_size_3d, PendingReadback},
+};
+
+use super::intersection::Intersection;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct IntersectMeshUniforms {
+    ray_count: u32,
+}
+
+/// Ray layout expected by intersect_mesh.wgsl
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct GpuRay {
+    origin: [f32; 4],
+    direction: [f32; 4],
+}
+
+impl From<&Ray> for GpuRay {
+    fn from(ray: &Ray) -> Self {
+        let o = ray.get_origin();
+        let d = ray.get_direction();
+        GpuRay {
+            origin: [o.x, o.y, o.z, 1.0],
+            direction: [d.x, d.y, d.z, 0.0],
+        }
+    }
+}
+
+pub struct MeshIntersector {
+    renderer: Rc<Renderer>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl MeshIntersector {
+    pub fn new(renderer: Rc<Renderer>) -> Self {
+        let device = renderer.get_device();
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("intersect mesh bind group layout"),
+            entries: &[
+                // Params
+                crate::utils::compute_uniform_bind_group_layout_entry(0),
+                // Tree
+                crate::utils::compute_buffer_bind_group_layout_entry(1, true),
+                // BBH indices
+                crate::utils::compute_buffer_bind_group_layout_entry(2, true),
+                // Vertex buffer
+                crate::utils::compute_buffer_bind_group_layout_entry(3, true),
+                // Index buffer
+                crate::utils::compute_buffer_bind_group_layout_entry(4, true),
+                // Rays
+                crate::utils::compute_buffer_bind_group_layout_entry(5, true),
+                // Hits
+                crate::utils::compute_buffer_bind_group_layout_entry(6, false),
+            ],
+        });
+
+        let pipeline = create_compute_pipeline(
+            device,
+            "intersect mesh",
+            include_str!("intersect_mesh.wgsl"),
+            &bind_group_layout,
+            "main",
+        );
+
+        Self {
+            renderer,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    /// Finds the closest hit for each ray.
+    /// Result i belongs to ray i, check is_hit() before using it.
+    ///
+    /// Vertex and index buffers must have been created with the STORAGE usage.
+    pub async fn intersect_mesh(
+        &self,
+        rays: &[Ray],
+        bbh: &MeshBBH,
+        vertex_buffer: &wgpu::Buffer,
+        index_buffer: &wgpu::Buffer,
+    ) -> Vec<Intersection> {
+        self.submit_intersect_mesh(rays, bbh, vertex_buffer, index_buffer)
+            .read()
+            .await
+    }
+
+    /// Submits intersect_mesh, read the result as Intersections.
+    pub fn submit_intersect_mesh(
+        &self,
+        rays: &[Ray],
+        bbh: &MeshBBH,
+        vertex_buffer: &wgpu::Buffer,
+        index_buffer: &wgpu::Buffer,
+    ) -> PendingReadback {
+        if rays.is_empty() {
+            return PendingReadback::empty();
+        }
+
+        let device = self.renderer.get_device();
+        let ray_count = rays.len() as u32;
+        let hits_size = ray_count as u64 * std::mem::size_of::<Intersection>() as u64;
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("intersect mesh params"),
+            contents: bytemuck::cast_slice(&[IntersectMeshUniforms { ray_count }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let gpu_rays: Vec<GpuRay> = rays.iter().map(GpuRay::from).collect();
+        let ray_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("intersect mesh rays"),
+            contents: bytemuck::cast_slice(&gpu_rays[..]),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let hits = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("intersect mesh hits"),
+            size: hits_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("intersect mesh"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: bbh.get_tree().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: bbh.get_indices().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: vertex_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: index_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: ray_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: hits.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("intersect mesh"),
+        });
+
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("intersect mesh"),
+                timestamp_writes: None,
+            });
+
+            compute_pass.set_pipeline(&self.pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[]);
+            let size = dispatch_size_3d(ray_count);
+            compute_pass.dispatch_workgroups(size, size, size);
+        }
+
+        PendingReadback::submit(device, self.renderer.get_queue(), encoder, &hits, hits_size)
+    }
+
+    pub fn get_renderer(&self) -> Rc<Renderer> {
+        self.renderer.clone()
+    }
+}
@@ -1,40 +1,159 @@
-@group(0) @binding(0) var<storage, read> bbh: array<Node>;
-@group(0) @binding(1) var<storage, read> vertex_buffer: array<Vertex>;
-@group(0) @binding(2) var<storage, read> index_buffer: array<u32>;
-@group(0) @binding(3) var<storate, read> rays: array<Ray>;
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> bbh: array<Node>;
+@group(0) @binding(2) var<storage, read> bbh_indices: array<u32>;
+@group(0) @binding(3) var<storage, read> vertex_buffer: array<Vertex>;
+@group(0) @binding(4) var<storage, read> index_buffer: array<u32>;
+@group(0) @binding(5) var<storage, read> rays: array<Ray>;
+@group(0) @binding(6) var<storage, read_write> hits: array<Hit>;
+
+struct Params {
+  ray_count: u32,
+}
 
 struct Ray {
-  // Maybe check alignment rules
-  position: Vec3,
-  direction: Vec3
+  origin: vec3<f32>,
+  direction: vec3<f32>,
 }
 
 struct Vertex {
-  position: Vec4,
-  normal: Vec4, 
+  position: vec4<f32>,
+  normal: vec4<f32>,
 }
 
+// Same layout for all of the mesh bbh generators.
+// A node is a leaf when it has no children.
 struct Node {
   min_corner: vec3<f32>,
-  l: u32,
   max_corner: vec3<f32>,
+  l: u32,
   r: u32,
-  center: vec3<f32>,
   left_child: u32,
 }
 
-fn intersect_bounding_box(ray: Ray, bb: BoundingBox) -> some shit {
-
+struct Hit {
+  point: vec3<f32>,
+  t: f32,
+  barycentric: vec3<f32>,
+  triangle: u32,
 }
 
-fn intersect_tri(ray: Ray, tri: Tri) -> some shit {
+const FLOAT_MAX = 3.40282346638528859812e+38f;
+const NO_HIT = 0xFFFFFFFFu;
+const EPSILON = 0.0000001;
+// Deep enough for any tree the generators can build (they stop at 100 levels).
+const STACK_SIZE = 128u;
 
+// Returns the distance to the box, or FLOAT_MAX on a miss.
+fn intersect_bounding_box(ray: Ray, inv_dir: vec3<f32>, min_corner: vec3<f32>, max_corner: vec3<f32>) -> f32 {
+  let t0 = (min_corner - ray.origin) * inv_dir;
+  let t1 = (max_corner - ray.origin) * inv_dir;
+  let t_small = min(t0, t1);
+  let t_big = max(t0, t1);
+  let t_min = max(max(t_small.x, t_small.y), max(t_small.z, 0.0));
+  let t_max = min(min(t_big.x, t_big.y), t_big.z);
+  if (t_min > t_max) {
+    return FLOAT_MAX;
+  }
+  return t_min;
 }
 
-@compute @workgroup_size(1,1,1) 
+// Moller Trumbore.
+// Returns (t, u, v) where u and v are the barycentrics of b and c.
+// t is FLOAT_MAX on a miss.
+fn intersect_triangle(ray: Ray, a: vec3<f32>, b: vec3<f32>, c: vec3<f32>) -> vec3<f32> {
+  let miss = vec3<f32>(FLOAT_MAX, 0.0, 0.0);
+  let ab = b - a;
+  let ac = c - a;
+  let p = cross(ray.direction, ac);
+  let det = dot(ab, p);
+  if (abs(det) < EPSILON) {
+    return miss;
+  }
+  let inv_det = 1.0 / det;
+  let s = ray.origin - a;
+  let u = dot(s, p) * inv_det;
+  if (u < 0.0 || u > 1.0) {
+    return miss;
+  }
+  let q = cross(s, ab);
+  let v = dot(ray.direction, q) * inv_det;
+  if (v < 0.0 || u + v > 1.0) {
+    return miss;
+  }
+  let t = dot(ac, q) * inv_det;
+  if (t < 0.0) {
+    return miss;
+  }
+  return vec3<f32>(t, u, v);
+}
+
+@compute @workgroup_size(1,1,1)
 fn main(
   @builtin(global_invocation_id) id: vec3<u32>,
-  @builtin(num_workgroups) num_workgroups: vec3<u32>
+  @builtin(num_workgroups) size: vec3<u32>,
   ) {
 
+  let index = id.x + id.y * size.x + id.z * size.x * size.y;
+  if (index >= params.ray_count) {
+    return;
+  }
+
+  let ray = rays[index];
+  let inv_dir = 1.0 / ray.direction;
+
+  var best = Hit(vec3<f32>(0.0, 0.0, 0.0), FLOAT_MAX, vec3<f32>(0.0, 0.0, 0.0), NO_HIT);
+
+  var stack: array<u32, STACK_SIZE>;
+  var stack_size = 1u;
+  stack[0] = 0u;
+
+  while (stack_size > 0u) {
+    stack_size--;
+    let node = bbh[stack[stack_size]];
+
+    if (intersect_bounding_box(ray, inv_dir, node.min_corner, node.max_corner) >= best.t) {
+      continue;
+    }
+
+    if (node.left_child == 0u) {
+      for (var i = node.l; i < node.r; i++) {
+        let triangle = bbh_indices[i];
+        let a = vertex_buffer[index_buffer[triangle * 3]].position.xyz;
+        let b = vertex_buffer[index_buffer[triangle * 3 + 1]].position.xyz;
+        let c = vertex_buffer[index_buffer[triangle * 3 + 2]].position.xyz;
+        let tuv = intersect_triangle(ray, a, b, c);
+        if (tuv.x < best.t) {
+          best.t = tuv.x;
+          best.barycentric = vec3<f32>(1.0 - tuv.y - tuv.z, tuv.y, tuv.z);
+          best.triangle = triangle;
+        }
+      }
+      continue;
+    }
+
+    if (stack_size + 2u > STACK_SIZE) {
+      // Tree is deeper than the stack, drop the subtree rather than overflow.
+      continue;
+    }
+
+    // Push the farther child first so the closer one is popped first.
+    let left = bbh[node.left_child];
+    let right = bbh[node.left_child + 1u];
+    let t_left = intersect_bounding_box(ray, inv_dir, left.min_corner, left.max_corner);
+    let t_right = intersect_bounding_box(ray, inv_dir, right.min_corner, right.max_corner);
+    if (t_left < t_right) {
+      stack[stack_size] = node.left_child + 1u;
+      stack[stack_size + 1u] = node.left_child;
+    } else {
+      stack[stack_size] = node.left_child;
+      stack[stack_size + 1u] = node.left_child + 1u;
+    }
+    stack_size += 2u;
+  }
+
+  if (best.triangle != NO_HIT) {
+    best.point = ray.origin + ray.direction * best.t;
+  }
+
+  hits[index] = best;
 }
@@ -1,8 +1,74 @@
-use crate::geometry::GeometryId;
+use crate::math::linear_algebra::vec3::Vec3;
 
+pub(crate) const NO_HIT: u32 = u32::MAX;
+
+/// Closest hit of a ray against a triangle mesh.
+/// Layout matches the Hit struct in intersect_mesh.wgsl.
 // TODO: different types of intersection
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Intersection {
-    geometry_id: GeometryId,
+    point: Vec3,
+    t: f32,
+    /// Weights of the triangles first, second and third vertex
+    barycentric: Vec3,
+    triangle: u32,
+}
+
+impl Intersection {
+    pub fn new(point: Vec3, t: f32, barycentric: Vec3, triangle: u32) -> Self {
+        Self {
+            point,
+            t,
+            barycentric,
+            triangle,
+        }
+    }
+    pub fn is_hit(&self) -> bool {
+        self.triangle != NO_HIT
+    }
+    pub fn get_point(&self) -> &Vec3 {
+        &self.point
+    }
+    pub fn get_t(&self) -> f32 {
+        self.t
+    }
+    pub fn get_barycentric(&self) -> &Vec3 {
+        &self.barycentric
+    }
+    /// Index of the triangle in the meshes index buffer, divided by 3
+    pub fn get_triangle(&self) -> u32 {
+        self.triangle
+    }
 }
 
-impl Intersection {}
+/// Closest hit of a ray against a set of line segments.
+/// Layout matches the Hit struct in intersect_lines.wgsl.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LineIntersection {
+    point: Vec3,
+    t: f32,
+    /// Position along the segment in [0, 1]
+    s: f32,
+    segment: u32,
+    _padding: [u32; 2],
+}
+
+impl LineIntersection {
+    pub fn is_hit(&self) -> bool {
+        self.t != f32::MAX
+    }
+    pub fn get_point(&self) -> &Vec3 {
+        &self.point
+    }
+    pub fn get_t(&self) -> f32 {
+        self.t
+    }
+    pub fn get_s(&self) -> f32 {
+        self.s
+    }
+    pub fn get_segment(&self) -> u32 {
+        self.segment
+    }
+}
@@ -1,2 +1,4 @@
+pub mod intersect_lines;
 pub mod intersect_mesh;
 pub mod intersection;
+pub mod pick;
@@ -0,0 +1,260 @@
+//! Click selection.
+//!
+//! Surfaces and meshes are traced through their bbh, which gets built on first pick if missing.
+//! Curves, polylines and lines are hit when they pass within a few pixels of the cursor.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    geometry::{Geometry, GeometryId},
+    instance::INSTANCES,
+    math::{geometry::ray::Ray, linear_algebra::vec3::Vec3},
+    scene::scene_interface::Scene,
+    utils::PendingReadback,
+    viewport::viewport_interface::Viewport,
+};
+
+use super::{
+    intersect_lines::closest_line_hit,
+    intersection::{Intersection, LineIntersection},
+};
+
+/// How close to a curve, polyline or line the cursor needs to be.
+const PICK_TOLERANCE_PIXELS: f32 = 5.0;
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone)]
+pub struct PickResult {
+    id: GeometryId,
+    point: Vec3,
+    u: f32,
+    v: f32,
+    is_surface: bool,
+    // Distance from the camera, for finding the closest hit
+    t: f32,
+}
+
+#[wasm_bindgen]
+impl PickResult {
+    pub fn get_id(&self) -> GeometryId {
+        self.id
+    }
+    /// World space
+    pub fn get_point(&self) -> Vec3 {
+        self.point
+    }
+    /// Surface u, or curve parameter
+    pub fn get_u(&self) -> f32 {
+        self.u
+    }
+    /// Surface v
+    pub fn get_v(&self) -> f32 {
+        self.v
+    }
+    pub fn is_surface(&self) -> bool {
+        self.is_surface
+    }
+}
+
+/// Where a pick readback came from, to look the geometry up again once it is read.
+#[derive(Debug, Copy, Clone)]
+enum PickTarget {
+    Surface,
+    Mesh,
+    Curve,
+    Polyline,
+    Lines,
+}
+
+#[wasm_bindgen]
+impl Scene {
+    /// x and y are in pixels from the top left corner of the viewports canvas.
+    /// Returns the closest geometry under the cursor.
+    pub async fn pick(&self, viewport: &Viewport, x: f32, y: f32) -> Option<PickResult> {
+        // The instance is only locked while submitting and after reading,
+        // the readbacks are awaited without holding it.
+        let (ray, pending) = {
+            let mut instances = INSTANCES.lock().unwrap();
+            let instance = instances.get_mut(&self.get_instance_handle()).unwrap();
+
+            let viewport = instance.get_viewport(viewport.get_handle());
+            let ray = viewport.pixel_to_ray(x, y);
+            let tolerance = viewport.get_pixel_size() * PICK_TOLERANCE_PIXELS;
+
+            let mesh_intersector = instance.get_mesh_intersector();
+            let lines_intersector = instance.get_lines_intersector();
+            let bbh_generator = instance.get_mesh_bbh_generator();
+
+            let scene = instance.get_scene_mut(self.get_handle());
+            scene.build_missing_bbhs(&bbh_generator);
+
+            let mut pending: Vec<(GeometryId, PickTarget, PendingReadback)> = Vec::new();
+
+            for (id, surface) in scene.get_surfaces().iter() {
+                let Some(local_ray) = to_local(surface, &ray) else {
+                    continue;
+                };
+                let readback = mesh_intersector.submit_intersect_mesh(
+                    &[local_ray],
+                    surface.get_bbh().unwrap(),
+                    surface.get_vertex_buffer(),
+                    surface.get_index_buffer(),
+                );
+                pending.push((*id, PickTarget::Surface, readback));
+            }
+
+            for (id, mesh) in scene.get_meshes().iter() {
+                let Some(local_ray) = to_local(mesh, &ray) else {
+                    continue;
+                };
+                let readback = mesh_intersector.submit_intersect_mesh(
+                    &[local_ray],
+                    mesh.get_bbh().unwrap(),
+                    mesh.get_vertex_buffer(),
+                    mesh.get_index_buffer(),
+                );
+                pending.push((*id, PickTarget::Mesh, readback));
+            }
+
+            for (id, curve) in scene.get_curves().iter() {
+                let readback = lines_intersector.submit_intersect_lines(
+                    &ray,
+                    curve.get_bind_group_object().get_model(),
+                    tolerance,
+                    curve.get_vertex_buffer(),
+                    None,
+                    curve.get_vertex_count().saturating_sub(1),
+                );
+                pending.push((*id, PickTarget::Curve, readback));
+            }
+
+            for (id, polyline) in scene.get_polylines().iter() {
+                let readback = lines_intersector.submit_intersect_lines(
+                    &ray,
+                    polyline.get_bind_group_object().get_model(),
+                    tolerance,
+                    polyline.get_vertex_buffer(),
+                    None,
+                    polyline.get_vertex_count().saturating_sub(1),
+                );
+                pending.push((*id, PickTarget::Polyline, readback));
+            }
+
+            for (id, lines) in scene.get_lines().iter() {
+                let readback = lines_intersector.submit_intersect_lines(
+                    &ray,
+                    lines.get_bind_group_object().get_model(),
+                    tolerance,
+                    lines.get_vertex_buffer(),
+                    Some(lines.get_index_buffer()),
+                    lines.get_index_count() / 2,
+                );
+                pending.push((*id, PickTarget::Lines, readback));
+            }
+
+            (ray, pending)
+        };
+
+        let mut mesh_hits: Vec<(GeometryId, PickTarget, Intersection)> = Vec::new();
+        let mut line_hits: Vec<(GeometryId, PickTarget, LineIntersection)> = Vec::new();
+        for (id, target, readback) in pending {
+            match target {
+                PickTarget::Surface | PickTarget::Mesh => {
+                    let hit = readback.read::<Intersection>().await[0];
+                    if hit.is_hit() {
+                        mesh_hits.push((id, target, hit));
+                    }
+                }
+                PickTarget::Curve | PickTarget::Polyline | PickTarget::Lines => {
+                    if let Some(hit) = closest_line_hit(&readback.read().await) {
+                        line_hits.push((id, target, hit));
+                    }
+                }
+            }
+        }
+
+        let instances = INSTANCES.lock().unwrap();
+        let scene = instances
+            .get(&self.get_instance_handle())
+            .unwrap()
+            .get_scene(self.get_handle());
+
+        let mut hits: Vec<PickResult> = Vec::new();
+
+        // Geometry deleted while reading back is skipped
+        for (id, target, hit) in mesh_hits {
+            match target {
+                PickTarget::Surface => {
+                    if let Some(surface) = scene.get_surfaces().get(&id) {
+                        let (u, v) =
+                            surface.get_triangle_uv(hit.get_triangle(), hit.get_barycentric());
+                        let point = to_world(surface, hit.get_point());
+                        hits.push(to_result(id, &ray, point, u, v, true));
+                    }
+                }
+                _ => {
+                    if let Some(mesh) = scene.get_meshes().get(&id) {
+                        let point = to_world(mesh, hit.get_point());
+                        hits.push(to_result(id, &ray, point, 0.0, 0.0, false));
+                    }
+                }
+            }
+        }
+
+        // Line hits are already in world space
+        for (id, target, hit) in line_hits {
+            let found = match target {
+                PickTarget::Curve => scene.get_curves().get(&id).map(|curve| {
+                    let params = curve.get_sample_params();
+                    let segment = hit.get_segment() as usize;
+                    params[segment] + (params[segment + 1] - params[segment]) * hit.get_s()
+                }),
+                PickTarget::Polyline => scene.get_polylines().get(&id).map(|_| 0.0),
+                _ => scene.get_lines().get(&id).map(|_| 0.0),
+            };
+            if let Some(u) = found {
+                hits.push(to_result(id, &ray, *hit.get_point(), u, 0.0, false));
+            }
+        }
+
+        hits.into_iter().min_by(|a, b| a.t.total_cmp(&b.t))
+    }
+}
+
+/// Moves a world space ray into the geometries model space.
+/// None, with a warning, if the model can not be inverted.
+fn to_local(geometry: &dyn Geometry, ray: &Ray) -> Option<Ray> {
+    let Some(inverse) = geometry.get_bind_group_object().get_model().inverse() else {
+        log::warn!("skipping geometry with a model matrix that is not invertible");
+        return None;
+    };
+    Some(Ray::new(
+        inverse.transform_point(ray.get_origin()),
+        inverse.transform_vector(ray.get_direction()),
+    ))
+}
+
+fn to_world(geometry: &dyn Geometry, local_point: &Vec3) -> Vec3 {
+    geometry
+        .get_bind_group_object()
+        .get_model()
+        .transform_point(local_point)
+}
+
+fn to_result(
+    id: GeometryId,
+    world_ray: &Ray,
+    point: Vec3,
+    u: f32,
+    v: f32,
+    is_surface: bool,
+) -> PickResult {
+    PickResult {
+        id,
+        point,
+        u,
+        v,
+        is_surface,
+        t: Vec3::subtract(&point, world_ray.get_origin()).len(),
+    }
+}
@@ -0,0 +1,118 @@
+//! Chooses where to sample curves and surfaces.
+//!
+//! Each knot span gets enough evenly spaced samples to satisfy the chordal deviation
+//! and angle tolerances, based on its length and max curvature.
+//! A segment of length s on an arc of radius r deviates from it by about s * s / 8r,
+//! and turns by s / r.
+
+use crate::math::{
+    linear_algebra::vec3::Vec3,
+    nurbs::{curve::NurbsCurve, surface::NurbsSurface},
+};
+
+use super::params::{
+    SamplingTolerance, MAX_SEGMENTS_PER_SPAN, MIN_SEGMENTS_PER_SPAN, PROBES_PER_SPAN,
+};
+
+/// Segments needed for a stretch of geometry with the given length and max curvature
+pub fn segment_count(length: f32, curvature: f32, tolerance: &SamplingTolerance) -> u32 {
+    let for_angle = length * curvature / tolerance.angle;
+    let for_deviation = length * f32::sqrt(curvature / (8.0 * tolerance.chordal_deviation));
+    let segments = for_angle.max(for_deviation).ceil();
+    if !segments.is_finite() {
+        return MAX_SEGMENTS_PER_SPAN;
+    }
+    (segments as u32).clamp(MIN_SEGMENTS_PER_SPAN, MAX_SEGMENTS_PER_SPAN)
+}
+
+/// Curvature from the first and second derivative
+fn curvature(d1: &Vec3, d2: &Vec3) -> f32 {
+    let speed_squared = Vec3::dot(d1, d1);
+    if speed_squared == 0.0 {
+        return 0.0;
+    }
+    Vec3::cross(d1, d2).len() / (speed_squared * speed_squared.sqrt())
+}
+
+/// Start and end of every non empty knot span
+fn get_spans(knots: &[f32], degree: u32) -> Vec<(f32, f32)> {
+    let degree = degree as usize;
+    (degree..knots.len() - degree - 1)
+        .filter(|i| knots[*i] < knots[i + 1])
+        .map(|i| (knots[i], knots[i + 1]))
+        .collect()
+}
+
+fn get_probes((start, end): (f32, f32)) -> impl Iterator<Item = f32> {
+    (0..=PROBES_PER_SPAN).map(move |i| start + (end - start) * i as f32 / PROBES_PER_SPAN as f32)
+}
+
+/// Evenly spaces segment_counts[i] segments over span i
+fn create_params(spans: &[(f32, f32)], segment_counts: &[u32]) -> Vec<f32> {
+    let mut res = Vec::new();
+    for ((start, end), segments) in spans.iter().zip(segment_counts.iter()) {
+        for i in 0..*segments {
+            res.push(start + (end - start) * i as f32 / *segments as f32);
+        }
+    }
+    res.push(spans.last().unwrap().1);
+    res
+}
+
+/// Parameters to sample the curve at, in increasing order
+pub fn create_curve_sample_params(curve: &NurbsCurve, tolerance: &SamplingTolerance) -> Vec<f32> {
+    let spans = get_spans(&curve.knots, curve.degree);
+    let segment_counts: Vec<u32> = spans
+        .iter()
+        .map(|span| {
+            let mut speed: f32 = 0.0;
+            let mut max_curvature: f32 = 0.0;
+            for t in get_probes(*span) {
+                let ders = curve.derivatives(t, 2);
+                speed = speed.max(ders[1].len());
+                max_curvature = max_curvature.max(curvature(&ders[1], &ders[2]));
+            }
+            segment_count(speed * (span.1 - span.0), max_curvature, tolerance)
+        })
+        .collect();
+    create_params(&spans, &segment_counts)
+}
+
+/// Parameters to sample the surface at in u and in v, in increasing order.
+/// Every u span uses the worst case over all of v, and the other way around,
+/// so the samples still form a grid.
+pub fn create_surface_sample_params(
+    surface: &NurbsSurface,
+    tolerance: &SamplingTolerance,
+) -> (Vec<f32>, Vec<f32>) {
+    let spans_u = get_spans(&surface.knots_u, surface.degree_u);
+    let spans_v = get_spans(&surface.knots_v, surface.degree_v);
+
+    let mut segment_counts_u = vec![MIN_SEGMENTS_PER_SPAN; spans_u.len()];
+    let mut segment_counts_v = vec![MIN_SEGMENTS_PER_SPAN; spans_v.len()];
+
+    for (i, span_u) in spans_u.iter().enumerate() {
+        for u in get_probes(*span_u) {
+            for (j, span_v) in spans_v.iter().enumerate() {
+                for v in get_probes(*span_v) {
+                    let ders = surface.derivatives(u, v, 2);
+
+                    let length_u = ders[1][0].len() * (span_u.1 - span_u.0);
+                    let curvature_u = curvature(&ders[1][0], &ders[2][0]);
+                    segment_counts_u[i] =
+                        segment_counts_u[i].max(segment_count(length_u, curvature_u, tolerance));
+
+                    let length_v = ders[0][1].len() * (span_v.1 - span_v.0);
+                    let curvature_v = curvature(&ders[0][1], &ders[0][2]);
+                    segment_counts_v[j] =
+                        segment_counts_v[j].max(segment_count(length_v, curvature_v, tolerance));
+                }
+            }
+        }
+    }
+
+    (
+        create_params(&spans_u, &segment_counts_u),
+        create_params(&spans_v, &segment_counts_v),
+    )
+}
@@ -1,9 +1,9 @@
-use std::rc::Rc;
+use std::{cell::Cell, rc::Rc};
 
 use wgpu::util::DeviceExt;
 
 use crate::{
-    gpu_samplers::params::SAMPLES_PER_SEGMENT, math::linear_algebra::vec4::Vec4,
+    gpu_samplers::params::SamplingTolerance, math::linear_algebra::vec4::Vec4,
     render::renderer::Renderer, utils::create_compute_pipeline,
 };
 
@@ -21,6 +21,8 @@ pub struct CurveSampler {
     renderer: Rc<Renderer>,
     bind_group_layout: wgpu::BindGroupLayout,
     pipeline: wgpu::ComputePipeline,
+    /// Used by curves that dont have their own
+    sampling_tolerance: Cell<SamplingTolerance>,
 }
 
 impl CurveSampler {
@@ -42,6 +44,8 @@ impl CurveSampler {
                 crate::utils::compute_buffer_bind_group_layout_entry(4, false),
                 // Samples
                 crate::utils::compute_buffer_bind_group_layout_entry(5, false),
+                // Sample params
+                crate::utils::compute_buffer_bind_group_layout_entry(6, true),
             ],
         });
 
@@ -57,14 +61,17 @@ impl CurveSampler {
             renderer,
             bind_group_layout,
             pipeline,
+            sampling_tolerance: Cell::new(SamplingTolerance::default()),
         }
     }
 
+    /// Samples the curve at each of sample_params, see gpu_samplers::adaptive
     pub fn sample_curve(
         &self,
         degree: u32,
         weighted_controls: &[Vec4],
         knots: &[f32],
+        sample_params: &[f32],
     ) -> wgpu::Buffer {
         let device = self.renderer.get_device();
         let queue = self.renderer.get_queue();
@@ -79,8 +86,7 @@ impl CurveSampler {
             usage: wgpu::BufferUsages::UNIFORM,
         });
 
-        let sample_count: u64 =
-            SAMPLES_PER_SEGMENT as u64 * (weighted_controls.len() as u64 - 1) + 1;
+        let sample_count = sample_params.len() as u64;
 
         let samples: wgpu::Buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("curve sampler output sample buffer"),
@@ -91,7 +97,9 @@ impl CurveSampler {
         let output: wgpu::Buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("curve sampler output buffer"),
             size: sample_count * 16,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            usage: wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
         let basis_funcs: wgpu::Buffer = device.create_buffer(&wgpu::BufferDescriptor {
@@ -113,7 +121,13 @@ impl CurveSampler {
             usage: wgpu::BufferUsages::STORAGE,
         });
 
-        let span_buffer = create_span_buffer(device, knots, degree, sample_count as u32);
+        let span_buffer = create_span_buffer(device, knots, degree, sample_params);
+
+        let sample_param_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("curve sample param buffer"),
+            contents: bytemuck::cast_slice(sample_params),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
 
         let bind_group: wgpu::BindGroup = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("curve sampler bind group"),
@@ -143,6 +157,10 @@ impl CurveSampler {
                     binding: 5,
                     resource: samples.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: sample_param_buffer.as_entire_binding(),
+                },
             ],
         });
 
@@ -170,6 +188,14 @@ impl CurveSampler {
         output
     }
 
+    pub fn get_sampling_tolerance(&self) -> SamplingTolerance {
+        self.sampling_tolerance.get()
+    }
+
+    pub fn set_sampling_tolerance(&self, sampling_tolerance: SamplingTolerance) {
+        self.sampling_tolerance.set(sampling_tolerance);
+    }
+
     pub fn get_renderer(&self) -> Rc<Renderer> {
         self.renderer.clone()
     }
@@ -5,6 +5,7 @@
 @group(0) @binding(3) var<storage, read> spans: array<u32>;
 @group(0) @binding(4) var<storage, read_write> basisFuncs: array<f32>;
 @group(0) @binding(5) var<storage, read_write> samples: array<vec4<f32>>;
+@group(0) @binding(6) var<storage, read> sampleParams: array<f32>;
 
 struct Params {
   controlCount: u32,
@@ -18,7 +19,7 @@ fn main(
   @builtin(global_invocation_id) id: vec3<u32>,
   @builtin(num_workgroups) size: vec3<u32>
   ) {
-  let u: f32 = f32(id.x) / f32(size.x - 1) * knots[params.knotCount - 1];
+  let u: f32 = sampleParams[id.x];
   let s = spans[id.x];
 
   let offset: u32 = id.x * (params.degree + 1);
@@ -138,4 +138,15 @@ impl IndexBufferGenerator {
         //self.memo.insert(key, index_buffer);
         index_buffer
     }
+
+    /// Index buffer of a trimmed surface, whose triangles come from the CPU, see gpu_samplers::trim
+    pub fn get_trimmed_index_buffer(&self, device: &wgpu::Device, indices: &[u32]) -> wgpu::Buffer {
+        device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("trimmed surface index buffer"),
+            contents: bytemuck::cast_slice(indices),
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::INDEX
+                | wgpu::BufferUsages::COPY_SRC,
+        })
+    }
 }
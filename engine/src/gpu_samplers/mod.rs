@@ -1,5 +1,8 @@
+pub mod adaptive;
 pub mod curve_sampler;
 pub mod index_buffer_generator;
+pub mod offset_sampler;
 pub mod params;
 pub mod surface_sampler;
+pub mod trim;
 pub mod utils;
@@ -0,0 +1,158 @@
+use std::rc::Rc;
+
+use wgpu::util::DeviceExt;
+
+use crate::{
+    math::linear_algebra::{mat4::Mat4, vec3::Vec3, vec4::Vec4},
+    render::renderer::Renderer,
+    utils::create_compute_pipeline,
+};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct OffsetSamplerUniforms {
+    model: Mat4,
+    distance: f32,
+    _padding: [u32; 3],
+}
+
+/// Offsets the samples of a surface along their normals, see Scene::offset_surface
+pub struct OffsetSampler {
+    renderer: Rc<Renderer>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl OffsetSampler {
+    pub fn new(renderer: Rc<Renderer>) -> OffsetSampler {
+        let device = renderer.get_device();
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("offset sampler bind group layout"),
+            entries: &[
+                // Params
+                crate::utils::compute_uniform_bind_group_layout_entry(0),
+                // Vertex buffer
+                crate::utils::compute_buffer_bind_group_layout_entry(1, true),
+                // Offsets
+                crate::utils::compute_buffer_bind_group_layout_entry(2, false),
+            ],
+        });
+
+        let pipeline = create_compute_pipeline(
+            device,
+            "offset sampler pipeline",
+            include_str!("offset_sampler.wgsl"),
+            &bind_group_layout,
+            "main",
+        );
+
+        OffsetSampler {
+            renderer,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    /// World space points distance along the normals of a grid of samples from SurfaceSampler::sample_surface.
+    /// The model may only rotate and move. Samples without a normal take the average of the samples around them,
+    /// and are only left in place if none of those have one either.
+    pub async fn sample_offset(
+        &self,
+        vertex_buffer: &wgpu::Buffer,
+        sample_count_u: u32,
+        sample_count_v: u32,
+        model: &Mat4,
+        distance: f32,
+    ) -> Vec<Vec3> {
+        let device = self.renderer.get_device();
+        let offsets_size = (sample_count_u * sample_count_v) as u64 * 16;
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("offset sampler params"),
+            contents: bytemuck::cast_slice(&[OffsetSamplerUniforms {
+                model: *model,
+                distance,
+                _padding: [0; 3],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let offsets = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("offset sampler offsets"),
+            size: offsets_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let read_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("offset sampler read buffer"),
+            size: offsets_size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("offset sampler bind group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: vertex_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: offsets.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("offset sampler"),
+        });
+
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("offset sampler"),
+                timestamp_writes: None,
+            });
+
+            compute_pass.set_pipeline(&self.pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[]);
+            compute_pass.dispatch_workgroups(sample_count_u, sample_count_v, 1);
+        }
+
+        encoder.copy_buffer_to_buffer(&offsets, 0, &read_buffer, 0, offsets_size);
+
+        let idx = self.renderer.get_queue().submit([encoder.finish()]);
+        device.poll(wgpu::Maintain::WaitForSubmissionIndex(idx));
+
+        let (sender, receiver) = futures::channel::oneshot::channel();
+
+        let slice = read_buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| {
+            let _ = sender.send(result);
+        });
+
+        receiver
+            .await
+            .expect("communication failed")
+            .expect("buffer reading failed");
+
+        let res: Vec<Vec3> = bytemuck::cast_slice::<u8, Vec4>(&slice.get_mapped_range())
+            .iter()
+            .map(|point| point.to_vec3_safe())
+            .collect();
+        read_buffer.unmap();
+
+        res
+    }
+
+    pub fn get_renderer(&self) -> Rc<Renderer> {
+        self.renderer.clone()
+    }
+}
@@ -0,0 +1,49 @@
+// Moves every sample of a surface distance along its normal, in world space
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> vertex_buffer: array<Vertex>;
+@group(0) @binding(2) var<storage, read_write> offsets: array<vec4<f32>>;
+
+struct Params {
+  model: mat4x4<f32>,
+  distance: f32,
+}
+
+struct Vertex {
+  position: vec4<f32>,
+  normal: vec4<f32>,
+}
+
+// The model only rotates and moves, so it maps normals like directions
+fn world_normal(index: u32) -> vec3<f32> {
+  return (params.model * vec4<f32>(vertex_buffer[index].normal.xyz, 0.0)).xyz;
+}
+
+@compute @workgroup_size(1,1,1)
+fn main(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(num_workgroups) size: vec3<u32>
+    ) {
+
+  let index = id.x + id.y * size.x;
+  let vertex = vertex_buffer[index];
+  let position = (params.model * vec4<f32>(vertex.position.xyz, 1.0)).xyz;
+  var normal = world_normal(index);
+
+  // The surface sampler finds no normal where even the neighbouring partials vanish,
+  // like at a patch collapsed to a point. Average the normals of the samples around it.
+  if (length(normal) == 0.0) {
+    for (var y = max(i32(id.y) - 1, 0); y <= min(i32(id.y) + 1, i32(size.y) - 1); y++) {
+      for (var x = max(i32(id.x) - 1, 0); x <= min(i32(id.x) + 1, i32(size.x) - 1); x++) {
+        normal += world_normal(u32(x) + u32(y) * size.x);
+      }
+    }
+  }
+
+  // Only samples with no normal around them stay where they are
+  if (length(normal) == 0.0) {
+    offsets[index] = vec4<f32>(position, 1.0);
+  } else {
+    offsets[index] = vec4<f32>(position + params.distance * normalize(normal), 1.0);
+  }
+}
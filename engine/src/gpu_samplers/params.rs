@@ -1,16 +1,25 @@
-use wasm_bindgen::prelude::*;
-
-// TODO: use a more sophisticated way of allocating samples.
-// Could take into account curvature, knot distribution, degree...
-pub const SAMPLES_PER_SEGMENT: u32 = 10;
-
-#[wasm_bindgen]
-pub fn get_samples_per_segment() -> u32 {
-    SAMPLES_PER_SEGMENT
+/// How closely sampled polylines and meshes follow the exact geometry.
+/// Sample counts are chosen per knot span so both tolerances are met.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SamplingTolerance {
+    /// Max distance between the geometry and a segment between two samples
+    pub chordal_deviation: f32,
+    /// Max angle in radians between the tangents at two neighbouring samples
+    pub angle: f32,
 }
 
-#[wasm_bindgen]
-pub fn set_samples_per_segment(samples: u32) {
-    // NOTE: This will be danderous one SAMPLES_PER_SEGMENT is mutable
-    todo!()
+impl Default for SamplingTolerance {
+    fn default() -> Self {
+        Self {
+            chordal_deviation: 0.01,
+            angle: 10.0_f32.to_radians(),
+        }
+    }
 }
+
+/// Bounds on the number of segments per knot span
+pub const MIN_SEGMENTS_PER_SPAN: u32 = 1;
+pub const MAX_SEGMENTS_PER_SPAN: u32 = 64;
+
+/// Parameters evaluated in each span when estimating its length and curvature
+pub const PROBES_PER_SPAN: u32 = 4;
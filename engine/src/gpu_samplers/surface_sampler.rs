@@ -8,16 +8,21 @@
 //!
 //!
 
-use std::rc::Rc;
+use std::{cell::Cell, rc::Rc};
 
 use wgpu::util::{BufferInitDescriptor, DeviceExt};
 
 use crate::{
-    gpu_samplers::params::SAMPLES_PER_SEGMENT, math::linear_algebra::vec4::Vec4,
-    render::renderer::Renderer, utils::create_compute_pipeline,
+    gpu_samplers::params::SamplingTolerance,
+    math::{linear_algebra::vec4::Vec4, nurbs::surface::NurbsSurface},
+    render::renderer::Renderer,
+    utils::create_compute_pipeline,
 };
 
-use super::{index_buffer_generator::IndexBufferGenerator, utils::create_span_buffer};
+use super::{
+    index_buffer_generator::IndexBufferGenerator, trim::TrimmedTessellation,
+    utils::create_span_buffer,
+};
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
@@ -35,6 +40,55 @@ struct SurfaceSamplerStage2Uniforms {
     degree_v: u32,
 }
 
+/// One parameter direction of a surface for SurfaceSampler::sample_surface
+pub struct SurfaceDirectionInput<'a> {
+    pub degree: u32,
+    pub control_count: u32,
+    pub knots: &'a [f32],
+    pub sample_params: &'a [f32],
+}
+
+impl<'a> SurfaceDirectionInput<'a> {
+    pub fn u(nurbs: &'a NurbsSurface, sample_params: &'a [f32]) -> Self {
+        Self {
+            degree: nurbs.degree_u,
+            control_count: nurbs.control_count_u,
+            knots: &nurbs.knots_u,
+            sample_params,
+        }
+    }
+
+    pub fn v(nurbs: &'a NurbsSurface, sample_params: &'a [f32]) -> Self {
+        Self {
+            degree: nurbs.degree_v,
+            control_count: nurbs.control_count_v,
+            knots: &nurbs.knots_v,
+            sample_params,
+        }
+    }
+}
+
+/// A direction with its spans and sample params uploaded
+struct DirectionBuffers<'a> {
+    input: &'a SurfaceDirectionInput<'a>,
+    spans: wgpu::Buffer,
+    sample_params: wgpu::Buffer,
+}
+
+impl<'a> DirectionBuffers<'a> {
+    fn new(device: &wgpu::Device, input: &'a SurfaceDirectionInput<'a>) -> Self {
+        Self {
+            input,
+            spans: create_span_buffer(device, input.knots, input.degree, input.sample_params),
+            sample_params: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("surface sample param buffer"),
+                contents: bytemuck::cast_slice(input.sample_params),
+                usage: wgpu::BufferUsages::STORAGE,
+            }),
+        }
+    }
+}
+
 pub struct SurfaceSampler {
     renderer: Rc<Renderer>,
     bind_group_layout_stage_1: wgpu::BindGroupLayout,
@@ -44,6 +98,8 @@ pub struct SurfaceSampler {
     pipeline_stage_2: wgpu::ComputePipeline,
     pipeline_stage_3: wgpu::ComputePipeline,
     index_buffer_generator: IndexBufferGenerator,
+    /// Used by surfaces that dont have their own
+    sampling_tolerance: Cell<SamplingTolerance>,
 }
 
 impl SurfaceSampler {
@@ -61,6 +117,8 @@ impl SurfaceSampler {
                     crate::utils::compute_buffer_bind_group_layout_entry(2, true),
                     // Basis Funcs
                     crate::utils::compute_buffer_bind_group_layout_entry(3, false),
+                    // Sample params
+                    crate::utils::compute_buffer_bind_group_layout_entry(4, true),
                 ],
             });
 
@@ -83,6 +141,8 @@ impl SurfaceSampler {
                     crate::utils::compute_buffer_bind_group_layout_entry(5, true),
                     // Samples
                     crate::utils::compute_buffer_bind_group_layout_entry(6, false),
+                    // Partials
+                    crate::utils::compute_buffer_bind_group_layout_entry(7, false),
                 ],
             });
         let bind_group_layout_stage_3 =
@@ -91,6 +151,14 @@ impl SurfaceSampler {
                 entries: &[
                     // Vertex_buffer
                     crate::utils::compute_buffer_bind_group_layout_entry(0, false),
+                    // Partials
+                    crate::utils::compute_buffer_bind_group_layout_entry(1, true),
+                    // Sample params U
+                    crate::utils::compute_buffer_bind_group_layout_entry(2, true),
+                    // Sample params V
+                    crate::utils::compute_buffer_bind_group_layout_entry(3, true),
+                    // UVs
+                    crate::utils::compute_buffer_bind_group_layout_entry(4, false),
                 ],
             });
 
@@ -125,115 +193,23 @@ impl SurfaceSampler {
             pipeline_stage_2,
             pipeline_stage_3,
             index_buffer_generator: IndexBufferGenerator::new(device),
+            sampling_tolerance: Cell::new(SamplingTolerance::default()),
         }
     }
 
     fn create_basis_funcs(
         &self,
-        control_count_u: u32,
-        control_count_v: u32,
-        degree_u: u32,
-        degree_v: u32,
-        knots_u: &[f32],
-        knots_v: &[f32],
-        span_buffer_u: &wgpu::Buffer,
-        span_buffer_v: &wgpu::Buffer,
+        u: &DirectionBuffers,
+        v: &DirectionBuffers,
     ) -> (wgpu::Buffer, wgpu::Buffer) {
         let device = self.renderer.get_device();
         let queue = self.renderer.get_queue();
-
-        let uniform_buffer_u = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("surface sampler stage 1 u uniform buffer"),
-            contents: bytemuck::cast_slice(&[SurfaceSamplerStage1Uniforms {
-                control_count: control_count_u,
-                knot_count: knots_u.len() as u32,
-                degree: degree_u,
-            }]),
-            usage: wgpu::BufferUsages::UNIFORM,
-        });
-
-        let uniform_buffer_v = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("surface sampler stage 1 v uniform buffer"),
-            contents: bytemuck::cast_slice(&[SurfaceSamplerStage1Uniforms {
-                control_count: control_count_v,
-                knot_count: knots_v.len() as u32,
-                degree: degree_v,
-            }]),
-            usage: wgpu::BufferUsages::UNIFORM,
-        });
-
-        let knot_buffer_u = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("surface sample knot u buffer"),
-            contents: bytemuck::cast_slice(knots_u),
-            usage: wgpu::BufferUsages::STORAGE,
-        });
-        let knot_buffer_v = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("surface sample knot v buffer"),
-            contents: bytemuck::cast_slice(knots_v),
-            usage: wgpu::BufferUsages::STORAGE,
-        });
-
-        let sample_count_u: u64 = SAMPLES_PER_SEGMENT as u64 * (control_count_u as u64 - 1) + 1;
-        let sample_count_v: u64 = SAMPLES_PER_SEGMENT as u64 * (control_count_v as u64 - 1) + 1;
-
-        let basis_funcs_u: wgpu::Buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("surface sampler basis funcs u buffer"),
-            size: sample_count_u * (degree_u + 1) as u64 * std::mem::size_of::<f32>() as u64,
-            usage: wgpu::BufferUsages::STORAGE,
-            mapped_at_creation: false,
-        });
-        let basis_funcs_v: wgpu::Buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("surface sampler basis funcs v buffer"),
-            size: sample_count_v * (degree_v + 1) as u64 * std::mem::size_of::<f32>() as u64,
-            usage: wgpu::BufferUsages::STORAGE,
-            mapped_at_creation: false,
-        });
-
-        let bind_group_u: wgpu::BindGroup = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("surface sampler bind group"),
-            layout: &self.bind_group_layout_stage_1,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: uniform_buffer_u.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: knot_buffer_u.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: span_buffer_u.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 3,
-                    resource: basis_funcs_u.as_entire_binding(),
-                },
-            ],
-        });
-
-        let bind_group_v: wgpu::BindGroup = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("surface sampler bind group"),
-            layout: &self.bind_group_layout_stage_1,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: uniform_buffer_v.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: knot_buffer_v.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: span_buffer_v.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 3,
-                    resource: basis_funcs_v.as_entire_binding(),
-                },
-            ],
-        });
+        let sample_count_u = u.input.sample_params.len() as u64;
+        let sample_count_v = v.input.sample_params.len() as u64;
+        let basis_funcs_u = self.create_basis_funcs_buffer(u, "u");
+        let basis_funcs_v = self.create_basis_funcs_buffer(v, "v");
+        let bind_group_u = self.create_stage_1_bind_group(u, &basis_funcs_u);
+        let bind_group_v = self.create_stage_1_bind_group(v, &basis_funcs_v);
 
         let mut encoder_u = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("surface sampler stage 1 u command encoder"),
@@ -270,32 +246,97 @@ impl SurfaceSampler {
         (basis_funcs_u, basis_funcs_v)
     }
 
+    /// Basis funcs followed by their derivatives, for each sample of one direction
+    fn create_basis_funcs_buffer(&self, direction: &DirectionBuffers, name: &str) -> wgpu::Buffer {
+        let sample_count = direction.input.sample_params.len() as u64;
+        self.renderer
+            .get_device()
+            .create_buffer(&wgpu::BufferDescriptor {
+                label: Some(&format!("surface sampler basis funcs {} buffer", name)),
+                size: sample_count
+                    * (direction.input.degree + 1) as u64
+                    * 2
+                    * std::mem::size_of::<f32>() as u64,
+                usage: wgpu::BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            })
+    }
+
+    fn create_stage_1_bind_group(
+        &self,
+        direction: &DirectionBuffers,
+        basis_funcs: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        let device = self.renderer.get_device();
+        let uniform_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("surface sampler stage 1 uniform buffer"),
+            contents: bytemuck::cast_slice(&[SurfaceSamplerStage1Uniforms {
+                control_count: direction.input.control_count,
+                knot_count: direction.input.knots.len() as u32,
+                degree: direction.input.degree,
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let knot_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("surface sample knot buffer"),
+            contents: bytemuck::cast_slice(direction.input.knots),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("surface sampler bind group"),
+            layout: &self.bind_group_layout_stage_1,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: knot_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: direction.spans.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: basis_funcs.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: direction.sample_params.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    /// Samples the surface on the grid of sample_params_u by sample_params_v,
+    /// see gpu_samplers::adaptive.
+    /// Returns the index buffer, the vertex buffer with analytic normals,
+    /// and a buffer with the (u, v) of each vertex as two f32s.
     pub fn sample_surface(
         &self,
-        degree_u: u32,
-        degree_v: u32,
         weighted_controls: &[Vec4],
-        control_count_u: u32,
-        control_count_v: u32,
-        knots_u: &[f32],
-        knots_v: &[f32],
-    ) -> (wgpu::Buffer, wgpu::Buffer) {
+        u: &SurfaceDirectionInput,
+        v: &SurfaceDirectionInput,
+    ) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer) {
         let device = self.renderer.get_device();
         let queue = self.renderer.get_queue();
 
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("surface sampler stage 2 uniform buffer"),
             contents: bytemuck::cast_slice(&[SurfaceSamplerStage2Uniforms {
-                control_count_u,
-                degree_u,
-                control_count_v,
-                degree_v,
+                control_count_u: u.control_count,
+                degree_u: u.degree,
+                control_count_v: v.control_count,
+                degree_v: v.degree,
             }]),
             usage: wgpu::BufferUsages::UNIFORM,
         });
 
-        let sample_count_u: u64 = SAMPLES_PER_SEGMENT as u64 * (control_count_u as u64 - 1) + 1;
-        let sample_count_v: u64 = SAMPLES_PER_SEGMENT as u64 * (control_count_v as u64 - 1) + 1;
+        let sample_count_u = u.sample_params.len() as u64;
+        let sample_count_v = v.sample_params.len() as u64;
 
         let samples: wgpu::Buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("surface sampler output sample buffer"),
@@ -307,24 +348,30 @@ impl SurfaceSampler {
             label: Some("surface sampler output buffer"),
             size: sample_count_u * sample_count_v * 16 * 2,
             usage: wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::STORAGE
                 | wgpu::BufferUsages::COPY_DST
                 | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
-        let span_buffer_u = create_span_buffer(device, knots_u, degree_u, sample_count_u as u32);
-        let span_buffer_v = create_span_buffer(device, knots_v, degree_v, sample_count_v as u32);
-
-        let (basis_funcs_u, basis_funcs_v) = self.create_basis_funcs(
-            control_count_u,
-            control_count_v,
-            degree_u,
-            degree_v,
-            knots_u,
-            knots_v,
-            &span_buffer_u,
-            &span_buffer_v,
-        );
+        let partials: wgpu::Buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("surface sampler partials buffer"),
+            size: sample_count_u * sample_count_v * 16 * 3,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        let uv_buffer: wgpu::Buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("surface sampler uv buffer"),
+            size: sample_count_u * sample_count_v * 8,
+            usage: wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let buffers_u = DirectionBuffers::new(device, u);
+        let buffers_v = DirectionBuffers::new(device, v);
+        let (basis_funcs_u, basis_funcs_v) = self.create_basis_funcs(&buffers_u, &buffers_v);
 
         let control_point_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("surface sample control point buffer"),
@@ -354,26 +401,48 @@ impl SurfaceSampler {
                 },
                 wgpu::BindGroupEntry {
                     binding: 4,
-                    resource: span_buffer_u.as_entire_binding(),
+                    resource: buffers_u.spans.as_entire_binding(),
                 },
                 wgpu::BindGroupEntry {
                     binding: 5,
-                    resource: span_buffer_v.as_entire_binding(),
+                    resource: buffers_v.spans.as_entire_binding(),
                 },
                 wgpu::BindGroupEntry {
                     binding: 6,
                     resource: samples.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 7,
+                    resource: partials.as_entire_binding(),
+                },
             ],
         });
         let bind_group_stage_3: wgpu::BindGroup =
             device.create_bind_group(&wgpu::BindGroupDescriptor {
                 label: Some("surface sampler bind group"),
                 layout: &self.bind_group_layout_stage_3,
-                entries: &[wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: samples.as_entire_binding(),
-                }],
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: samples.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: partials.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: buffers_u.sample_params.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: buffers_v.sample_params.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 4,
+                        resource: uv_buffer.as_entire_binding(),
+                    },
+                ],
             });
 
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -420,8 +489,98 @@ impl SurfaceSampler {
             sample_count_v as u32,
         );
 
-        (index_buffer, vertex_buffer)
+        (index_buffer, vertex_buffer, uv_buffer)
     }
+
+    /// Cuts the buffers of sample_surface down to the kept region of a trimmed surface.
+    /// The extra vertices of the tessellation are evaluated on the CPU and go after the grid vertices,
+    /// and the index buffer is replaced by one with the trimmed triangles.
+    pub fn trim_samples(
+        &self,
+        nurbs: &NurbsSurface,
+        vertex_buffer: &wgpu::Buffer,
+        uv_buffer: &wgpu::Buffer,
+        grid_vertex_count: u64,
+        tessellation: &TrimmedTessellation,
+    ) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer) {
+        let device = self.renderer.get_device();
+        let queue = self.renderer.get_queue();
+        let vertex_count = grid_vertex_count + tessellation.extra_uvs.len() as u64;
+
+        let trimmed_vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("trimmed surface vertex buffer"),
+            size: vertex_count * 16 * 2,
+            usage: wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let trimmed_uv_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("trimmed surface uv buffer"),
+            size: vertex_count * 8,
+            usage: wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        // Same layout as the samples, position then normal
+        let extra_vertices: Vec<[f32; 4]> = tessellation
+            .extra_uvs
+            .iter()
+            .flat_map(|[u, v]| {
+                let point = nurbs.point(*u, *v);
+                let normal = nurbs.normal(*u, *v);
+                [
+                    [point.x, point.y, point.z, 1.0],
+                    [normal.x, normal.y, normal.z, 0.0],
+                ]
+            })
+            .collect();
+        if !extra_vertices.is_empty() {
+            queue.write_buffer(
+                &trimmed_vertex_buffer,
+                grid_vertex_count * 16 * 2,
+                bytemuck::cast_slice(&extra_vertices),
+            );
+            queue.write_buffer(
+                &trimmed_uv_buffer,
+                grid_vertex_count * 8,
+                bytemuck::cast_slice(&tessellation.extra_uvs),
+            );
+        }
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("surface sampler trim command encoder"),
+        });
+        encoder.copy_buffer_to_buffer(
+            vertex_buffer,
+            0,
+            &trimmed_vertex_buffer,
+            0,
+            grid_vertex_count * 16 * 2,
+        );
+        encoder.copy_buffer_to_buffer(uv_buffer, 0, &trimmed_uv_buffer, 0, grid_vertex_count * 8);
+        let idx = queue.submit([encoder.finish()]);
+        device.poll(wgpu::Maintain::WaitForSubmissionIndex(idx));
+
+        let index_buffer = self
+            .index_buffer_generator
+            .get_trimmed_index_buffer(device, &tessellation.indices);
+
+        (index_buffer, trimmed_vertex_buffer, trimmed_uv_buffer)
+    }
+
+    pub fn get_sampling_tolerance(&self) -> SamplingTolerance {
+        self.sampling_tolerance.get()
+    }
+
+    pub fn set_sampling_tolerance(&self, sampling_tolerance: SamplingTolerance) {
+        self.sampling_tolerance.set(sampling_tolerance);
+    }
+
     pub fn get_renderer(&self) -> Rc<Renderer> {
         self.renderer.clone()
     }
@@ -1,10 +1,12 @@
 // In phase1 the basis funcs are evaluated.
-// this creates basis funcs evaludated for each u.
+// this creates basis funcs evaludated for each sample param.
+// Each sample gets degree + 1 basis funcs followed by their first derivatives.
 
 @group(0) @binding(0) var<uniform> params: Params;
 @group(0) @binding(1) var<storage, read> knots: array<f32>;
 @group(0) @binding(2) var<storage, read> spans: array<u32>;
 @group(0) @binding(3) var<storage, read_write> basis_funcs: array<f32>;
+@group(0) @binding(4) var<storage, read> sample_params: array<f32>;
 
 struct Params {
   control_count: u32,
@@ -19,12 +21,19 @@ fn main(
   @builtin(num_workgroups) size: vec3<u32>
   ) {
 
-  let u: f32 = f32(id.x) / f32(size.x - 1) * knots[params.knot_count - 1];
+  let u: f32 = sample_params[id.x];
   let s: u32 = spans[id.x];
-  let offset: u32 = id.x * (params.degree + 1);
+  let offset: u32 = id.x * (params.degree + 1) * 2;
+  let derivative_offset: u32 = offset + params.degree + 1;
 
   basis_funcs[offset] = 1.0;
   for (var j: u32 = 1; j <= params.degree; j++) {
+    if (j == params.degree) {
+      // Keep the degree - 1 funcs, the derivatives are built from them
+      for (var r: u32 = 0; r < j; r++) {
+        basis_funcs[derivative_offset + r] = basis_funcs[offset + r];
+      }
+    }
     var saved: f32 = 0;
     for (var r: u32 = 0; r < j; r++) {
       let left: f32 = u - knots[s - (j - r) + 1];
@@ -35,5 +44,26 @@ fn main(
     }
     basis_funcs[j + offset] = saved;
   }
+
+  // N'(i, p) = p * N(i, p - 1) / (u(i + p) - u(i)) - p * N(i + 1, p - 1) / (u(i + p + 1) - u(i + 1))
+  // Highest first, so every degree - 1 func is read before it is overwritten.
+  let p: f32 = f32(params.degree);
+  for (var k: u32 = 0; k <= params.degree; k++) {
+    let r: u32 = params.degree - k;
+    var derivative: f32 = 0;
+    if (r > 0) {
+      let denominator = knots[s + r] - knots[s + r - params.degree];
+      if (denominator > 0.0) {
+        derivative += p * basis_funcs[derivative_offset + r - 1] / denominator;
+      }
+    }
+    if (r < params.degree) {
+      let denominator = knots[s + r + 1] - knots[s + r + 1 - params.degree];
+      if (denominator > 0.0) {
+        derivative -= p * basis_funcs[derivative_offset + r] / denominator;
+      }
+    }
+    basis_funcs[derivative_offset + r] = derivative;
+  }
 }
 
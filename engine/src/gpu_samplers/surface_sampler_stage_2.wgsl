@@ -1,3 +1,4 @@
+
 // compute samples
 
 @group(0) @binding(0) var<uniform> params: Params;
@@ -7,6 +8,8 @@
 @group(0) @binding(4) var<storage, read> spans_u: array<u32>;
 @group(0) @binding(5) var<storage, read> spans_v: array<u32>;
 @group(0) @binding(6) var<storage, read_write> samples: array<vec4<f32>>;
+// Homogeneous point, u partial and v partial of each sample, used by stage 3
+@group(0) @binding(7) var<storage, read_write> partials: array<vec4<f32>>;
 
 struct Params {
   control_count_u: u32,
@@ -22,19 +25,33 @@ struct Params {
       ) {
 
     var sample = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    var sample_du = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    var sample_dv = vec4<f32>(0.0, 0.0, 0.0, 0.0);
     let span_u = spans_u[id.x];
     let span_v = spans_v[id.y];
-    let u_offset = id.x * (params.degree_u + 1);
-    let v_offset = id.y * (params.degree_v + 1);
+    // Basis funcs are followed by their derivatives, see stage 1
+    let u_offset = id.x * (params.degree_u + 1) * 2;
+    let v_offset = id.y * (params.degree_v + 1) * 2;
+    let du_offset = u_offset + params.degree_u + 1;
+    let dv_offset = v_offset + params.degree_v + 1;
 
     for (var i: u32 = 0; i <= params.degree_u; i++) {
       for (var j: u32 = 0; j <= params.degree_v; j++) {
         let idx_x = span_u - params.degree_u + i;
         let idx_y = span_v - params.degree_v + j;
         let idx = idx_x + idx_y * params.control_count_u;
-        sample += weighted_controls[idx] * (basis_funcs_u[u_offset + i] * basis_funcs_v[v_offset + j]);
+        let control = weighted_controls[idx];
+        sample += control * (basis_funcs_u[u_offset + i] * basis_funcs_v[v_offset + j]);
+        sample_du += control * (basis_funcs_u[du_offset + i] * basis_funcs_v[v_offset + j]);
+        sample_dv += control * (basis_funcs_u[u_offset + i] * basis_funcs_v[dv_offset + j]);
       }
     }
+
+    let index = id.x + id.y * size.x;
+    partials[index * 3] = sample;
+    partials[index * 3 + 1] = sample_du;
+    partials[index * 3 + 2] = sample_dv;
+
     sample /= sample.w;
-    samples[(id.x + id.y * size.x) * 2] = sample;
+    samples[index * 2] = sample;
   }
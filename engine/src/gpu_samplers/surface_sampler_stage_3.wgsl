@@ -1,6 +1,33 @@
-// Calculate normals
+// Calculate normals from the partials of stage 2, and write the (u, v) of each sample
 
 @group(0) @binding(0) var<storage, read_write> vertex_buffer: array<vec4<f32>>;
+@group(0) @binding(1) var<storage, read> partials: array<vec4<f32>>;
+@group(0) @binding(2) var<storage, read> sample_params_u: array<f32>;
+@group(0) @binding(3) var<storage, read> sample_params_v: array<f32>;
+@group(0) @binding(4) var<storage, read_write> uvs: array<vec2<f32>>;
+
+const EPSILON = 0.00001;
+
+// S = A / w, so S' = (A' - w' * S) / w
+fn rational_derivative(point: vec4<f32>, derivative: vec4<f32>) -> vec3<f32> {
+  return (derivative.xyz - derivative.w * point.xyz / point.w) / point.w;
+}
+
+fn partial_u(index: u32) -> vec3<f32> {
+  return rational_derivative(partials[index * 3], partials[index * 3 + 1]);
+}
+
+fn partial_v(index: u32) -> vec3<f32> {
+  return rational_derivative(partials[index * 3], partials[index * 3 + 2]);
+}
+
+// True when a partial vanishes or they are parallel.
+// Measured against the longer partial, rounding keeps a collapsed one from being exactly 0.
+fn is_degenerate(normal: vec3<f32>, du: vec3<f32>, dv: vec3<f32>) -> bool {
+  let len = length(normal);
+  let scale = max(length(du), length(dv));
+  return len == 0.0 || len <= EPSILON * scale * scale;
+}
 
 @compute @workgroup_size(1,1,1) 
   fn main(
@@ -8,44 +35,35 @@
       @builtin(num_workgroups) size: vec3<u32>
       ) {
 
-    var offset_x: i32;
-    var offset_y: i32;
-    var flip: bool = false;
+    let index = id.x + id.y * size.x;
+    // One sample inwards, there are always at least two samples in each direction
+    let neighbour_u = select(index - 1, index + 1, id.x == 0);
+    let neighbour_v = select(index - size.x, index + size.x, id.y == 0);
 
-    if (id.x == 0) {
-      offset_x = 2;
-      flip = !flip;
-    } else {
-      offset_x = -2;
+    let du = partial_u(index);
+    let dv = partial_v(index);
+    var normal = cross(du, dv);
+
+    // Poles and collapsed edges have a vanishing partial.
+    // Borrow it from the neighbouring row or column, which points the same way.
+    if (is_degenerate(normal, du, dv)) {
+      let neighbour_du = partial_u(neighbour_v);
+      let neighbour_dv = partial_v(neighbour_u);
+      normal = cross(neighbour_du, dv);
+      if (is_degenerate(normal, neighbour_du, dv)) {
+        normal = cross(du, neighbour_dv);
+        if (is_degenerate(normal, du, neighbour_dv)) {
+          normal = cross(neighbour_du, neighbour_dv);
+        }
+      }
     }
 
-    if (id.y == 0) {
-      offset_y = i32(size.x) * 2;
-      flip = !flip;
+    // Only a patch collapsed to a point gets here, leave it unshaded rather than NaN
+    if (length(normal) == 0.0) {
+      vertex_buffer[index * 2 + 1] = vec4<f32>(0.0, 0.0, 0.0, 0.0);
     } else {
-      offset_y = i32(size.x) * -2;
+      vertex_buffer[index * 2 + 1] = vec4<f32>(normalize(normal), 0.0);
     }
 
-    let idx_point: i32 = i32(id.x + id.y * size.x) * 2;
-    let idx_point_dx: i32 = idx_point + offset_x;
-    let idx_point_dy: i32 = idx_point + offset_y;
-
-    let point = vertex_buffer[u32(idx_point)];
-    let point_dx = vertex_buffer[u32(idx_point_dx)];
-    let point_dy = vertex_buffer[u32(idx_point_dy)];
-
-// TODO: think about w...
-// TEST
-
-    let dx = point_dx - point;
-    let dy = point_dy - point;
-
-    var normal = vec4<f32>(normalize(cross(dx.xyz, dy.xyz)), 0.0);
-    if (flip){ normal = -1.0 * normal;}
-    
-    vertex_buffer[u32(idx_point) + 1] = normal;
-
+    uvs[index] = vec2<f32>(sample_params_u[id.x], sample_params_v[id.y]);
   }
-
-
-
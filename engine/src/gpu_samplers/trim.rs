@@ -0,0 +1,430 @@
+//! Tessellation of the kept region of a trimmed surface.
+//!
+//! The surface is still sampled on its grid. Cells no trimming loop enters are kept or dropped whole.
+//! Cells a loop crosses are cut along the loop and their kept part is triangulated by ear clipping,
+//! so triangle edges follow the trim boundary.
+//! Loop points and the points where loops cross grid lines become extra vertices,
+//! numbered after the grid vertices.
+//!
+//! A point on a grid line belongs to the cell above or to the right of it.
+
+use std::collections::HashMap;
+
+use crate::math::nurbs::trim::{signed_area, SurfaceTrims, TrimLoop};
+
+use super::{adaptive::create_curve_sample_params, params::SamplingTolerance};
+
+/// Chordal deviation of sampled trim curves, as a fraction of the size of the parameter domain
+const RELATIVE_DEVIATION: f32 = 1e-3;
+
+/// (u, v) samples of every curve of the loop in order, the closing point is not repeated
+pub fn sample_trim_loop(trim_loop: &TrimLoop, tolerance: &SamplingTolerance) -> Vec<[f32; 2]> {
+    let mut res: Vec<[f32; 2]> = Vec::new();
+    for curve in trim_loop.curves.iter() {
+        for t in create_curve_sample_params(curve, tolerance) {
+            let point = curve.point(t);
+            let point = [point.x, point.y];
+            if res.last() != Some(&point) {
+                res.push(point);
+            }
+        }
+    }
+    if res.len() > 1 && res.first() == res.last() {
+        res.pop();
+    }
+    res
+}
+
+/// Sampled loops, outer first, each oriented with the kept region on its left.
+/// Loops enclosing no area are left out.
+/// domain_size scales the sampling tolerance, use the size of the surfaces parameter domain.
+pub fn sample_trims(trims: &SurfaceTrims, domain_size: f32) -> Vec<Vec<[f32; 2]>> {
+    let tolerance = SamplingTolerance {
+        chordal_deviation: RELATIVE_DEVIATION * domain_size,
+        ..SamplingTolerance::default()
+    };
+    let outer = trims.outer.iter().map(|outer| (outer, true));
+    let inner = trims.inner.iter().map(|inner| (inner, false));
+    outer
+        .chain(inner)
+        .filter_map(|(trim_loop, is_outer)| {
+            let mut polyline = sample_trim_loop(trim_loop, &tolerance);
+            let area = signed_area(&polyline);
+            if polyline.len() < 3 || area == 0.0 {
+                return None;
+            }
+            if (area > 0.0) != is_outer {
+                polyline.reverse();
+            }
+            Some(polyline)
+        })
+        .collect()
+}
+
+/// Triangles of the kept region over a sample grid
+#[derive(Debug, Clone, Default)]
+pub struct TrimmedTessellation {
+    /// (u, v) of the vertices after the grid vertices
+    pub extra_uvs: Vec<[f32; 2]>,
+    /// Three per triangle, counterclockwise in (u, v) like the untrimmed grid
+    pub indices: Vec<u32>,
+}
+
+type Cell = (usize, usize);
+
+struct Grid<'a> {
+    params_u: &'a [f32],
+    params_v: &'a [f32],
+}
+
+impl Grid<'_> {
+    fn column(&self, u: f32) -> usize {
+        self.params_u[1..self.params_u.len() - 1].partition_point(|line| *line <= u)
+    }
+
+    fn row(&self, v: f32) -> usize {
+        self.params_v[1..self.params_v.len() - 1].partition_point(|line| *line <= v)
+    }
+
+    fn cell(&self, point: [f32; 2]) -> Cell {
+        (self.column(point[0]), self.row(point[1]))
+    }
+
+    fn clamp(&self, point: [f32; 2]) -> [f32; 2] {
+        let last_u = self.params_u.len() - 1;
+        let last_v = self.params_v.len() - 1;
+        [
+            point[0].clamp(self.params_u[0], self.params_u[last_u]),
+            point[1].clamp(self.params_v[0], self.params_v[last_v]),
+        ]
+    }
+
+    /// Bottom left, bottom right, top right and top left, counterclockwise
+    fn corners(&self, (column, row): Cell) -> [[f32; 2]; 4] {
+        let (u0, u1) = (self.params_u[column], self.params_u[column + 1]);
+        let (v0, v1) = (self.params_v[row], self.params_v[row + 1]);
+        [[u0, v0], [u1, v0], [u1, v1], [u0, v1]]
+    }
+
+    /// Where the segment p q leaves cell on its way to the cell of q, and the cell it goes into
+    fn crossing(&self, cell: Cell, target: Cell, p: [f32; 2], q: [f32; 2]) -> ([f32; 2], Cell) {
+        let (column, row) = cell;
+        // Grid line crossed in each direction, with how far along the segment it is
+        let step_u = (target.0 != column).then(|| {
+            let (line, next) = if target.0 > column {
+                (column + 1, column + 1)
+            } else {
+                (column, column - 1)
+            };
+            let u = self.params_u[line];
+            ((u - p[0]) / (q[0] - p[0]), u, next)
+        });
+        let step_v = (target.1 != row).then(|| {
+            let (line, next) = if target.1 > row {
+                (row + 1, row + 1)
+            } else {
+                (row, row - 1)
+            };
+            let v = self.params_v[line];
+            ((v - p[1]) / (q[1] - p[1]), v, next)
+        });
+
+        match (step_u, step_v) {
+            // Through a corner, straight into the diagonal neighbour
+            (Some((t_u, u, next_column)), Some((t_v, v, next_row))) if t_u == t_v => {
+                ([u, v], (next_column, next_row))
+            }
+            (Some((t_u, u, next_column)), step_v) if step_v.is_none_or(|(t_v, _, _)| t_u < t_v) => {
+                let v =
+                    (p[1] + (q[1] - p[1]) * t_u).clamp(self.params_v[row], self.params_v[row + 1]);
+                ([u, v], (next_column, row))
+            }
+            (_, Some((t_v, v, next_row))) => {
+                let u = (p[0] + (q[0] - p[0]) * t_v)
+                    .clamp(self.params_u[column], self.params_u[column + 1]);
+                ([u, v], (column, next_row))
+            }
+            _ => unreachable!("only called while the cells differ"),
+        }
+    }
+}
+
+/// Adds a u sample param inside every loop that no grid line crosses yet,
+/// so each loop passes through more than one cell and no cell holds a whole loop.
+pub fn add_crossing_params(params_u: &mut Vec<f32>, params_v: &[f32], loops: &[Vec<[f32; 2]>]) {
+    for polyline in loops {
+        let grid = Grid { params_u, params_v };
+        let (min, max) = polyline
+            .iter()
+            .map(|point| grid.clamp(*point)[0])
+            .fold((f32::MAX, f32::MIN), |(min, max), u| {
+                (min.min(u), max.max(u))
+            });
+        let lines = &params_u[1..params_u.len() - 1];
+        if min < max && !lines.iter().any(|line| min < *line && *line <= max) {
+            let u = (min + max) / 2.0;
+            let at = params_u.partition_point(|param| *param < u);
+            params_u.insert(at, u);
+        }
+    }
+}
+
+/// Triangulates the part of the grid kept by the trims.
+/// loops are the trims sampled with sample_trims,
+/// and the grid should already have its crossing params, see add_crossing_params.
+pub fn tessellate_trimmed_grid(
+    params_u: &[f32],
+    params_v: &[f32],
+    trims: &SurfaceTrims,
+    loops: &[Vec<[f32; 2]>],
+) -> TrimmedTessellation {
+    let grid = Grid { params_u, params_v };
+    let loops: Vec<Vec<[f32; 2]>> = loops
+        .iter()
+        .map(|polyline| polyline.iter().map(|point| grid.clamp(*point)).collect())
+        .collect();
+    let mut chains: HashMap<Cell, Vec<Vec<[f32; 2]>>> = HashMap::new();
+    for polyline in loops.iter() {
+        split_loop(&grid, polyline, &mut chains);
+    }
+
+    let count_u = params_u.len();
+    let grid_vertex_count = (count_u * params_v.len()) as u32;
+    let grid_index = |column: usize, row: usize| (column + row * count_u) as u32;
+    let mut extra_vertices: HashMap<[u32; 2], u32> = HashMap::new();
+    let mut res = TrimmedTessellation::default();
+
+    for row in 0..params_v.len() - 1 {
+        for column in 0..count_u - 1 {
+            let corners = grid.corners((column, row));
+            let Some(cell_chains) = chains.get(&(column, row)) else {
+                let center = [
+                    (corners[0][0] + corners[2][0]) / 2.0,
+                    (corners[0][1] + corners[2][1]) / 2.0,
+                ];
+                if trims.contains(&loops, center) {
+                    // Same triangles as index_buffer_generator.wgsl
+                    let x1y1 = grid_index(column, row);
+                    let x2y1 = grid_index(column + 1, row);
+                    let x2y2 = grid_index(column + 1, row + 1);
+                    let x1y2 = grid_index(column, row + 1);
+                    res.indices
+                        .extend_from_slice(&[x1y1, x2y1, x2y2, x1y1, x2y2, x1y2]);
+                }
+                continue;
+            };
+
+            for polygon in cell_polygons(&corners, cell_chains) {
+                let indices: Vec<u32> = polygon
+                    .iter()
+                    .map(|point| {
+                        let corner = corners.iter().position(|corner| corner == point);
+                        if let Some(corner) = corner {
+                            let (i, j) = [(0, 0), (1, 0), (1, 1), (0, 1)][corner];
+                            return grid_index(column + i, row + j);
+                        }
+                        let key = [point[0].to_bits(), point[1].to_bits()];
+                        *extra_vertices.entry(key).or_insert_with(|| {
+                            res.extra_uvs.push(*point);
+                            grid_vertex_count + res.extra_uvs.len() as u32 - 1
+                        })
+                    })
+                    .collect();
+                for triangle in triangulate(&polygon) {
+                    res.indices.extend(triangle.iter().map(|i| indices[*i]));
+                }
+            }
+        }
+    }
+    res
+}
+
+/// Cuts a loop at the grid lines into chains that each run through one cell,
+/// from where the loop enters the cell to where it leaves.
+fn split_loop(grid: &Grid, polyline: &[[f32; 2]], chains: &mut HashMap<Cell, Vec<Vec<[f32; 2]>>>) {
+    let mut add_chain = |cell: Cell, chain: Vec<[f32; 2]>| {
+        // Loops that only touch a cell at a point leave nothing in it
+        if chain.iter().any(|point| *point != chain[0]) {
+            chains.entry(cell).or_default().push(chain);
+        }
+    };
+
+    let n = polyline.len();
+    let mut cell = grid.cell(polyline[0]);
+    let mut chain = vec![polyline[0]];
+    // Runs from the start of the loop to where it first leaves a cell, closes the last chain
+    let mut first_chain: Option<Vec<[f32; 2]>> = None;
+    for i in 0..n {
+        let (p, q) = (polyline[i], polyline[(i + 1) % n]);
+        let target = grid.cell(q);
+        while cell != target {
+            let (crossing, next) = grid.crossing(cell, target, p, q);
+            chain.push(crossing);
+            let finished = std::mem::replace(&mut chain, vec![crossing]);
+            if first_chain.is_none() {
+                first_chain = Some(finished);
+            } else {
+                add_chain(cell, finished);
+            }
+            cell = next;
+        }
+        chain.push(q);
+    }
+
+    // Back at the start, without a crossing the loop would be inside one cell,
+    // which add_crossing_params rules out
+    if let Some(first_chain) = first_chain {
+        chain.extend_from_slice(&first_chain[1..]);
+        add_chain(cell, chain);
+    }
+}
+
+/// Counterclockwise position along the border of the cell, 0 at the bottom left corner and 4 all the way round.
+/// Corners are 0, 1, 2 and 3.
+fn border_position(corners: &[[f32; 2]; 4], [u, v]: [f32; 2]) -> f32 {
+    let [u0, v0] = corners[0];
+    let [u1, v1] = corners[2];
+    if v == v0 && u < u1 {
+        (u - u0) / (u1 - u0)
+    } else if u == u1 && v < v1 {
+        1.0 + (v - v0) / (v1 - v0)
+    } else if v == v1 && u > u0 {
+        2.0 + (u1 - u) / (u1 - u0)
+    } else {
+        3.0 + (v1 - v) / (v1 - v0)
+    }
+}
+
+/// Kept parts of a cell as counterclockwise polygons.
+/// The kept region is left of every chain, so after each chain leaves the cell
+/// the border is followed counterclockwise to the next chain entering it.
+fn cell_polygons(corners: &[[f32; 2]; 4], chains: &[Vec<[f32; 2]>]) -> Vec<Vec<[f32; 2]>> {
+    let entries: Vec<f32> = chains
+        .iter()
+        .map(|chain| border_position(corners, chain[0]))
+        .collect();
+    let mut used = vec![false; chains.len()];
+    let mut res = Vec::new();
+
+    for first in 0..chains.len() {
+        if used[first] {
+            continue;
+        }
+        let mut polygon: Vec<[f32; 2]> = Vec::new();
+        let mut current = first;
+        loop {
+            used[current] = true;
+            polygon.extend_from_slice(&chains[current]);
+            let exit = border_position(corners, *chains[current].last().unwrap());
+            let along = |position: f32| (position - exit).rem_euclid(4.0);
+            let next = (0..chains.len())
+                .min_by(|a, b| along(entries[*a]).total_cmp(&along(entries[*b])))
+                .unwrap();
+            let gap = along(entries[next]);
+            let mut passed: Vec<usize> = (0..4)
+                .filter(|corner| along(*corner as f32) > 0.0 && along(*corner as f32) < gap)
+                .collect();
+            passed.sort_by(|a, b| along(*a as f32).total_cmp(&along(*b as f32)));
+            polygon.extend(passed.iter().map(|corner| corners[*corner]));
+
+            if next == first {
+                res.push(polygon);
+                break;
+            }
+            if used[next] {
+                // Chains that do not link up come from loops crossing each other
+                break;
+            }
+            current = next;
+        }
+    }
+
+    for polygon in res.iter_mut() {
+        remove_spikes(polygon);
+    }
+    // Chains along the border can make the walk retrace them, leaving nothing
+    res.retain(|polygon| polygon.len() >= 3);
+    res
+}
+
+/// Removes repeated points and points where the polygon doubles back on itself
+fn remove_spikes(polygon: &mut Vec<[f32; 2]>) {
+    loop {
+        polygon.dedup();
+        while polygon.len() > 1 && polygon.first() == polygon.last() {
+            polygon.pop();
+        }
+        let n = polygon.len();
+        let spike = (0..n).find(|i| {
+            let (a, b, c) = (polygon[(i + n - 1) % n], polygon[*i], polygon[(i + 1) % n]);
+            let backwards = (b[0] - a[0]) * (c[0] - b[0]) + (b[1] - a[1]) * (c[1] - b[1]) < 0.0;
+            cross(a, b, c) == 0.0 && backwards
+        });
+        match spike {
+            Some(i) if n > 2 => {
+                polygon.remove(i);
+            }
+            _ => return,
+        }
+    }
+}
+
+/// Twice the signed area of the triangle, positive when counterclockwise
+fn cross(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Ear clipping of a counterclockwise polygon, returns indices into it.
+/// Points on straight stretches are kept, the neighbouring cell shares them.
+fn triangulate(polygon: &[[f32; 2]]) -> Vec<[usize; 3]> {
+    let mut remaining: Vec<usize> = (0..polygon.len()).collect();
+    let mut res = Vec::new();
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let corner = |i: usize| {
+            (
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            )
+        };
+        let is_ear = |i: usize| {
+            let (a, b, c) = corner(i);
+            let (pa, pb, pc) = (polygon[a], polygon[b], polygon[c]);
+            cross(pa, pb, pc) > 0.0
+                && remaining.iter().all(|k| {
+                    let p = polygon[*k];
+                    p == pa
+                        || p == pb
+                        || p == pc
+                        || cross(pa, pb, p) < 0.0
+                        || cross(pb, pc, p) < 0.0
+                        || cross(pc, pa, p) < 0.0
+                })
+        };
+        // Degenerate polygons can run out of ears, then flat or reflex points are dropped
+        let Some(i) = (0..n).find(|i| is_ear(*i)).or_else(|| {
+            (0..n).find(|i| {
+                let (a, b, c) = corner(*i);
+                cross(polygon[a], polygon[b], polygon[c]) <= 0.0
+            })
+        }) else {
+            break;
+        };
+        let (a, b, c) = corner(i);
+        if cross(polygon[a], polygon[b], polygon[c]) > 0.0 {
+            res.push([a, b, c]);
+        }
+        remaining.remove(i);
+    }
+    if remaining.len() == 3
+        && cross(
+            polygon[remaining[0]],
+            polygon[remaining[1]],
+            polygon[remaining[2]],
+        ) > 0.0
+    {
+        res.push([remaining[0], remaining[1], remaining[2]]);
+    }
+    res
+}
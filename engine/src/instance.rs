@@ -1,9 +1,18 @@
 use std::sync::Mutex;
 use std::{collections::HashMap, rc::Rc};
 
+use crate::geometry::{curve::Curve, GeometryId};
 use crate::gpu_acceleration_structures::mesh_bbh::mesh_bbh_generator::MeshBBHGenerator;
 use crate::gpu_algorithms::AlgorithmResources;
+use crate::gpu_frustum_tracing::select_lines::LinesSelector;
+use crate::gpu_frustum_tracing::select_mesh::MeshSelector;
+use crate::gpu_mesh_intersection::overlap::MeshOverlapper;
+use crate::gpu_mesh_intersection::section::MeshSectioner;
+use crate::gpu_ray_tracing::intersect_lines::LinesIntersector;
+use crate::gpu_ray_tracing::intersect_mesh::MeshIntersector;
 use crate::gpu_samplers::curve_sampler::CurveSampler;
+use crate::gpu_samplers::offset_sampler::OffsetSampler;
+use crate::gpu_samplers::params::SamplingTolerance;
 use crate::gpu_samplers::surface_sampler::SurfaceSampler;
 use crate::scene::scene_interface::Scene;
 use crate::viewport::viewport_interface::Viewport;
@@ -31,7 +40,16 @@ pub struct InstanceInternal {
     viewports: HashMap<Handle, ViewportInternal>,
     curve_sampler: Rc<CurveSampler>,
     surface_sampler: Rc<SurfaceSampler>,
+    offset_sampler: Rc<OffsetSampler>,
     mesh_bbh_generator: Rc<MeshBBHGenerator>,
+    mesh_intersector: Rc<MeshIntersector>,
+    lines_intersector: Rc<LinesIntersector>,
+    mesh_selector: Rc<MeshSelector>,
+    lines_selector: Rc<LinesSelector>,
+    mesh_overlapper: Rc<MeshOverlapper>,
+    mesh_sectioner: Rc<MeshSectioner>,
+    /// Isocurves drawn in each direction on every surface
+    isocurve_density: u32,
 }
 unsafe impl Send for InstanceInternal {}
 
@@ -41,18 +59,33 @@ impl InstanceInternal {
         let algorithm_resources = Rc::new(AlgorithmResources::new(renderer.clone()));
         let curve_sampler = Rc::new(CurveSampler::new(renderer.clone()));
         let surface_sampler = Rc::new(SurfaceSampler::new(renderer.clone()));
+        let offset_sampler = Rc::new(OffsetSampler::new(renderer.clone()));
         let mesh_bbh_generator = Rc::new(MeshBBHGenerator::new(
             renderer.clone(),
             algorithm_resources.clone(),
         ));
+        let mesh_intersector = Rc::new(MeshIntersector::new(renderer.clone()));
+        let lines_intersector = Rc::new(LinesIntersector::new(renderer.clone()));
+        let mesh_selector = Rc::new(MeshSelector::new(renderer.clone()));
+        let lines_selector = Rc::new(LinesSelector::new(renderer.clone()));
+        let mesh_overlapper = Rc::new(MeshOverlapper::new(renderer.clone()));
+        let mesh_sectioner = Rc::new(MeshSectioner::new(renderer.clone()));
         let instance = InstanceInternal {
             scenes: HashMap::new(),
             viewports: HashMap::new(),
             curve_sampler,
             surface_sampler,
+            offset_sampler,
             renderer,
             algorithm_resources,
             mesh_bbh_generator,
+            mesh_intersector,
+            lines_intersector,
+            mesh_selector,
+            lines_selector,
+            mesh_overlapper,
+            mesh_sectioner,
+            isocurve_density: 0,
         };
 
         let handle = new_handle();
@@ -73,14 +106,16 @@ impl InstanceInternal {
         handle
     }
 
-    pub fn draw_scene_to_viewport(&self, scene: &Scene, viewport: &Viewport) {
+    pub fn draw_scene_to_viewport(&mut self, scene: &Scene, viewport: &Viewport) {
         let viewport = self.viewports.get(&viewport.get_handle()).unwrap();
-        let scene = self.scenes.get(&scene.get_handle()).unwrap();
+        let scene = self.scenes.get_mut(&scene.get_handle()).unwrap();
+        scene.build_missing_isocurves(&self.curve_sampler, self.isocurve_density);
         self.renderer.render(scene, viewport);
     }
 
-    pub fn draw_scene_to_all_viewports(&self, scene: &Scene) {
-        let scene = self.scenes.get(&scene.get_handle()).unwrap();
+    pub fn draw_scene_to_all_viewports(&mut self, scene: &Scene) {
+        let scene = self.scenes.get_mut(&scene.get_handle()).unwrap();
+        scene.build_missing_isocurves(&self.curve_sampler, self.isocurve_density);
         for (_, viewport) in self.viewports.iter() {
             self.renderer.render(scene, viewport);
         }
@@ -89,6 +124,9 @@ impl InstanceInternal {
     pub fn get_viewport_mut(&mut self, viewport_handle: Handle) -> &mut ViewportInternal {
         self.viewports.get_mut(&viewport_handle).unwrap()
     }
+    pub fn get_scene(&self, scene_handle: Handle) -> &SceneInternal {
+        self.scenes.get(&scene_handle).unwrap()
+    }
     pub fn get_scene_mut(&mut self, scene_handle: Handle) -> &mut SceneInternal {
         self.scenes.get_mut(&scene_handle).unwrap()
     }
@@ -101,7 +139,90 @@ impl InstanceInternal {
     pub fn get_surface_sampler(&self) -> Rc<SurfaceSampler> {
         self.surface_sampler.clone()
     }
+    pub fn get_offset_sampler(&self) -> Rc<OffsetSampler> {
+        self.offset_sampler.clone()
+    }
     pub fn get_mesh_bbh_generator(&self) -> Rc<MeshBBHGenerator> {
         self.mesh_bbh_generator.clone()
     }
+    pub fn get_mesh_intersector(&self) -> Rc<MeshIntersector> {
+        self.mesh_intersector.clone()
+    }
+    pub fn get_lines_intersector(&self) -> Rc<LinesIntersector> {
+        self.lines_intersector.clone()
+    }
+    pub fn get_mesh_selector(&self) -> Rc<MeshSelector> {
+        self.mesh_selector.clone()
+    }
+    pub fn get_lines_selector(&self) -> Rc<LinesSelector> {
+        self.lines_selector.clone()
+    }
+    pub fn get_mesh_overlapper(&self) -> Rc<MeshOverlapper> {
+        self.mesh_overlapper.clone()
+    }
+    pub fn get_mesh_sectioner(&self) -> Rc<MeshSectioner> {
+        self.mesh_sectioner.clone()
+    }
+    pub fn get_viewport(&self, viewport_handle: Handle) -> &ViewportInternal {
+        self.viewports.get(&viewport_handle).unwrap()
+    }
+
+    /// Resamples every curve and surface that does not have its own tolerance
+    pub fn set_sampling_tolerance(&mut self, sampling_tolerance: SamplingTolerance) {
+        self.curve_sampler
+            .set_sampling_tolerance(sampling_tolerance);
+        self.surface_sampler
+            .set_sampling_tolerance(sampling_tolerance);
+        for (_, scene) in self.scenes.iter_mut() {
+            for (_, curve) in scene.get_curves_mut().iter_mut() {
+                if curve.get_sampling_tolerance().is_none() {
+                    curve.resample(&self.curve_sampler);
+                }
+            }
+            for (_, surface) in scene.get_surfaces_mut().iter_mut() {
+                if surface.get_sampling_tolerance().is_none() {
+                    surface.resample();
+                }
+            }
+        }
+    }
+
+    /// Isocurves to draw in each direction on every surface, 0 draws none.
+    /// They are rebuilt on the next draw.
+    pub fn set_isocurve_density(&mut self, density: u32) {
+        self.isocurve_density = density;
+    }
+
+    /// Runs edit on a curve, with the sampler it needs to resample.
+    /// None if there is no such curve.
+    pub fn edit_curve<R>(
+        &mut self,
+        scene_handle: Handle,
+        id: GeometryId,
+        edit: impl FnOnce(&mut Curve, &CurveSampler) -> R,
+    ) -> Option<R> {
+        let curve = self
+            .scenes
+            .get_mut(&scene_handle)
+            .unwrap()
+            .get_curves_mut()
+            .get_mut(&id)?;
+        Some(edit(curve, &self.curve_sampler))
+    }
+
+    /// None falls back to the instances tolerance.
+    /// Does nothing for geometry that is not sampled.
+    pub fn set_geometry_sampling_tolerance(
+        &mut self,
+        scene_handle: Handle,
+        id: GeometryId,
+        sampling_tolerance: Option<SamplingTolerance>,
+    ) {
+        let scene = self.scenes.get_mut(&scene_handle).unwrap();
+        if let Some(curve) = scene.get_curves_mut().get_mut(&id) {
+            curve.set_sampling_tolerance(&self.curve_sampler, sampling_tolerance);
+        } else if let Some(surface) = scene.get_surfaces_mut().get_mut(&id) {
+            surface.set_sampling_tolerance(sampling_tolerance);
+        }
+    }
 }
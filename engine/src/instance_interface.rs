@@ -2,6 +2,7 @@ use wasm_bindgen::prelude::*;
 use web_sys::HtmlCanvasElement;
 
 use crate::{
+    gpu_samplers::params::SamplingTolerance,
     instance::{Handle, InstanceInternal},
     scene::scene_interface::Scene,
     utils::get_instance_mut,
@@ -47,4 +48,20 @@ impl WebCadInstance {
     pub fn draw_scene_to_viewport(&self, scene: &Scene, viewport: &Viewport) {
         get_instance_mut!(&self.handle).draw_scene_to_viewport(scene, viewport);
     }
+
+    /// Default tolerance for sampling curves and surfaces.
+    /// Angle is in radians.
+    #[wasm_bindgen]
+    pub fn set_sampling_tolerance(&self, chordal_deviation: f32, angle: f32) {
+        get_instance_mut!(&self.handle).set_sampling_tolerance(SamplingTolerance {
+            chordal_deviation,
+            angle,
+        });
+    }
+
+    /// Isocurves drawn in each direction on every surface, 0 for none
+    #[wasm_bindgen]
+    pub fn set_isocurve_density(&self, density: u32) {
+        get_instance_mut!(&self.handle).set_isocurve_density(density);
+    }
 }
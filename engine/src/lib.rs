@@ -2,6 +2,7 @@ pub mod geometry;
 pub mod gpu_acceleration_structures;
 pub mod gpu_algorithms;
 pub mod gpu_frustum_tracing;
+pub mod gpu_mesh_intersection;
 pub mod gpu_ray_tracing;
 pub mod gpu_samplers;
 pub mod instance;
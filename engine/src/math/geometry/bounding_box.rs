@@ -1,3 +1,8 @@
+use crate::math::linear_algebra::vec3::Vec3;
+
+use super::ray::Ray;
+
+#[derive(Debug, Copy, Clone)]
 pub struct BoundingBox {
     x_min: f32,
     x_max: f32,
@@ -7,4 +12,119 @@ pub struct BoundingBox {
     z_max: f32,
 }
 
-impl BoundingBox {}
+impl BoundingBox {
+    pub fn new(min_corner: &Vec3, max_corner: &Vec3) -> Self {
+        Self {
+            x_min: min_corner.x,
+            x_max: max_corner.x,
+            y_min: min_corner.y,
+            y_max: max_corner.y,
+            z_min: min_corner.z,
+            z_max: max_corner.z,
+        }
+    }
+
+    /// Smallest box containing all of points, None if there are none
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Option<Self> {
+        points.into_iter().fold(None, |res: Option<Self>, point| {
+            Some(match res {
+                None => Self::new(&point, &point),
+                Some(bb) => Self {
+                    x_min: bb.x_min.min(point.x),
+                    x_max: bb.x_max.max(point.x),
+                    y_min: bb.y_min.min(point.y),
+                    y_max: bb.y_max.max(point.y),
+                    z_min: bb.z_min.min(point.z),
+                    z_max: bb.z_max.max(point.z),
+                },
+            })
+        })
+    }
+
+    /// Grown by margin on every side
+    pub fn expanded(&self, margin: f32) -> Self {
+        Self {
+            x_min: self.x_min - margin,
+            x_max: self.x_max + margin,
+            y_min: self.y_min - margin,
+            y_max: self.y_max + margin,
+            z_min: self.z_min - margin,
+            z_max: self.z_max + margin,
+        }
+    }
+
+    /// Touching boxes intersect
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.x_min <= other.x_max
+            && other.x_min <= self.x_max
+            && self.y_min <= other.y_max
+            && other.y_min <= self.y_max
+            && self.z_min <= other.z_max
+            && other.z_min <= self.z_max
+    }
+
+    pub fn diagonal(&self) -> f32 {
+        Vec3::distance(&self.get_min_corner(), &self.get_max_corner())
+    }
+
+    pub fn get_min_corner(&self) -> Vec3 {
+        Vec3::new(self.x_min, self.y_min, self.z_min)
+    }
+    pub fn get_max_corner(&self) -> Vec3 {
+        Vec3::new(self.x_max, self.y_max, self.z_max)
+    }
+
+    pub fn get_corners(&self) -> [Vec3; 8] {
+        [
+            Vec3::new(self.x_min, self.y_min, self.z_min),
+            Vec3::new(self.x_max, self.y_min, self.z_min),
+            Vec3::new(self.x_max, self.y_max, self.z_min),
+            Vec3::new(self.x_min, self.y_max, self.z_min),
+            Vec3::new(self.x_min, self.y_min, self.z_max),
+            Vec3::new(self.x_max, self.y_min, self.z_max),
+            Vec3::new(self.x_max, self.y_max, self.z_max),
+            Vec3::new(self.x_min, self.y_max, self.z_max),
+        ]
+    }
+
+    /// Pairs of indices into get_corners
+    pub const EDGES: [(usize, usize); 12] = [
+        (0, 1),
+        (1, 2),
+        (2, 3),
+        (3, 0),
+        (4, 5),
+        (5, 6),
+        (6, 7),
+        (7, 4),
+        (0, 4),
+        (1, 5),
+        (2, 6),
+        (3, 7),
+    ];
+
+    /// Slab test, only counts hits in front of the ray origin
+    pub fn intersects_ray(&self, ray: &Ray) -> bool {
+        let origin = ray.get_origin();
+        let direction = ray.get_direction();
+        let mut t_min: f32 = 0.0;
+        let mut t_max = f32::MAX;
+        for (o, d, min, max) in [
+            (origin.x, direction.x, self.x_min, self.x_max),
+            (origin.y, direction.y, self.y_min, self.y_max),
+            (origin.z, direction.z, self.z_min, self.z_max),
+        ] {
+            if d == 0.0 {
+                if o < min || o > max {
+                    return false;
+                }
+                continue;
+            }
+            let t0 = (min - o) / d;
+            let t1 = (max - o) / d;
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+        }
+        t_min <= t_max
+    }
+}
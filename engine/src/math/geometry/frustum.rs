@@ -2,6 +2,8 @@ use crate::math::linear_algebra::{mat4::Mat4, vec3::Vec3};
 
 use super::{bounding_box::BoundingBox, ray::Ray};
 
+/// Infinite pyramid spanned by four rays from a shared origin.
+/// Side normals point inward.
 #[derive(Debug, Copy, Clone)]
 pub struct Frustum {
     origin: Vec3,
@@ -17,54 +19,85 @@ pub struct Frustum {
 
 impl Frustum {
     pub fn new(top_left: &Ray, top_right: &Ray, bottom_right: &Ray, bottom_left: &Ray) -> Frustum {
-        let up = Vec3::cross(top_left.get_direction(), top_right.get_direction()).to_normalized();
-        let right =
-            Vec3::cross(top_right.get_direction(), bottom_right.get_direction()).to_normalized();
-        let down =
-            Vec3::cross(bottom_right.get_direction(), bottom_left.get_direction()).to_normalized();
-        let left =
-            Vec3::cross(bottom_left.get_direction(), top_left.get_direction()).to_normalized();
-        Frustum {
+        let mut frustum = Frustum {
             origin: *top_left.get_origin(),
-            up,
-            right,
-            down,
-            left,
+            up: Vec3::default(),
+            right: Vec3::default(),
+            down: Vec3::default(),
+            left: Vec3::default(),
             top_left: *top_left.get_direction(),
             top_right: *top_right.get_direction(),
             bottom_left: *bottom_left.get_direction(),
             bottom_right: *bottom_right.get_direction(),
-        }
+        };
+        frustum.update_normals();
+        frustum
     }
+
     pub fn contains_point(&self, point: &Vec3) -> bool {
         let v = Vec3::subtract(point, &self.origin);
-        Vec3::dot(&self.up, &v) > 0.0
-            || Vec3::dot(&self.right, &v) > 0.0
-            || Vec3::dot(&self.down, &v) > 0.0
-            || Vec3::dot(&self.left, &v) > 0.0
+        self.get_normals()
+            .iter()
+            .all(|normal| Vec3::dot(normal, &v) >= 0.0)
     }
 
     pub fn contains_line_fully(&self, start: &Vec3, end: &Vec3) -> bool {
         self.contains_point(start) && self.contains_point(end)
     }
 
+    /// Clips the segment against each side.
     pub fn contains_line_partially(&self, start: &Vec3, end: &Vec3) -> bool {
-        let mut dir = Vec3::subtract(end, start);
-        let len = dir.len();
-        dir.scale(1.0 / len);
+        let v_start = Vec3::subtract(start, &self.origin);
+        let v_end = Vec3::subtract(end, &self.origin);
 
-        let ray = Ray::new(*start, dir);
+        let mut t_enter: f32 = 0.0;
+        let mut t_exit: f32 = 1.0;
 
-        todo!();
+        for normal in self.get_normals().iter() {
+            let d_start = Vec3::dot(normal, &v_start);
+            let d_end = Vec3::dot(normal, &v_end);
+            if d_start < 0.0 && d_end < 0.0 {
+                return false;
+            }
+            if d_start < 0.0 {
+                t_enter = t_enter.max(d_start / (d_start - d_end));
+            } else if d_end < 0.0 {
+                t_exit = t_exit.min(d_start / (d_start - d_end));
+            }
+        }
 
-        true
+        t_enter <= t_exit
     }
 
-    pub fn contains_bounding_box_fully(bb: BoundingBox) -> bool {
-        todo!();
+    pub fn contains_bounding_box_fully(&self, bb: &BoundingBox) -> bool {
+        bb.get_corners()
+            .iter()
+            .all(|corner| self.contains_point(corner))
     }
-    pub fn contains_bounding_box_partially(bb: BoundingBox) -> bool {
-        todo!();
+
+    pub fn contains_bounding_box_partially(&self, bb: &BoundingBox) -> bool {
+        let corners = bb.get_corners();
+
+        // Cheap rejection, box is entirely outside of one side
+        for normal in self.get_normals().iter() {
+            if corners
+                .iter()
+                .all(|corner| Vec3::dot(normal, &Vec3::subtract(corner, &self.origin)) < 0.0)
+            {
+                return false;
+            }
+        }
+
+        // Two convex shapes overlap if an edge of one crosses the other
+        if BoundingBox::EDGES
+            .iter()
+            .any(|(a, b)| self.contains_line_partially(&corners[*a], &corners[*b]))
+        {
+            return true;
+        }
+        self.get_edges()
+            .iter()
+            .any(|edge| bb.intersects_ray(&Ray::new(self.origin, *edge)))
     }
 
     pub fn transform(&mut self, t: Mat4) -> &mut Self {
@@ -73,7 +106,30 @@ impl Frustum {
         self.top_right = t.transform_vector(&self.top_right);
         self.bottom_right = t.transform_vector(&self.bottom_right);
         self.bottom_left = t.transform_vector(&self.bottom_left);
+        self.update_normals();
+        self
+    }
+
+    pub fn get_origin(&self) -> &Vec3 {
+        &self.origin
+    }
+
+    /// Up, right, down, left
+    pub fn get_normals(&self) -> [Vec3; 4] {
+        [self.up, self.right, self.down, self.left]
+    }
 
+    /// Top left, top right, bottom right, bottom left
+    pub fn get_edges(&self) -> [Vec3; 4] {
+        [
+            self.top_left,
+            self.top_right,
+            self.bottom_right,
+            self.bottom_left,
+        ]
+    }
+
+    fn update_normals(&mut self) {
         let forward = Vec3::add(
             &self.top_left,
             &Vec3::add(
@@ -96,7 +152,5 @@ impl Frustum {
             self.left.scale(-1.0);
             self.right.scale(-1.0);
         }
-
-        self
     }
 }
@@ -16,4 +16,9 @@ impl Plane {
     pub fn get_normal(&self) -> &Vec3 {
         &self.normal
     }
+
+    /// Positive on the side the normal points to, the normal does not need to be unit length
+    pub fn signed_distance(&self, point: &Vec3) -> f32 {
+        Vec3::dot(&Vec3::subtract(point, &self.origin), &self.normal) / self.normal.len()
+    }
 }
@@ -72,13 +72,26 @@ impl Vec3 {
             w: n,
         }
     }
+    /// Radians, between 0 and pi
     pub fn angle_between(a: &Vec3, b: &Vec3) -> f32 {
-        f32::cos(Vec3::dot(&a.to_normalized(), &b.to_normalized()))
+        f32::acos(Vec3::dot(&a.to_normalized(), &b.to_normalized()).clamp(-1.0, 1.0))
     }
 
     pub fn distance(a: &Vec3, b: &Vec3) -> f32 {
-        let delta = Vec3::subtract(a, b);
-        Vec3::dot(&delta, &delta)
+        Vec3::subtract(a, b).len()
+    }
+
+    /// Some unit vector perpendicular to v
+    pub fn any_perpendicular(v: &Vec3) -> Vec3 {
+        // Cross with the axis v is least aligned with
+        let axis = if v.x.abs() <= v.y.abs() && v.x.abs() <= v.z.abs() {
+            Vec3::new(1.0, 0.0, 0.0)
+        } else if v.y.abs() <= v.z.abs() {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(0.0, 0.0, 1.0)
+        };
+        Vec3::cross(v, &axis).to_normalized()
     }
 
     pub fn rotate(v: &Vec3, center: &Vec3, axis: &Vec3, theta: f32) -> Vec3 {
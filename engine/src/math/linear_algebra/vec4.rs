@@ -29,6 +29,43 @@ impl Vec4 {
         }
     }
 
+    pub fn add(a: &Vec4, b: &Vec4) -> Vec4 {
+        Vec4 {
+            x: a.x + b.x,
+            y: a.y + b.y,
+            z: a.z + b.z,
+            w: a.w + b.w,
+        }
+    }
+
+    pub fn subtract(a: &Vec4, b: &Vec4) -> Vec4 {
+        Vec4 {
+            x: a.x - b.x,
+            y: a.y - b.y,
+            z: a.z - b.z,
+            w: a.w - b.w,
+        }
+    }
+
+    pub fn to_scaled(v: &Vec4, s: f32) -> Vec4 {
+        Vec4 {
+            x: v.x * s,
+            y: v.y * s,
+            z: v.z * s,
+            w: v.w * s,
+        }
+    }
+
+    /// a at t = 0, b at t = 1
+    pub fn lerp(a: &Vec4, b: &Vec4, t: f32) -> Vec4 {
+        Vec4 {
+            x: a.x + (b.x - a.x) * t,
+            y: a.y + (b.y - a.y) * t,
+            z: a.z + (b.z - a.z) * t,
+            w: a.w + (b.w - a.w) * t,
+        }
+    }
+
     pub fn to_vec3_safe(self) -> Vec3 {
         if self.w == 0.0 {
             log::warn!("sketch");
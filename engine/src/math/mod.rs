@@ -1,4 +1,5 @@
 pub mod geometry;
 pub mod linear_algebra;
+pub mod nurbs;
 pub mod utils;
 
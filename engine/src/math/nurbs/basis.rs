@@ -0,0 +1,129 @@
+/// Index of the knot span containing t, A2.1.
+/// Parameters outside of the domain are clamped to the first or last span.
+pub fn find_span(degree: u32, knots: &[f32], t: f32) -> usize {
+    let degree = degree as usize;
+    let n = knots.len() - degree - 2;
+    if t >= knots[n + 1] {
+        return n;
+    }
+    if t <= knots[degree] {
+        return degree;
+    }
+    let mut low = degree;
+    let mut high = n + 1;
+    let mut mid = (low + high) / 2;
+    while t < knots[mid] || t >= knots[mid + 1] {
+        if t < knots[mid] {
+            high = mid;
+        } else {
+            low = mid;
+        }
+        mid = (low + high) / 2;
+    }
+    mid
+}
+
+/// Non zero basis functions at t, A2.2.
+/// Result i belongs to control span - degree + i.
+pub fn basis_functions(span: usize, t: f32, degree: u32, knots: &[f32]) -> Vec<f32> {
+    let degree = degree as usize;
+    let mut res = vec![0.0; degree + 1];
+    let mut left = vec![0.0; degree + 1];
+    let mut right = vec![0.0; degree + 1];
+    res[0] = 1.0;
+    for j in 1..=degree {
+        left[j] = t - knots[span + 1 - j];
+        right[j] = knots[span + j] - t;
+        let mut saved = 0.0;
+        for r in 0..j {
+            let temp = res[r] / (right[r + 1] + left[j - r]);
+            res[r] = saved + right[r + 1] * temp;
+            saved = left[j - r] * temp;
+        }
+        res[j] = saved;
+    }
+    res
+}
+
+/// Non zero basis functions and their derivatives at t, A2.3.
+/// Result k, i is the kth derivative of the basis function of control span - degree + i.
+/// Derivatives above the degree are 0.
+pub fn basis_function_derivatives(
+    span: usize,
+    t: f32,
+    degree: u32,
+    knots: &[f32],
+    derivative_count: usize,
+) -> Vec<Vec<f32>> {
+    let p = degree as usize;
+    let mut ndu = vec![vec![0.0; p + 1]; p + 1];
+    let mut left = vec![0.0; p + 1];
+    let mut right = vec![0.0; p + 1];
+    ndu[0][0] = 1.0;
+    for j in 1..=p {
+        left[j] = t - knots[span + 1 - j];
+        right[j] = knots[span + j] - t;
+        let mut saved = 0.0;
+        for r in 0..j {
+            // Lower triangle holds knot differences
+            ndu[j][r] = right[r + 1] + left[j - r];
+            let temp = ndu[r][j - 1] / ndu[j][r];
+            // Upper triangle holds basis functions
+            ndu[r][j] = saved + right[r + 1] * temp;
+            saved = left[j - r] * temp;
+        }
+        ndu[j][j] = saved;
+    }
+
+    let mut ders = vec![vec![0.0; p + 1]; derivative_count + 1];
+    for j in 0..=p {
+        ders[0][j] = ndu[j][p];
+    }
+
+    let mut a = vec![vec![0.0; p + 1]; 2];
+    for r in 0..=p {
+        let mut s1 = 0;
+        let mut s2 = 1;
+        a[0][0] = 1.0;
+        for k in 1..=derivative_count.min(p) {
+            let mut d = 0.0;
+            let rk = r as i32 - k as i32;
+            let pk = p - k;
+            if r >= k {
+                a[s2][0] = a[s1][0] / ndu[pk + 1][rk as usize];
+                d = a[s2][0] * ndu[rk as usize][pk];
+            }
+            let j1 = if rk >= -1 { 1 } else { (-rk) as usize };
+            let j2 = if r as i32 - 1 <= pk as i32 {
+                k - 1
+            } else {
+                p - r
+            };
+            for j in j1..=j2 {
+                let idx = (rk + j as i32) as usize;
+                a[s2][j] = (a[s1][j] - a[s1][j - 1]) / ndu[pk + 1][idx];
+                d += a[s2][j] * ndu[idx][pk];
+            }
+            if r <= pk {
+                a[s2][k] = -a[s1][k - 1] / ndu[pk + 1][r];
+                d += a[s2][k] * ndu[r][pk];
+            }
+            ders[k][r] = d;
+            std::mem::swap(&mut s1, &mut s2);
+        }
+    }
+
+    let mut factor = p as f32;
+    for (k, row) in ders
+        .iter_mut()
+        .enumerate()
+        .take(derivative_count.min(p) + 1)
+        .skip(1)
+    {
+        for der in row.iter_mut() {
+            *der *= factor;
+        }
+        factor *= (p - k) as f32;
+    }
+    ders
+}
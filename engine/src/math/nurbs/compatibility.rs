@@ -0,0 +1,89 @@
+//! Bringing curves to a common degree and knot vector, needed before they can be rows of one surface.
+
+use crate::math::linear_algebra::vec4::Vec4;
+
+use super::{curve::NurbsCurve, knot_insertion::knot_multiplicity};
+
+impl NurbsCurve {
+    /// Linearly maps the knots so the domain becomes start to end, the shape does not change
+    pub fn reparameterize(&mut self, start: f32, end: f32) {
+        let (old_start, old_end) = self.domain();
+        let scale = (end - start) / (old_end - old_start);
+        for knot in self.knots.iter_mut() {
+            *knot = start + (*knot - old_start) * scale;
+        }
+        // Keep the ends exact so knots can be compared between curves
+        let p = self.degree as usize;
+        let len = self.knots.len();
+        self.knots[..=p].fill(start);
+        self.knots[len - p - 1..].fill(end);
+    }
+}
+
+/// Gives every curve the highest degree among them, a domain of 0 to 1 and the union of their knots.
+/// Each curve keeps its shape.
+pub fn make_compatible(curves: &mut [NurbsCurve]) {
+    let Some(degree) = curves.iter().map(|curve| curve.degree).max() else {
+        return;
+    };
+    for curve in curves.iter_mut() {
+        curve.reparameterize(0.0, 1.0);
+        curve.elevate_degree(degree - curve.degree);
+    }
+
+    // Every interior knot at the highest multiplicity any curve has it
+    let p = degree as usize;
+    let mut merged: Vec<f32> = Vec::new();
+    for curve in curves.iter() {
+        let interior = &curve.knots[p + 1..curve.knots.len() - p - 1];
+        for knot in interior {
+            let needed = knot_multiplicity(interior, *knot);
+            if knot_multiplicity(&merged, *knot) < needed {
+                merged.push(*knot);
+            }
+        }
+    }
+    merged.sort_by(f32::total_cmp);
+
+    for curve in curves.iter_mut() {
+        let interior = &curve.knots[p + 1..curve.knots.len() - p - 1];
+        let mut missing = Vec::new();
+        for (i, knot) in merged.iter().enumerate() {
+            // Occurrences of knot in merged up to and including i
+            let nth = merged[..=i].iter().filter(|k| **k == *knot).count();
+            if knot_multiplicity(interior, *knot) < nth {
+                missing.push(*knot);
+            }
+        }
+        curve.refine_knots(&missing);
+    }
+}
+
+/// One curve running through each curve in turn, with a corner where they meet.
+/// Each curve must start where the one before it ends. The domain is 0 to the number of curves.
+pub fn join_curves(curves: &[NurbsCurve]) -> Option<NurbsCurve> {
+    let degree = curves.iter().map(|curve| curve.degree).max()?;
+    let p = degree as usize;
+    let mut weighted_controls: Vec<Vec4> = Vec::new();
+    let mut knots = vec![0.0; p + 1];
+    for (i, curve) in curves.iter().enumerate() {
+        let mut curve = curve.clone();
+        curve.elevate_degree(degree - curve.degree);
+        curve.reparameterize(i as f32, i as f32 + 1.0);
+        // Scaling every weight leaves the curve alone, this makes the shared control agree
+        if let Some(last) = weighted_controls.last() {
+            let scale = last.w / curve.weighted_controls[0].w;
+            for control in curve.weighted_controls.iter_mut() {
+                *control = Vec4::to_scaled(control, scale);
+            }
+        }
+        let len = curve.knots.len();
+        knots.extend_from_slice(&curve.knots[p + 1..len - p - 1]);
+        // The end knot at full multiplicity but one, the shared control stands in for the last
+        knots.extend(std::iter::repeat_n(i as f32 + 1.0, p));
+        let skip = if i == 0 { 0 } else { 1 };
+        weighted_controls.extend_from_slice(&curve.weighted_controls[skip..]);
+    }
+    knots.push(curves.len() as f32);
+    Some(NurbsCurve::new(degree, weighted_controls, knots))
+}
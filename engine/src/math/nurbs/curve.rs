@@ -0,0 +1,121 @@
+use crate::math::linear_algebra::{mat4::Mat4, vec3::Vec3, vec4::Vec4};
+
+use super::{
+    basis::{basis_function_derivatives, basis_functions, find_span},
+    binomial,
+};
+
+/// Rational b-spline curve, same representation as geometry::curve::Curve
+#[derive(Debug, Clone, PartialEq)]
+pub struct NurbsCurve {
+    pub degree: u32,
+    pub weighted_controls: Vec<Vec4>,
+    pub knots: Vec<f32>,
+}
+
+impl NurbsCurve {
+    pub fn new(degree: u32, weighted_controls: Vec<Vec4>, knots: Vec<f32>) -> Self {
+        debug_assert_eq!(
+            knots.len(),
+            weighted_controls.len() + degree as usize + 1,
+            "knot count must be control count + degree + 1"
+        );
+        Self {
+            degree,
+            weighted_controls,
+            knots,
+        }
+    }
+
+    /// Applies m to every control, exact for affine transforms
+    pub fn transform(&mut self, m: &Mat4) {
+        for control in self.weighted_controls.iter_mut() {
+            *control = m.transform(control);
+        }
+    }
+
+    /// First and last parameter
+    pub fn domain(&self) -> (f32, f32) {
+        (
+            self.knots[self.degree as usize],
+            self.knots[self.knots.len() - self.degree as usize - 1],
+        )
+    }
+
+    /// Weighted point, A4.1
+    pub fn homogeneous_point(&self, t: f32) -> Vec4 {
+        let span = find_span(self.degree, &self.knots, t);
+        let basis = basis_functions(span, t, self.degree, &self.knots);
+        let first = span - self.degree as usize;
+        let mut res = Vec4::default();
+        for (i, b) in basis.iter().enumerate() {
+            let control = &self.weighted_controls[first + i];
+            res.x += control.x * b;
+            res.y += control.y * b;
+            res.z += control.z * b;
+            res.w += control.w * b;
+        }
+        res
+    }
+
+    pub fn point(&self, t: f32) -> Vec3 {
+        self.homogeneous_point(t).to_vec3_safe()
+    }
+
+    /// Derivatives of the weighted curve, result k is the kth derivative
+    pub fn homogeneous_derivatives(&self, t: f32, derivative_count: usize) -> Vec<Vec4> {
+        let span = find_span(self.degree, &self.knots, t);
+        let ders = basis_function_derivatives(span, t, self.degree, &self.knots, derivative_count);
+        let first = span - self.degree as usize;
+        ders.iter()
+            .map(|basis| {
+                let mut res = Vec4::default();
+                for (i, b) in basis.iter().enumerate() {
+                    let control = &self.weighted_controls[first + i];
+                    res.x += control.x * b;
+                    res.y += control.y * b;
+                    res.z += control.z * b;
+                    res.w += control.w * b;
+                }
+                res
+            })
+            .collect()
+    }
+
+    /// Point and derivatives, result k is the kth derivative, A4.2
+    pub fn derivatives(&self, t: f32, derivative_count: usize) -> Vec<Vec3> {
+        let homogeneous = self.homogeneous_derivatives(t, derivative_count);
+        let mut res: Vec<Vec3> = Vec::with_capacity(derivative_count + 1);
+        for k in 0..=derivative_count {
+            let mut v = Vec3::new(homogeneous[k].x, homogeneous[k].y, homogeneous[k].z);
+            for i in 1..=k {
+                v = Vec3::subtract(
+                    &v,
+                    &Vec3::to_scaled(&res[k - i], binomial(k, i) * homogeneous[i].w),
+                );
+            }
+            res.push(Vec3::to_scaled(&v, 1.0 / homogeneous[0].w));
+        }
+        res
+    }
+
+    /// Unit tangent
+    pub fn tangent(&self, t: f32) -> Vec3 {
+        self.derivatives(t, 1)[1].to_normalized()
+    }
+
+    /// Curvature vector, points toward the center of curvature with length 1 / radius
+    pub fn curvature(&self, t: f32) -> Vec3 {
+        let ders = self.derivatives(t, 2);
+        let d1 = &ders[1];
+        let d2 = &ders[2];
+        let speed_squared = Vec3::dot(d1, d1);
+        if speed_squared == 0.0 {
+            return Vec3::default();
+        }
+        // Component of the second derivative normal to the tangent
+        let normal_part =
+            Vec3::subtract(d2, &Vec3::to_scaled(d1, Vec3::dot(d1, d2) / speed_squared));
+        Vec3::to_scaled(&normal_part, 1.0 / speed_squared)
+    }
+}
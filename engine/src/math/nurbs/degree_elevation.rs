@@ -0,0 +1,197 @@
+//! Degree elevation, the shape does not change.
+
+use crate::math::linear_algebra::vec4::Vec4;
+
+use super::{binomial, curve::NurbsCurve, surface::NurbsSurface};
+
+impl NurbsCurve {
+    /// Raises the degree by times, A5.9.
+    /// Interior knots keep their continuity, so their multiplicity goes up by times.
+    pub fn elevate_degree(&mut self, times: u32) {
+        if times == 0 {
+            return;
+        }
+        let p = self.degree as usize;
+        let t = times as usize;
+        let knots = &self.knots;
+        let controls = &self.weighted_controls;
+        let m = knots.len() - 1;
+        let ph = p + t;
+        let ph2 = ph / 2;
+
+        // Coefficients for elevating a single bezier segment
+        let mut bezalfs = vec![vec![0.0; p + 1]; ph + 1];
+        bezalfs[0][0] = 1.0;
+        bezalfs[ph][p] = 1.0;
+        for (i, row) in bezalfs.iter_mut().enumerate().take(ph2 + 1).skip(1) {
+            let inv = 1.0 / binomial(ph, i);
+            for (j, alf) in row
+                .iter_mut()
+                .enumerate()
+                .take(p.min(i) + 1)
+                .skip(i.saturating_sub(t))
+            {
+                *alf = inv * binomial(p, j) * binomial(t, i - j);
+            }
+        }
+        for i in ph2 + 1..ph {
+            for j in i.saturating_sub(t)..=p.min(i) {
+                bezalfs[i][j] = bezalfs[ph - i][p - j];
+            }
+        }
+
+        let segment_count = knots.windows(2).filter(|w| w[0] < w[1]).count();
+        let mut new_knots = vec![0.0; knots.len() + (segment_count + 1) * t];
+        let mut new_controls = vec![Vec4::default(); controls.len() + segment_count * t];
+
+        let mut bpts: Vec<Vec4> = controls[..=p].to_vec();
+        let mut next_bpts = vec![Vec4::default(); p.saturating_sub(1)];
+        let mut ebpts = vec![Vec4::default(); ph + 1];
+        let mut alfs = vec![0.0; p.saturating_sub(1)];
+
+        let mut mh = ph;
+        let mut kind = ph + 1;
+        let mut r: isize = -1;
+        let mut a = p;
+        let mut b = p + 1;
+        let mut cind = 1;
+        let mut ua = knots[0];
+        new_controls[0] = controls[0];
+        for knot in new_knots.iter_mut().take(ph + 1) {
+            *knot = ua;
+        }
+
+        while b < m {
+            let i = b;
+            while b < m && knots[b] == knots[b + 1] {
+                b += 1;
+            }
+            let mul = b - i + 1;
+            mh += mul + t;
+            let ub = knots[b];
+            let oldr = r;
+            r = p as isize - mul as isize;
+
+            let lbz = if oldr > 0 {
+                (oldr as usize + 1).div_ceil(2)
+            } else {
+                1
+            };
+            let rbz = if r > 0 {
+                ph - (r as usize).div_ceil(2)
+            } else {
+                ph
+            };
+
+            // Insert ub r times to get a bezier segment
+            if r > 0 {
+                let r = r as usize;
+                let numer = ub - ua;
+                for k in (mul + 1..=p).rev() {
+                    alfs[k - mul - 1] = numer / (knots[a + k] - ua);
+                }
+                for j in 1..=r {
+                    let save = r - j;
+                    let s = mul + j;
+                    for k in (s..=p).rev() {
+                        bpts[k] = Vec4::lerp(&bpts[k - 1], &bpts[k], alfs[k - s]);
+                    }
+                    next_bpts[save] = bpts[p];
+                }
+            }
+
+            for i in lbz..=ph {
+                ebpts[i] = Vec4::default();
+                for j in i.saturating_sub(t)..=p.min(i) {
+                    ebpts[i] = Vec4::add(&ebpts[i], &Vec4::to_scaled(&bpts[j], bezalfs[i][j]));
+                }
+            }
+
+            // Remove ua oldr times, it was inserted to get the previous segment
+            if oldr > 1 {
+                let oldr = oldr as usize;
+                let mut first = kind - 2;
+                let den = ub - ua;
+                let bet = (ub - new_knots[kind - 1]) / den;
+                for (tr, last) in (1..oldr).zip(kind..) {
+                    let mut i = first;
+                    let mut j = last;
+                    let mut kj = j as isize - kind as isize + 1;
+                    while j as isize - i as isize > tr as isize {
+                        if i < cind {
+                            let alf = (ub - new_knots[i]) / (ua - new_knots[i]);
+                            new_controls[i] =
+                                Vec4::lerp(&new_controls[i - 1], &new_controls[i], alf);
+                        }
+                        if j >= lbz {
+                            let kj = kj as usize;
+                            let gam = if j - tr <= kind - ph + oldr {
+                                (ub - new_knots[j - tr]) / den
+                            } else {
+                                bet
+                            };
+                            ebpts[kj] = Vec4::lerp(&ebpts[kj + 1], &ebpts[kj], gam);
+                        }
+                        i += 1;
+                        j -= 1;
+                        kj -= 1;
+                    }
+                    first -= 1;
+                }
+            }
+
+            if a != p {
+                for _ in 0..ph - oldr as usize {
+                    new_knots[kind] = ua;
+                    kind += 1;
+                }
+            }
+            let count = rbz + 1 - lbz;
+            new_controls[cind..cind + count].copy_from_slice(&ebpts[lbz..=rbz]);
+            cind += count;
+
+            if b < m {
+                let r = r.max(0) as usize;
+                bpts[..r].copy_from_slice(&next_bpts[..r]);
+                for j in r..=p {
+                    bpts[j] = controls[b - p + j];
+                }
+                a = b;
+                b += 1;
+                ua = ub;
+            } else {
+                for i in 0..=ph {
+                    new_knots[kind + i] = ub;
+                }
+            }
+        }
+
+        let control_count = mh - ph;
+        new_knots.truncate(mh + 1);
+        new_controls.truncate(control_count);
+
+        self.degree += times;
+        self.knots = new_knots;
+        self.weighted_controls = new_controls;
+    }
+}
+
+impl NurbsSurface {
+    /// Raises the degree in u by times
+    pub fn elevate_degree_u(&mut self, times: u32) {
+        let mut curves = self.curves_u();
+        for curve in curves.iter_mut() {
+            curve.elevate_degree(times);
+        }
+        *self = NurbsSurface::from_curves_u(curves, self.degree_v, self.knots_v.clone());
+    }
+
+    /// Raises the degree in v by times
+    pub fn elevate_degree_v(&mut self, times: u32) {
+        let mut curves = self.curves_v();
+        for curve in curves.iter_mut() {
+            curve.elevate_degree(times);
+        }
+        *self = NurbsSurface::from_curves_v(curves, self.degree_u, self.knots_u.clone());
+    }
+}
@@ -0,0 +1,211 @@
+//! Least squares approximation of point data, section 9.4.
+
+use crate::math::linear_algebra::{vec3::Vec3, vec4::Vec4};
+
+use super::{
+    basis::{basis_functions, find_span},
+    curve::NurbsCurve,
+    interpolation::{create_params, point_distances, Parameterization},
+    solve_linear_system,
+    surface::NurbsSurface,
+};
+
+/// How far the points are from the fitted geometry, measured at the parameter each point was fitted to
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Deviation {
+    pub max: f32,
+    pub average: f32,
+}
+
+impl Deviation {
+    fn from_distances(distances: impl Iterator<Item = f32>) -> Self {
+        let mut res = Deviation::default();
+        let mut count = 0;
+        for distance in distances {
+            res.max = res.max.max(distance);
+            res.average += distance;
+            count += 1;
+        }
+        res.average /= count.max(1) as f32;
+        res
+    }
+}
+
+/// Knots that put at least one parameter in every span, eq 9.69
+pub fn fitting_knots(params: &[f32], degree: u32, control_count: usize) -> Vec<f32> {
+    let p = degree as usize;
+    let m = params.len() - 1;
+    let n = control_count - 1;
+    let d = (m + 1) as f32 / (n - p + 1) as f32;
+    let mut knots = vec![0.0; p + 1];
+    for j in 1..=n - p {
+        let i = (j as f32 * d) as usize;
+        let alpha = j as f32 * d - i as f32;
+        knots.push((1.0 - alpha) * params[i - 1] + alpha * params[i]);
+    }
+    knots.extend(std::iter::repeat_n(1.0, p + 1));
+    knots
+}
+
+/// Controls for a curve through the first and last point that is closest to the rest in the
+/// least squares sense, A9.7. None if the system is singular.
+pub fn fit_controls(
+    points: &[Vec4],
+    params: &[f32],
+    degree: u32,
+    knots: &[f32],
+    control_count: usize,
+) -> Option<Vec<Vec4>> {
+    let m = points.len() - 1;
+    let n = control_count - 1;
+    let p = degree as usize;
+
+    // Basis of every control at every parameter
+    let basis_rows: Vec<Vec<f32>> = params
+        .iter()
+        .map(|t| {
+            let mut row = vec![0.0; n + 1];
+            let span = find_span(degree, knots, *t);
+            for (i, b) in basis_functions(span, *t, degree, knots).iter().enumerate() {
+                row[span - p + i] = *b;
+            }
+            row
+        })
+        .collect();
+
+    let mut controls = vec![Vec4::default(); n + 1];
+    controls[0] = points[0];
+    controls[n] = points[m];
+    if n < 2 {
+        return Some(controls);
+    }
+
+    // The ends are fixed, so only the inner controls are unknown
+    let unknowns = n - 1;
+    let mut normal_matrix = vec![vec![0.0; unknowns]; unknowns];
+    let mut rhs = vec![Vec4::default(); unknowns];
+    for k in 1..m {
+        let row = &basis_rows[k];
+        let residual = Vec4::subtract(
+            &Vec4::subtract(&points[k], &Vec4::to_scaled(&points[0], row[0])),
+            &Vec4::to_scaled(&points[m], row[n]),
+        );
+        for i in 1..n {
+            if row[i] == 0.0 {
+                continue;
+            }
+            rhs[i - 1] = Vec4::add(&rhs[i - 1], &Vec4::to_scaled(&residual, row[i]));
+            for j in 1..n {
+                normal_matrix[i - 1][j - 1] += row[i] * row[j];
+            }
+        }
+    }
+
+    let inner = solve_linear_system(normal_matrix, rhs)?;
+    controls[1..n].copy_from_slice(&inner);
+    Some(controls)
+}
+
+/// Curve of the given degree and control count approximating ordered points.
+/// The first and last points are hit exactly.
+/// None if there are fewer points than controls, or fewer controls than degree + 1.
+pub fn fit_curve(
+    points: &[Vec3],
+    degree: u32,
+    control_count: usize,
+) -> Option<(NurbsCurve, Deviation)> {
+    if degree == 0 || control_count < degree as usize + 1 || points.len() < control_count {
+        return None;
+    }
+    let params = create_params(
+        &point_distances(points, false),
+        Parameterization::ChordLength,
+    );
+    let knots = fitting_knots(&params, degree, control_count);
+    let homogeneous: Vec<Vec4> = points.iter().map(|point| point.append(1.0)).collect();
+    let controls = fit_controls(&homogeneous, &params, degree, &knots, control_count)?;
+    let curve = NurbsCurve::new(degree, controls, knots);
+
+    let deviation = Deviation::from_distances(
+        points
+            .iter()
+            .zip(params.iter())
+            .map(|(point, t)| Vec3::subtract(&curve.point(*t), point).len()),
+    );
+    Some((curve, deviation))
+}
+
+/// Chord length parameters for each line of points, averaged over the lines, eq 9.6 extended to grids
+fn averaged_params(lines: &[Vec<Vec3>]) -> Vec<f32> {
+    let count = lines[0].len();
+    let mut res = vec![0.0; count];
+    for line in lines {
+        let params = create_params(&point_distances(line, false), Parameterization::ChordLength);
+        for (sum, t) in res.iter_mut().zip(params) {
+            *sum += t;
+        }
+    }
+    res.iter_mut().for_each(|t| *t /= lines.len() as f32);
+    res[count - 1] = 1.0;
+    res
+}
+
+/// Surface approximating a grid of points stored with u changing fastest.
+/// Fits every row in u, then every column of the row controls in v, section 9.4.3.
+/// None if either direction has fewer points than controls or fewer controls than degree + 1.
+#[allow(clippy::too_many_arguments)]
+pub fn fit_surface(
+    points: &[Vec3],
+    point_count_u: usize,
+    point_count_v: usize,
+    degree_u: u32,
+    degree_v: u32,
+    control_count_u: usize,
+    control_count_v: usize,
+) -> Option<(NurbsSurface, Deviation)> {
+    if degree_u == 0
+        || degree_v == 0
+        || points.len() != point_count_u * point_count_v
+        || control_count_u < degree_u as usize + 1
+        || control_count_v < degree_v as usize + 1
+        || point_count_u < control_count_u
+        || point_count_v < control_count_v
+    {
+        return None;
+    }
+
+    let rows: Vec<Vec<Vec3>> = points
+        .chunks(point_count_u)
+        .map(|row| row.to_vec())
+        .collect();
+    let columns: Vec<Vec<Vec3>> = (0..point_count_u)
+        .map(|i_u| rows.iter().map(|row| row[i_u]).collect())
+        .collect();
+    let params_u = averaged_params(&rows);
+    let params_v = averaged_params(&columns);
+    let knots_u = fitting_knots(&params_u, degree_u, control_count_u);
+    let knots_v = fitting_knots(&params_v, degree_v, control_count_v);
+
+    let row_controls = rows
+        .iter()
+        .map(|row| {
+            let homogeneous: Vec<Vec4> = row.iter().map(|point| point.append(1.0)).collect();
+            fit_controls(&homogeneous, &params_u, degree_u, &knots_u, control_count_u)
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut curves_v = Vec::with_capacity(control_count_u);
+    for i_u in 0..control_count_u {
+        let column: Vec<Vec4> = row_controls.iter().map(|row| row[i_u]).collect();
+        let controls = fit_controls(&column, &params_v, degree_v, &knots_v, control_count_v)?;
+        curves_v.push(NurbsCurve::new(degree_v, controls, knots_v.clone()));
+    }
+    let surface = NurbsSurface::from_curves_v(curves_v, degree_u, knots_u);
+
+    let deviation = Deviation::from_distances(points.iter().enumerate().map(|(i, point)| {
+        let u = params_u[i % point_count_u];
+        let v = params_v[i / point_count_u];
+        Vec3::subtract(&surface.point(u, v), point).len()
+    }));
+    Some((surface, deviation))
+}
@@ -0,0 +1,195 @@
+//! Global interpolation through points, A9.1.
+//! Points are passed as Vec4 so weighted controls can be interpolated too, plain points use w = 1.
+
+use crate::math::linear_algebra::{vec3::Vec3, vec4::Vec4};
+
+use wasm_bindgen::prelude::*;
+
+use super::{
+    basis::{basis_function_derivatives, basis_functions, find_span},
+    curve::NurbsCurve,
+    solve_linear_system,
+};
+
+/// How parameters are spread between the points
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Parameterization {
+    Uniform = 0,
+    /// Proportional to the distance between points, eq 9.5
+    ChordLength = 1,
+    /// Proportional to the square root of the distance, follows sharp turns more tightly
+    Centripetal = 2,
+}
+
+/// Distances between consecutive points.
+/// Closed adds the distance from the last point back to the first.
+pub fn point_distances(points: &[Vec3], closed: bool) -> Vec<f32> {
+    let mut distances: Vec<f32> = points
+        .windows(2)
+        .map(|pair| Vec3::subtract(&pair[1], &pair[0]).len())
+        .collect();
+    if closed {
+        distances.push(Vec3::subtract(&points[0], &points[points.len() - 1]).len());
+    }
+    distances
+}
+
+/// Parameters from 0 to 1, one more than there are distances
+pub fn create_params(distances: &[f32], parameterization: Parameterization) -> Vec<f32> {
+    let steps: Vec<f32> = distances
+        .iter()
+        .map(|d| match parameterization {
+            Parameterization::Uniform => 1.0,
+            Parameterization::ChordLength => *d,
+            Parameterization::Centripetal => d.sqrt(),
+        })
+        .collect();
+    let total: f32 = steps.iter().sum();
+    let count = distances.len();
+    if total <= 0.0 {
+        return (0..=count).map(|i| i as f32 / count as f32).collect();
+    }
+
+    let mut params = Vec::with_capacity(count + 1);
+    let mut sum = 0.0;
+    params.push(0.0);
+    for step in steps.iter().take(count - 1) {
+        sum += step;
+        params.push(sum / total);
+    }
+    params.push(1.0);
+    params
+}
+
+/// Clamped knots by averaging the parameters, eq 9.8
+pub fn averaged_knots(params: &[f32], degree: u32) -> Vec<f32> {
+    let p = degree as usize;
+    let n = params.len() - 1;
+    let mut knots = vec![0.0; p + 1];
+    for j in 1..=n - p {
+        knots.push(params[j..j + p].iter().sum::<f32>() / p as f32);
+    }
+    knots.extend(std::iter::repeat_n(1.0, p + 1));
+    knots
+}
+
+/// Curve passing through points[k] at params[k].
+/// The degree is lowered when there are too few points, None if the system is singular.
+pub fn interpolate(points: &[Vec4], params: &[f32], degree: u32) -> Option<NurbsCurve> {
+    if points.len() < 2 || points.len() != params.len() {
+        return None;
+    }
+    let degree = degree.min(points.len() as u32 - 1);
+    let knots = averaged_knots(params, degree);
+
+    let n = points.len();
+    let mut matrix = vec![vec![0.0; n]; n];
+    for (row, t) in matrix.iter_mut().zip(params) {
+        let span = find_span(degree, &knots, *t);
+        let basis = basis_functions(span, *t, degree, &knots);
+        for (i, b) in basis.iter().enumerate() {
+            row[span - degree as usize + i] = *b;
+        }
+    }
+
+    let controls = solve_linear_system(matrix, points.to_vec())?;
+    Some(NurbsCurve::new(degree, controls, knots))
+}
+
+/// Like interpolate, but also matches the first derivative at either end, section 9.2.2.
+/// Derivatives are with respect to the parameter, which runs from 0 to 1.
+/// Each derivative adds a control.
+pub fn interpolate_with_derivatives(
+    points: &[Vec4],
+    params: &[f32],
+    degree: u32,
+    start_derivative: Option<Vec4>,
+    end_derivative: Option<Vec4>,
+) -> Option<NurbsCurve> {
+    if points.len() < 2 || points.len() != params.len() {
+        return None;
+    }
+    let n = points.len() - 1;
+
+    // Repeating a parameter for each derivative makes room for its control in the averaged knots
+    let mut knot_params = params.to_vec();
+    let mut constraints: Vec<(f32, usize, Vec4)> = Vec::with_capacity(points.len() + 2);
+    if let Some(derivative) = start_derivative {
+        knot_params.insert(0, params[0]);
+        constraints.push((params[0], 1, derivative));
+    }
+    if let Some(derivative) = end_derivative {
+        knot_params.push(params[n]);
+        constraints.push((params[n], 1, derivative));
+    }
+    constraints.extend(params.iter().zip(points).map(|(t, point)| (*t, 0, *point)));
+
+    let control_count = knot_params.len();
+    let degree = degree.min(control_count as u32 - 1);
+    let p = degree as usize;
+    let knots = averaged_knots(&knot_params, degree);
+
+    let mut matrix = vec![vec![0.0; control_count]; control_count];
+    let mut rhs = Vec::with_capacity(control_count);
+    for (row, (t, derivative, value)) in matrix.iter_mut().zip(constraints) {
+        let span = find_span(degree, &knots, t);
+        let basis = &basis_function_derivatives(span, t, degree, &knots, derivative)[derivative];
+        for (i, b) in basis.iter().enumerate() {
+            row[span - p + i] = *b;
+        }
+        rhs.push(value);
+    }
+
+    let controls = solve_linear_system(matrix, rhs)?;
+    Some(NurbsCurve::new(degree, controls, knots))
+}
+
+/// Closed curve through the points that is smooth where it meets itself.
+/// params has one more entry than points, the last is where the curve gets back to the first point.
+/// The result is clamped with a domain of 0 to 1.
+pub fn interpolate_closed(points: &[Vec4], params: &[f32], degree: u32) -> Option<NurbsCurve> {
+    let n = points.len();
+    if n < 3 || params.len() != n + 1 {
+        return None;
+    }
+    let degree = degree.min(n as u32 - 1);
+    let p = degree as usize;
+
+    // Knots repeat with period 1, even degrees put them between parameters to keep the system solvable
+    let knot_values: Vec<f32> = (0..n)
+        .map(|i| {
+            if p % 2 == 1 {
+                params[i]
+            } else {
+                (params[i] + params[i + 1]) / 2.0
+            }
+        })
+        .collect();
+    let periodic_knot = |i: isize| {
+        let wraps = i.div_euclid(n as isize);
+        knot_values[i.rem_euclid(n as isize) as usize] + wraps as f32
+    };
+    let start = knot_values[0];
+    let knots: Vec<f32> = (0..n + 2 * p + 1)
+        .map(|j| periodic_knot(j as isize - p as isize) - start)
+        .collect();
+
+    // The last p controls repeat the first p
+    let mut matrix = vec![vec![0.0; n]; n];
+    for (row, param) in matrix.iter_mut().zip(params) {
+        let t = (param - start).rem_euclid(1.0);
+        let span = find_span(degree, &knots, t);
+        let basis = basis_functions(span, t, degree, &knots);
+        for (i, b) in basis.iter().enumerate() {
+            row[(span - p + i) % n] += *b;
+        }
+    }
+
+    let mut controls = solve_linear_system(matrix, points.to_vec())?;
+    controls.extend_from_within(..p);
+
+    let mut curve = NurbsCurve::new(degree, controls, knots);
+    curve.clamp();
+    Some(curve)
+}
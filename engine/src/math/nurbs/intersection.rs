@@ -0,0 +1,475 @@
+//! Curve intersections.
+//!
+//! Both curves are split in half until the boxes around their controls stop touching or the pieces
+//! are flat, then every pair of flat pieces is refined with Newton iteration from where their chords meet.
+//! Pieces keep the parameters of the curve they came from, so results need no mapping back.
+//! Stretches where the curves coincide are found separately, by checking whole spans between break points.
+
+use crate::math::{
+    geometry::{bounding_box::BoundingBox, plane::Plane},
+    linear_algebra::vec3::Vec3,
+};
+
+use super::curve::NurbsCurve;
+
+/// Limit on the number of halvings, counted over both curves
+const MAX_DEPTH: usize = 32;
+const MAX_ITERATIONS: usize = 32;
+/// Pieces are flat enough to refine when their controls are this fraction of the curves size off their chord
+const FLATNESS: f32 = 1e-3;
+/// Convergence of Newton iteration, relative to the size of the coordinates
+const DISTANCE_EPSILON: f32 = 1e-6;
+/// Samples per span when checking whether a stretch lies on the other curve or plane
+const OVERLAP_SAMPLES: usize = 8;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CurveCurveIntersection {
+    pub t_a: f32,
+    pub t_b: f32,
+    pub point: Vec3,
+}
+
+/// Stretch where the curves coincide.
+/// Runs forward on curve a, t_b goes backwards when the curves run opposite ways.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CurveCurveOverlap {
+    pub start: CurveCurveIntersection,
+    pub end: CurveCurveIntersection,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CurvePlaneIntersection {
+    pub t: f32,
+    pub point: Vec3,
+}
+
+/// Stretch of the curve lying in the plane
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CurvePlaneOverlap {
+    pub start: CurvePlaneIntersection,
+    pub end: CurvePlaneIntersection,
+}
+
+fn control_points(curve: &NurbsCurve) -> impl Iterator<Item = Vec3> + '_ {
+    curve
+        .weighted_controls
+        .iter()
+        .map(|control| control.to_vec3_safe())
+}
+
+/// Contains the curve, since it lies in the convex hull of its controls
+fn control_box(curve: &NurbsCurve) -> BoundingBox {
+    BoundingBox::from_points(control_points(curve)).unwrap()
+}
+
+/// Farthest control from the chord between the first and last control
+fn flatness(curve: &NurbsCurve) -> f32 {
+    let first = curve.weighted_controls[0].to_vec3_safe();
+    let last = curve.weighted_controls[curve.weighted_controls.len() - 1].to_vec3_safe();
+    let chord = Vec3::subtract(&last, &first);
+    let chord_len = chord.len();
+    control_points(curve)
+        .map(|point| {
+            let to_point = Vec3::subtract(&point, &first);
+            if chord_len == 0.0 {
+                to_point.len()
+            } else {
+                Vec3::cross(&to_point, &chord).len() / chord_len
+            }
+        })
+        .fold(0.0, f32::max)
+}
+
+fn split_in_half(curve: &NurbsCurve) -> Option<(NurbsCurve, NurbsCurve)> {
+    let (start, end) = curve.domain();
+    curve.split_at((start + end) / 2.0)
+}
+
+/// Distinct knots, including the ends of the domain
+fn breaks(curve: &NurbsCurve) -> Vec<f32> {
+    let (start, end) = curve.domain();
+    let mut res: Vec<f32> = curve
+        .knots
+        .iter()
+        .copied()
+        .filter(|knot| *knot >= start && *knot <= end)
+        .collect();
+    res.dedup();
+    res
+}
+
+/// Evenly spread parameters, OVERLAP_SAMPLES per span
+fn seeds(curve: &NurbsCurve) -> Vec<f32> {
+    let mut res = Vec::new();
+    for pair in breaks(curve).windows(2) {
+        for i in 0..OVERLAP_SAMPLES {
+            res.push(pair[0] + (pair[1] - pair[0]) * i as f32 / OVERLAP_SAMPLES as f32);
+        }
+    }
+    res.push(curve.domain().1);
+    res
+}
+
+/// Parameters between start and end to check a stretch with, the ends, the seeds inside and the midpoints between them
+fn stretch_params(start: f32, end: f32, seeds: &[f32]) -> Vec<f32> {
+    let mut points = vec![start];
+    points.extend(seeds.iter().copied().filter(|t| *t > start && *t < end));
+    points.push(end);
+    let mut res = Vec::with_capacity(points.len() * 2);
+    for pair in points.windows(2) {
+        res.push(pair[0]);
+        res.push((pair[0] + pair[1]) / 2.0);
+    }
+    res.push(end);
+    res
+}
+
+/// Sorted with values closer than epsilon merged
+fn sorted_breaks(mut values: Vec<f32>, epsilon: f32) -> Vec<f32> {
+    values.sort_by(f32::total_cmp);
+    values.dedup_by(|b, a| *b - *a <= epsilon);
+    values
+}
+
+/// Where the chords of two flat pieces come closest, as parameters on each piece
+fn chord_params(a: &NurbsCurve, b: &NurbsCurve) -> (f32, f32) {
+    let a0 = a.weighted_controls[0].to_vec3_safe();
+    let a1 = a.weighted_controls[a.weighted_controls.len() - 1].to_vec3_safe();
+    let b0 = b.weighted_controls[0].to_vec3_safe();
+    let b1 = b.weighted_controls[b.weighted_controls.len() - 1].to_vec3_safe();
+    let d_a = Vec3::subtract(&a1, &a0);
+    let d_b = Vec3::subtract(&b1, &b0);
+    let r = Vec3::subtract(&a0, &b0);
+    let aa = Vec3::dot(&d_a, &d_a);
+    let bb = Vec3::dot(&d_b, &d_b);
+    let ab = Vec3::dot(&d_a, &d_b);
+    let ar = Vec3::dot(&d_a, &r);
+    let br = Vec3::dot(&d_b, &r);
+    let denominator = aa * bb - ab * ab;
+    let (s, t) = if denominator <= 1e-12 * aa * bb || aa == 0.0 || bb == 0.0 {
+        // Parallel or degenerate chords, the middles are as good as anything
+        (0.5, 0.5)
+    } else {
+        (
+            ((ab * br - bb * ar) / denominator).clamp(0.0, 1.0),
+            ((aa * br - ab * ar) / denominator).clamp(0.0, 1.0),
+        )
+    };
+    let (start_a, end_a) = a.domain();
+    let (start_b, end_b) = b.domain();
+    (
+        start_a + (end_a - start_a) * s,
+        start_b + (end_b - start_b) * t,
+    )
+}
+
+fn subdivide_pair(
+    a: &NurbsCurve,
+    b: &NurbsCurve,
+    tolerance: f32,
+    flat: f32,
+    depth: usize,
+    seeds: &mut Vec<(f32, f32)>,
+) {
+    let box_a = control_box(a);
+    let box_b = control_box(b);
+    if !box_a.expanded(tolerance).intersects(&box_b) {
+        return;
+    }
+    let flat_a = flatness(a) <= flat;
+    let flat_b = flatness(b) <= flat;
+    if (flat_a && flat_b) || depth >= MAX_DEPTH {
+        seeds.push(chord_params(a, b));
+        return;
+    }
+
+    let split_a = !flat_a && (flat_b || box_a.diagonal() >= box_b.diagonal());
+    let halves = if split_a {
+        split_in_half(a)
+    } else {
+        split_in_half(b)
+    };
+    let Some((left, right)) = halves else {
+        seeds.push(chord_params(a, b));
+        return;
+    };
+    for half in [left, right] {
+        if split_a {
+            subdivide_pair(&half, b, tolerance, flat, depth + 1, seeds);
+        } else {
+            subdivide_pair(a, &half, tolerance, flat, depth + 1, seeds);
+        }
+    }
+}
+
+/// Gauss Newton on |a(s) - b(t)|, converges to crossings and to touching points.
+/// None if the curves end up further apart than tolerance.
+fn refine_pair(
+    a: &NurbsCurve,
+    b: &NurbsCurve,
+    mut s: f32,
+    mut t: f32,
+    tolerance: f32,
+) -> Option<CurveCurveIntersection> {
+    let (start_a, end_a) = a.domain();
+    let (start_b, end_b) = b.domain();
+    for _ in 0..MAX_ITERATIONS {
+        let derivatives_a = a.derivatives(s, 1);
+        let derivatives_b = b.derivatives(t, 1);
+        let r = Vec3::subtract(&derivatives_a[0], &derivatives_b[0]);
+        let epsilon = DISTANCE_EPSILON * (1.0 + derivatives_a[0].len());
+        if r.len() <= epsilon {
+            break;
+        }
+        let d_a = &derivatives_a[1];
+        let d_b = &derivatives_b[1];
+        let aa = Vec3::dot(d_a, d_a);
+        let bb = Vec3::dot(d_b, d_b);
+        let ab = Vec3::dot(d_a, d_b);
+        let determinant = aa * bb - ab * ab;
+        if determinant <= 1e-12 * aa * bb {
+            break;
+        }
+        // Normal equations of [d_a, -d_b] [ds, dt] = -r
+        let rhs_s = -Vec3::dot(d_a, &r);
+        let rhs_t = Vec3::dot(d_b, &r);
+        let next_s = (s + (rhs_s * bb + rhs_t * ab) / determinant).clamp(start_a, end_a);
+        let next_t = (t + (rhs_t * aa + rhs_s * ab) / determinant).clamp(start_b, end_b);
+        let step = Vec3::subtract(
+            &Vec3::to_scaled(d_a, next_s - s),
+            &Vec3::to_scaled(d_b, next_t - t),
+        );
+        (s, t) = (next_s, next_t);
+        if step.len() <= epsilon {
+            break;
+        }
+    }
+    let point = a.point(s);
+    (Vec3::distance(&point, &b.point(t)) <= tolerance).then_some(CurveCurveIntersection {
+        t_a: s,
+        t_b: t,
+        point,
+    })
+}
+
+/// Stretches of a lying within tolerance of b.
+/// A stretch can only start or stop at an end or knot of a, or where an end or knot of b lies on a.
+fn curve_overlaps(a: &NurbsCurve, b: &NurbsCurve, tolerance: f32) -> Vec<CurveCurveOverlap> {
+    let seeds_a = seeds(a);
+    let seeds_b = seeds(b);
+    let on_b = |t_a: f32| {
+        let point = a.point(t_a);
+        let (t_b, closest) = b.closest_point(&point, &seeds_b);
+        (Vec3::distance(&point, &closest) <= tolerance).then_some(CurveCurveIntersection {
+            t_a,
+            t_b,
+            point,
+        })
+    };
+
+    let (start, end) = a.domain();
+    let mut candidates = breaks(a);
+    for t_b in breaks(b) {
+        let point = b.point(t_b);
+        let (t_a, closest) = a.closest_point(&point, &seeds_a);
+        if Vec3::distance(&point, &closest) <= tolerance {
+            candidates.push(t_a);
+        }
+    }
+    let candidates = sorted_breaks(candidates, 1e-6 * (end - start));
+
+    let mut res: Vec<CurveCurveOverlap> = Vec::new();
+    for pair in candidates.windows(2) {
+        if !stretch_params(pair[0], pair[1], &seeds_a)
+            .into_iter()
+            .all(|t_a| on_b(t_a).is_some())
+        {
+            continue;
+        }
+        let (Some(first), Some(last)) = (on_b(pair[0]), on_b(pair[1])) else {
+            continue;
+        };
+        match res.last_mut() {
+            Some(overlap) if overlap.end.t_a == pair[0] => overlap.end = last,
+            _ => res.push(CurveCurveOverlap {
+                start: first,
+                end: last,
+            }),
+        }
+    }
+    res
+}
+
+/// Crossing and touching points of two curves, sorted along a, and the stretches where they coincide.
+/// Points inside an overlap are left out.
+pub fn intersect_curves(
+    a: &NurbsCurve,
+    b: &NurbsCurve,
+    tolerance: f32,
+) -> (Vec<CurveCurveIntersection>, Vec<CurveCurveOverlap>) {
+    let overlaps = curve_overlaps(a, b, tolerance);
+    let flat = tolerance.max(FLATNESS * (control_box(a).diagonal() + control_box(b).diagonal()));
+    let mut seeds = Vec::new();
+    subdivide_pair(a, b, tolerance, flat, 0, &mut seeds);
+
+    let mut points: Vec<CurveCurveIntersection> = Vec::new();
+    for (s, t) in seeds {
+        let Some(hit) = refine_pair(a, b, s, t, tolerance) else {
+            continue;
+        };
+        let in_overlap = overlaps.iter().any(|overlap| {
+            (overlap.start.t_a..=overlap.end.t_a).contains(&hit.t_a)
+                || Vec3::distance(&overlap.start.point, &hit.point) <= tolerance
+                || Vec3::distance(&overlap.end.point, &hit.point) <= tolerance
+        });
+        let found = points
+            .iter()
+            .any(|point| Vec3::distance(&point.point, &hit.point) <= tolerance);
+        if !in_overlap && !found {
+            points.push(hit);
+        }
+    }
+    points.sort_by(|x, y| x.t_a.total_cmp(&y.t_a));
+    (points, overlaps)
+}
+
+fn subdivide_plane(
+    curve: &NurbsCurve,
+    plane: &Plane,
+    tolerance: f32,
+    flat: f32,
+    depth: usize,
+    seeds: &mut Vec<f32>,
+) {
+    let (min, max) = control_points(curve)
+        .map(|point| plane.signed_distance(&point))
+        .fold((f32::MAX, f32::MIN), |(min, max), d| {
+            (min.min(d), max.max(d))
+        });
+    if min > tolerance || max < -tolerance {
+        return;
+    }
+
+    let (start, end) = curve.domain();
+    // Near the plane all the way along, which also covers touching the plane without crossing
+    let in_plane = min >= -tolerance && max <= tolerance;
+    if in_plane || flatness(curve) <= flat || depth >= MAX_DEPTH {
+        let d0 = plane.signed_distance(&curve.weighted_controls[0].to_vec3_safe());
+        let d1 = plane.signed_distance(
+            &curve.weighted_controls[curve.weighted_controls.len() - 1].to_vec3_safe(),
+        );
+        let s = if d0 != d1 {
+            (d0 / (d0 - d1)).clamp(0.0, 1.0)
+        } else {
+            0.5
+        };
+        seeds.push(start + (end - start) * s);
+        return;
+    }
+
+    let Some((left, right)) = split_in_half(curve) else {
+        seeds.push((start + end) / 2.0);
+        return;
+    };
+    subdivide_plane(&left, plane, tolerance, flat, depth + 1, seeds);
+    subdivide_plane(&right, plane, tolerance, flat, depth + 1, seeds);
+}
+
+/// Newton on the distance to the plane, falling back to Newton on its derivative,
+/// which finds where the curve comes closest when it touches without crossing.
+fn refine_plane(curve: &NurbsCurve, plane: &Plane, seed: f32, tolerance: f32) -> Option<f32> {
+    let (start, end) = curve.domain();
+    let normal = plane.get_normal().to_normalized();
+    let distance = |t: f32| plane.signed_distance(&curve.point(t));
+
+    for derivative in [0, 1] {
+        let mut t = seed;
+        for _ in 0..MAX_ITERATIONS {
+            let derivatives = curve.derivatives(t, derivative + 1);
+            let f = if derivative == 0 {
+                plane.signed_distance(&derivatives[0])
+            } else {
+                Vec3::dot(&derivatives[1], &normal)
+            };
+            let f_prime = Vec3::dot(&derivatives[derivative + 1], &normal);
+            let epsilon = DISTANCE_EPSILON * (1.0 + derivatives[0].len());
+            if (derivative == 0 && f.abs() <= epsilon) || f_prime == 0.0 {
+                break;
+            }
+            let next = (t - f / f_prime).clamp(start, end);
+            let step = (next - t) * derivatives[1].len();
+            t = next;
+            if step.abs() <= epsilon {
+                break;
+            }
+        }
+        if distance(t).abs() <= tolerance {
+            return Some(t);
+        }
+    }
+    None
+}
+
+/// Spans lying within tolerance of the plane, merged where they meet.
+/// A polynomial piece can only leave the plane at a knot, so whole spans are checked.
+fn plane_overlaps(curve: &NurbsCurve, plane: &Plane, tolerance: f32) -> Vec<CurvePlaneOverlap> {
+    let seeds = seeds(curve);
+    let mut res: Vec<CurvePlaneOverlap> = Vec::new();
+    for pair in breaks(curve).windows(2) {
+        if !stretch_params(pair[0], pair[1], &seeds)
+            .into_iter()
+            .all(|t| plane.signed_distance(&curve.point(t)).abs() <= tolerance)
+        {
+            continue;
+        }
+        let last = CurvePlaneIntersection {
+            t: pair[1],
+            point: curve.point(pair[1]),
+        };
+        match res.last_mut() {
+            Some(overlap) if overlap.end.t == pair[0] => overlap.end = last,
+            _ => res.push(CurvePlaneOverlap {
+                start: CurvePlaneIntersection {
+                    t: pair[0],
+                    point: curve.point(pair[0]),
+                },
+                end: last,
+            }),
+        }
+    }
+    res
+}
+
+/// Crossing and touching points of a curve and a plane, sorted along the curve, and the stretches lying in the plane.
+/// Points inside an overlap are left out.
+pub fn intersect_curve_plane(
+    curve: &NurbsCurve,
+    plane: &Plane,
+    tolerance: f32,
+) -> (Vec<CurvePlaneIntersection>, Vec<CurvePlaneOverlap>) {
+    let overlaps = plane_overlaps(curve, plane, tolerance);
+    let flat = tolerance.max(FLATNESS * control_box(curve).diagonal());
+    let mut seeds = Vec::new();
+    subdivide_plane(curve, plane, tolerance, flat, 0, &mut seeds);
+
+    let mut points: Vec<CurvePlaneIntersection> = Vec::new();
+    for seed in seeds {
+        let Some(t) = refine_plane(curve, plane, seed, tolerance) else {
+            continue;
+        };
+        let point = curve.point(t);
+        let in_overlap = overlaps.iter().any(|overlap| {
+            (overlap.start.t..=overlap.end.t).contains(&t)
+                || Vec3::distance(&overlap.start.point, &point) <= tolerance
+                || Vec3::distance(&overlap.end.point, &point) <= tolerance
+        });
+        let found = points
+            .iter()
+            .any(|hit| Vec3::distance(&hit.point, &point) <= tolerance);
+        if !in_overlap && !found {
+            points.push(CurvePlaneIntersection { t, point });
+        }
+    }
+    points.sort_by(|x, y| x.t.total_cmp(&y.t));
+    (points, overlaps)
+}
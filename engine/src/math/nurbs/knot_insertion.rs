@@ -0,0 +1,259 @@
+//! Knot insertion, refinement and splitting, and isocurves of surfaces.
+//! None of these change the shape of the curve.
+
+use crate::math::linear_algebra::vec4::Vec4;
+
+use super::{
+    basis::find_span,
+    curve::NurbsCurve,
+    surface::{NurbsSurface, SurfaceDirection},
+};
+
+/// Number of times t appears in the knot vector
+pub fn knot_multiplicity(knots: &[f32], t: f32) -> usize {
+    knots.iter().filter(|knot| **knot == t).count()
+}
+
+impl NurbsCurve {
+    /// Inserts t up to times times, A5.1.
+    /// Multiplicity is capped at the degree, returns how many knots were inserted.
+    pub fn insert_knot(&mut self, t: f32, times: usize) -> usize {
+        let (start, end) = self.domain();
+        if t <= start || t >= end {
+            return 0;
+        }
+        let p = self.degree as usize;
+        let s = knot_multiplicity(&self.knots, t);
+        let r = times.min(p.saturating_sub(s));
+        if r == 0 {
+            return 0;
+        }
+
+        let k = find_span(self.degree, &self.knots, t);
+        self.insert_knot_in_span(t, r, k, s);
+        r
+    }
+
+    /// The body of A5.1, inserts t r times where knots[k] <= t < knots[k + 1] and t already appears s times
+    fn insert_knot_in_span(&mut self, t: f32, r: usize, k: usize, s: usize) {
+        let p = self.degree as usize;
+        let n = self.weighted_controls.len() - 1;
+        let old = &self.weighted_controls;
+
+        let mut knots = Vec::with_capacity(self.knots.len() + r);
+        knots.extend_from_slice(&self.knots[..=k]);
+        knots.extend(std::iter::repeat_n(t, r));
+        knots.extend_from_slice(&self.knots[k + 1..]);
+
+        let mut controls = vec![Vec4::default(); n + 1 + r];
+        controls[..=k - p].copy_from_slice(&old[..=k - p]);
+        controls[k - s + r..].copy_from_slice(&old[k - s..]);
+
+        let mut temp: Vec<Vec4> = old[k - p..=k - s].to_vec();
+        let mut l = 0;
+        for j in 1..=r {
+            l = k - p + j;
+            for i in 0..=p - j - s {
+                let alpha = (t - self.knots[l + i]) / (self.knots[i + k + 1] - self.knots[l + i]);
+                temp[i] = Vec4::lerp(&temp[i], &temp[i + 1], alpha);
+            }
+            controls[l] = temp[0];
+            controls[k + r - j - s] = temp[p - j - s];
+        }
+        if l + 1 < k - s {
+            controls[l + 1..k - s].copy_from_slice(&temp[1..k - s - l]);
+        }
+
+        self.knots = knots;
+        self.weighted_controls = controls;
+    }
+
+    /// Turns an unclamped knot vector into a clamped one over the same domain.
+    /// The shape is kept, the controls outside the domain are dropped.
+    pub fn clamp(&mut self) {
+        let p = self.degree as usize;
+        let (start, end) = self.domain();
+
+        // End first so the span at the start does not move
+        for t in [end, start] {
+            let s = knot_multiplicity(&self.knots, t);
+            if s < p {
+                let k = self.knots.iter().rposition(|knot| *knot <= t).unwrap();
+                self.insert_knot_in_span(t, p - s, k, s);
+            }
+        }
+
+        let before = self.knots.iter().filter(|knot| **knot < start).count();
+        let after = self.knots.iter().filter(|knot| **knot > end).count();
+        let start_multiplicity = knot_multiplicity(&self.knots, start);
+        let end_multiplicity = knot_multiplicity(&self.knots, end);
+
+        let first = before + start_multiplicity - p - 1;
+        let last = self.weighted_controls.len() - (after + end_multiplicity - p - 1);
+
+        let mut knots = vec![start; p + 1];
+        knots.extend(
+            self.knots
+                .iter()
+                .copied()
+                .filter(|knot| *knot > start && *knot < end),
+        );
+        knots.extend(std::iter::repeat_n(end, p + 1));
+
+        self.weighted_controls = self.weighted_controls[first..last].to_vec();
+        self.knots = knots;
+    }
+
+    /// Inserts all of new_knots at once, A5.4.
+    /// new_knots must be sorted and inside the domain.
+    pub fn refine_knots(&mut self, new_knots: &[f32]) {
+        if new_knots.is_empty() {
+            return;
+        }
+        let p = self.degree as usize;
+        let old_knots = &self.knots;
+        let old = &self.weighted_controls;
+        let n = old.len() - 1;
+        let m = n + p + 1;
+        let r = new_knots.len() - 1;
+
+        let a = find_span(self.degree, old_knots, new_knots[0]);
+        let b = find_span(self.degree, old_knots, new_knots[r]) + 1;
+
+        let mut knots = vec![0.0; m + r + 2];
+        let mut controls = vec![Vec4::default(); n + r + 2];
+
+        controls[..=a - p].copy_from_slice(&old[..=a - p]);
+        for j in b - 1..=n {
+            controls[j + r + 1] = old[j];
+        }
+        knots[..=a].copy_from_slice(&old_knots[..=a]);
+        for j in b + p..=m {
+            knots[j + r + 1] = old_knots[j];
+        }
+
+        let mut i = b + p - 1;
+        let mut k = b + p + r;
+        for j in (0..=r).rev() {
+            while new_knots[j] <= old_knots[i] && i > a {
+                controls[k - p - 1] = old[i - p - 1];
+                knots[k] = old_knots[i];
+                k -= 1;
+                i -= 1;
+            }
+            controls[k - p - 1] = controls[k - p];
+            for l in 1..=p {
+                let index = k - p + l;
+                let alpha = knots[k + l] - new_knots[j];
+                if alpha == 0.0 {
+                    controls[index - 1] = controls[index];
+                } else {
+                    let alpha = alpha / (knots[k + l] - old_knots[i + l - p]);
+                    controls[index - 1] = Vec4::lerp(&controls[index], &controls[index - 1], alpha);
+                }
+            }
+            knots[k] = new_knots[j];
+            k -= 1;
+        }
+
+        self.knots = knots;
+        self.weighted_controls = controls;
+    }
+
+    /// Splits into the part before t and the part after t.
+    /// None if t is not strictly inside the domain.
+    pub fn split_at(&self, t: f32) -> Option<(NurbsCurve, NurbsCurve)> {
+        let (start, end) = self.domain();
+        if t <= start || t >= end {
+            return None;
+        }
+        let p = self.degree as usize;
+
+        // With t at full multiplicity the curve passes through a control point at t
+        let mut curve = self.clone();
+        curve.insert_knot(t, p);
+
+        let mut left_knots: Vec<f32> = curve.knots.iter().copied().filter(|k| *k < t).collect();
+        left_knots.extend(std::iter::repeat_n(t, p + 1));
+        let mut right_knots: Vec<f32> = vec![t; p + 1];
+        right_knots.extend(curve.knots.iter().copied().filter(|k| *k > t));
+
+        let left_count = left_knots.len() - p - 1;
+        let right_count = right_knots.len() - p - 1;
+        let control_count = curve.weighted_controls.len();
+
+        Some((
+            NurbsCurve::new(
+                self.degree,
+                curve.weighted_controls[..left_count].to_vec(),
+                left_knots,
+            ),
+            NurbsCurve::new(
+                self.degree,
+                curve.weighted_controls[control_count - right_count..].to_vec(),
+                right_knots,
+            ),
+        ))
+    }
+
+    /// The part between start and end, clamped to the domain.
+    /// None if that leaves nothing.
+    pub fn sub_curve(&self, start: f32, end: f32) -> Option<NurbsCurve> {
+        let (domain_start, domain_end) = self.domain();
+        let (start, end) = (start.max(domain_start), end.min(domain_end));
+        if start >= end {
+            return None;
+        }
+        let curve = match self.split_at(start) {
+            Some((_, right)) => right,
+            None => self.clone(),
+        };
+        Some(match curve.split_at(end) {
+            Some((left, _)) => left,
+            None => curve,
+        })
+    }
+
+    /// Weighted control the curve passes through at t, found by inserting t to full multiplicity
+    fn homogeneous_point_by_insertion(&self, t: f32) -> Vec4 {
+        let (start, end) = self.domain();
+        if t <= start {
+            return self.weighted_controls[0];
+        }
+        if t >= end {
+            return *self.weighted_controls.last().unwrap();
+        }
+        let p = self.degree as usize;
+        let mut curve = self.clone();
+        curve.insert_knot(t, p);
+        // With t appearing p times ending at knots[k], the curve is at control k - p
+        let k = curve.knots.iter().rposition(|knot| *knot == t).unwrap();
+        curve.weighted_controls[k - p]
+    }
+}
+
+impl NurbsSurface {
+    /// Exact curve running in direction with the other parameter held at param, clamped to the domain.
+    /// Inserting param to full multiplicity across direction leaves one row of controls on the surface,
+    /// that row is the curve and it keeps the degree and knots of direction.
+    pub fn isocurve(&self, direction: SurfaceDirection, param: f32) -> NurbsCurve {
+        match direction {
+            SurfaceDirection::U => NurbsCurve::new(
+                self.degree_u,
+                self.curves_v()
+                    .iter()
+                    .map(|column| column.homogeneous_point_by_insertion(param))
+                    .collect(),
+                self.knots_u.clone(),
+            ),
+            SurfaceDirection::V => NurbsCurve::new(
+                self.degree_v,
+                self.curves_u()
+                    .iter()
+                    .map(|row| row.homogeneous_point_by_insertion(param))
+                    .collect(),
+                self.knots_v.clone(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Knot removal within a tolerance.
+//! A knot is only removed when the curve moves by less than the tolerance.
+
+use crate::math::linear_algebra::{vec3::Vec3, vec4::Vec4};
+
+use super::{curve::NurbsCurve, knot_insertion::knot_multiplicity, surface::NurbsSurface};
+
+/// Points per knot span where the result is compared against the original
+const DEVIATION_SAMPLES_PER_SPAN: usize = 8;
+
+fn distance_4d(a: &Vec4, b: &Vec4) -> f32 {
+    let d = Vec4::subtract(a, b);
+    (d.x * d.x + d.y * d.y + d.z * d.z + d.w * d.w).sqrt()
+}
+
+/// Converts a distance tolerance into one for weighted controls, eq 5.30
+fn homogeneous_tolerance(controls: &[Vec4], tolerance: f32) -> f32 {
+    let min_weight = controls.iter().map(|c| c.w).fold(f32::MAX, f32::min);
+    let max_distance = controls
+        .iter()
+        .map(|c| c.to_vec3_safe().len())
+        .fold(0.0, f32::max);
+    tolerance * min_weight / (1.0 + max_distance)
+}
+
+/// Parameters spread over every span of the domain, to measure how far removals moved the curve
+fn deviation_params(knots: &[f32], degree: u32) -> Vec<f32> {
+    let degree = degree as usize;
+    let domain = &knots[degree..knots.len() - degree];
+    let mut res: Vec<f32> = domain
+        .windows(2)
+        .filter(|span| span[0] < span[1])
+        .flat_map(|span| {
+            (0..DEVIATION_SAMPLES_PER_SPAN).map(move |i| {
+                span[0] + (span[1] - span[0]) * i as f32 / DEVIATION_SAMPLES_PER_SPAN as f32
+            })
+        })
+        .collect();
+    res.push(domain[domain.len() - 1]);
+    res
+}
+
+/// Distinct interior knots
+fn interior_knots(knots: &[f32], degree: u32) -> Vec<f32> {
+    let degree = degree as usize;
+    let mut res: Vec<f32> = knots[degree + 1..knots.len() - degree - 1].to_vec();
+    res.dedup();
+    res
+}
+
+impl NurbsCurve {
+    /// Removes t up to times times, A5.8.
+    /// Returns how many times it was removed.
+    pub fn remove_knot(&mut self, t: f32, times: usize, tolerance: f32) -> usize {
+        let (start, end) = self.domain();
+        let s = knot_multiplicity(&self.knots, t);
+        if t <= start || t >= end || s == 0 {
+            return 0;
+        }
+        let tolerance = homogeneous_tolerance(&self.weighted_controls, tolerance);
+
+        let p = self.degree as isize;
+        let s = s as isize;
+        let knots = &self.knots;
+        let controls = &mut self.weighted_controls;
+        let n = controls.len() as isize - 1;
+        let m = n + p + 1;
+        let order = p + 1;
+        // Index of the last t
+        let r = knots.iter().rposition(|k| *k == t).unwrap() as isize;
+
+        let first_out = (2 * r - s - p) / 2;
+        let mut last = r - s;
+        let mut first = r - p;
+        let mut temp = vec![Vec4::default(); (2 * p + 3) as usize];
+
+        let u = |i: isize| knots[i as usize];
+
+        let mut removed: isize = 0;
+        while removed < times.min(s as usize) as isize {
+            let off = first - 1;
+            temp[0] = controls[off as usize];
+            temp[(last + 1 - off) as usize] = controls[(last + 1) as usize];
+            let mut i = first;
+            let mut j = last;
+            let mut ii = 1;
+            let mut jj = last - off;
+            while j - i > removed {
+                let alfi = (t - u(i)) / (u(i + order + removed) - u(i));
+                let alfj = (t - u(j - removed)) / (u(j + order) - u(j - removed));
+                temp[ii as usize] = Vec4::to_scaled(
+                    &Vec4::subtract(
+                        &controls[i as usize],
+                        &Vec4::to_scaled(&temp[(ii - 1) as usize], 1.0 - alfi),
+                    ),
+                    1.0 / alfi,
+                );
+                temp[jj as usize] = Vec4::to_scaled(
+                    &Vec4::subtract(
+                        &controls[j as usize],
+                        &Vec4::to_scaled(&temp[(jj + 1) as usize], alfj),
+                    ),
+                    1.0 / (1.0 - alfj),
+                );
+                i += 1;
+                ii += 1;
+                j -= 1;
+                jj -= 1;
+            }
+
+            let removable = if j - i < removed {
+                distance_4d(&temp[(ii - 1) as usize], &temp[(jj + 1) as usize]) <= tolerance
+            } else {
+                let alfi = (t - u(i)) / (u(i + order + removed) - u(i));
+                let expected = Vec4::lerp(
+                    &temp[(ii - 1) as usize],
+                    &temp[(ii + removed + 1) as usize],
+                    alfi,
+                );
+                distance_4d(&controls[i as usize], &expected) <= tolerance
+            };
+            if !removable {
+                break;
+            }
+
+            let mut i = first;
+            let mut j = last;
+            while j - i > removed {
+                controls[i as usize] = temp[(i - off) as usize];
+                controls[j as usize] = temp[(j - off) as usize];
+                i += 1;
+                j -= 1;
+            }
+            first -= 1;
+            last += 1;
+            removed += 1;
+        }
+
+        if removed == 0 {
+            return 0;
+        }
+
+        for k in r + 1..=m {
+            self.knots[(k - removed) as usize] = self.knots[k as usize];
+        }
+        self.knots.truncate((m + 1 - removed) as usize);
+
+        let mut j = first_out;
+        let mut i = j;
+        for k in 1..removed {
+            if k % 2 == 1 {
+                i += 1;
+            } else {
+                j -= 1;
+            }
+        }
+        for k in i + 1..=n {
+            controls[j as usize] = controls[k as usize];
+            j += 1;
+        }
+        controls.truncate((n + 1 - removed) as usize);
+
+        removed as usize
+    }
+
+    /// Removes every interior knot as many times as the tolerance allows.
+    /// Each removal stays within the tolerance on its own, so every result is also
+    /// checked against the original curve and removed fewer times if the errors added up.
+    /// Returns the number of knots removed.
+    pub fn remove_knots(&mut self, tolerance: f32) -> usize {
+        let original = self.clone();
+        let params = deviation_params(&self.knots, self.degree);
+        let mut removed = 0;
+        for t in interior_knots(&self.knots, self.degree) {
+            removed += remove_checked(
+                self,
+                |curve, times| curve.remove_knot(t, times, tolerance),
+                |curve| {
+                    params
+                        .iter()
+                        .all(|t| Vec3::distance(&original.point(*t), &curve.point(*t)) <= tolerance)
+                },
+            );
+        }
+        removed
+    }
+}
+
+impl NurbsSurface {
+    /// Removes t from the u knots up to times times.
+    /// Every row has to stay within the tolerance.
+    pub fn remove_knot_u(&mut self, t: f32, times: usize, tolerance: f32) -> usize {
+        let curves = self.curves_u();
+        let Some(curves) = remove_knot_from_all(curves, t, times, tolerance) else {
+            return 0;
+        };
+        let removed = self.knots_u.len() - curves[0].knots.len();
+        *self = NurbsSurface::from_curves_u(curves, self.degree_v, self.knots_v.clone());
+        removed
+    }
+
+    /// Removes t from the v knots up to times times.
+    /// Every column has to stay within the tolerance.
+    pub fn remove_knot_v(&mut self, t: f32, times: usize, tolerance: f32) -> usize {
+        let curves = self.curves_v();
+        let Some(curves) = remove_knot_from_all(curves, t, times, tolerance) else {
+            return 0;
+        };
+        let removed = self.knots_v.len() - curves[0].knots.len();
+        *self = NurbsSurface::from_curves_v(curves, self.degree_u, self.knots_u.clone());
+        removed
+    }
+
+    /// Removes every interior knot in both directions as many times as the tolerance allows.
+    /// Checked against the original surface like NurbsCurve::remove_knots.
+    /// Returns the number of knots removed.
+    pub fn remove_knots(&mut self, tolerance: f32) -> usize {
+        let original = self.clone();
+        let params_u = deviation_params(&self.knots_u, self.degree_u);
+        let params_v = deviation_params(&self.knots_v, self.degree_v);
+        let within_tolerance = |candidate: &NurbsSurface| {
+            params_v.iter().all(|v| {
+                params_u.iter().all(|u| {
+                    Vec3::distance(&original.point(*u, *v), &candidate.point(*u, *v)) <= tolerance
+                })
+            })
+        };
+
+        let mut removed = 0;
+        for t in interior_knots(&self.knots_u, self.degree_u) {
+            removed += remove_checked(
+                self,
+                |surface, times| surface.remove_knot_u(t, times, tolerance),
+                within_tolerance,
+            );
+        }
+        for t in interior_knots(&self.knots_v, self.degree_v) {
+            removed += remove_checked(
+                self,
+                |surface, times| surface.remove_knot_v(t, times, tolerance),
+                within_tolerance,
+            );
+        }
+        removed
+    }
+}
+
+/// Removes a knot as many times as remove allows while the result stays within_tolerance,
+/// trying fewer times when it does not. Returns how many times it was removed.
+fn remove_checked<T: Clone>(
+    item: &mut T,
+    remove: impl Fn(&mut T, usize) -> usize,
+    within_tolerance: impl Fn(&T) -> bool,
+) -> usize {
+    let mut times = usize::MAX;
+    while times > 0 {
+        let mut candidate = item.clone();
+        let count = remove(&mut candidate, times);
+        if count == 0 {
+            break;
+        }
+        if within_tolerance(&candidate) {
+            *item = candidate;
+            return count;
+        }
+        times = count - 1;
+    }
+    0
+}
+
+/// Removes t the same number of times from every curve, so they keep sharing a knot vector.
+/// None when it can not be removed at all.
+fn remove_knot_from_all(
+    curves: Vec<NurbsCurve>,
+    t: f32,
+    times: usize,
+    tolerance: f32,
+) -> Option<Vec<NurbsCurve>> {
+    let mut times = times;
+    for curve in curves.iter() {
+        times = times.min(curve.clone().remove_knot(t, times, tolerance));
+        if times == 0 {
+            return None;
+        }
+    }
+    Some(
+        curves
+            .into_iter()
+            .map(|mut curve| {
+                curve.remove_knot(t, times, tolerance);
+                curve
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,73 @@
+//! CPU evaluation of rational b-splines.
+//!
+//! Uses the same representation as the GPU samplers:
+//! controls are weighted (x * w, y * w, z * w, w) and knot vectors are clamped.
+//! Surface controls are stored with u changing fastest.
+//!
+//! Algorithm numbers refer to The NURBS Book.
+
+use crate::math::linear_algebra::vec4::Vec4;
+
+pub mod basis;
+pub mod compatibility;
+pub mod curve;
+pub mod degree_elevation;
+pub mod fitting;
+pub mod interpolation;
+pub mod intersection;
+pub mod knot_insertion;
+pub mod knot_removal;
+pub mod offset;
+pub mod projection;
+pub mod surface;
+pub mod surface_intersection;
+pub mod trim;
+
+pub(crate) fn binomial(n: usize, k: usize) -> f32 {
+    let mut res = 1.0;
+    for i in 0..k {
+        res = res * (n - i) as f32 / (i + 1) as f32;
+    }
+    res
+}
+
+/// Solves matrix * x = rhs with partial pivoting, one Vec4 unknown per row.
+/// None when the matrix is singular.
+pub(crate) fn solve_linear_system(
+    mut matrix: Vec<Vec<f32>>,
+    mut rhs: Vec<Vec4>,
+) -> Option<Vec<Vec4>> {
+    let n = rhs.len();
+    for col in 0..n {
+        let pivot =
+            (col..n).max_by(|a, b| matrix[*a][col].abs().total_cmp(&matrix[*b][col].abs()))?;
+        if matrix[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+        let (pivot_rows, rows_below) = matrix.split_at_mut(col + 1);
+        let pivot_row = &pivot_rows[col];
+        for (offset, row) in rows_below.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            if factor == 0.0 {
+                continue;
+            }
+            for (value, pivot_value) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot_value;
+            }
+            let below = col + 1 + offset;
+            rhs[below] = Vec4::subtract(&rhs[below], &Vec4::to_scaled(&rhs[col], factor));
+        }
+    }
+
+    let mut res = vec![Vec4::default(); n];
+    for row in (0..n).rev() {
+        let mut sum = rhs[row];
+        for k in row + 1..n {
+            sum = Vec4::subtract(&sum, &Vec4::to_scaled(&res[k], matrix[row][k]));
+        }
+        res[row] = Vec4::to_scaled(&sum, 1.0 / matrix[row][row]);
+    }
+    Some(res)
+}
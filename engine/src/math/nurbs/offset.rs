@@ -0,0 +1,124 @@
+//! Offsets of surfaces, every point moved the same distance along the normal.
+//!
+//! The offset folds over itself where the surface curves towards the offset side
+//! with a radius smaller than the distance.
+
+use wasm_bindgen::prelude::*;
+
+use crate::math::linear_algebra::vec3::Vec3;
+
+use super::surface::NurbsSurface;
+
+/// Area of the first fundamental form below which the surface has no tangent plane
+const DEGENERATE_EPSILON: f32 = 1e-10;
+
+/// Stretch of the parameter domain where the offset folds over itself
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OffsetSelfIntersection {
+    pub min_u: f32,
+    pub min_v: f32,
+    pub max_u: f32,
+    pub max_v: f32,
+    /// Where the curvature radius is smallest
+    pub u: f32,
+    pub v: f32,
+    pub radius: f32,
+}
+
+impl NurbsSurface {
+    /// Principal curvatures, larger first, from the fundamental forms.
+    /// Positive where the surface bends towards its normal, the center of curvature is at point + normal / k.
+    /// None where there is no tangent plane, like at poles.
+    pub fn principal_curvatures(&self, u: f32, v: f32) -> Option<(f32, f32)> {
+        let ders = self.derivatives(u, v, 2);
+        let (su, sv) = (&ders[1][0], &ders[0][1]);
+        let e = Vec3::dot(su, su);
+        let f = Vec3::dot(su, sv);
+        let g = Vec3::dot(sv, sv);
+        let area = e * g - f * f;
+        if area <= DEGENERATE_EPSILON * e.max(g).powi(2) {
+            return None;
+        }
+        let normal = self.normal(u, v);
+        let l = Vec3::dot(&ders[2][0], &normal);
+        let m = Vec3::dot(&ders[1][1], &normal);
+        let n = Vec3::dot(&ders[0][2], &normal);
+
+        let mean = (e * n + g * l - 2.0 * f * m) / (2.0 * area);
+        let gaussian = (l * n - m * m) / area;
+        let spread = (mean * mean - gaussian).max(0.0).sqrt();
+        Some((mean + spread, mean - spread))
+    }
+
+    /// Regions of the grid of params where the offset by distance folds,
+    /// because the surface curves towards the offset side tighter than the distance.
+    /// Neighbouring grid points that fold make up one region.
+    pub fn offset_self_intersections(
+        &self,
+        params_u: &[f32],
+        params_v: &[f32],
+        distance: f32,
+    ) -> Vec<OffsetSelfIntersection> {
+        let count_u = params_u.len();
+        // Curvature radius at each grid point that folds
+        let radii: Vec<Option<f32>> = params_v
+            .iter()
+            .flat_map(|v| params_u.iter().map(move |u| (*u, *v)))
+            .map(|(u, v)| {
+                let (k1, k2) = self.principal_curvatures(u, v)?;
+                // Moving by distance along the normal reaches the center of curvature when distance * k >= 1
+                let k = if distance > 0.0 { k1 } else { k2 };
+                (distance * k >= 1.0).then(|| 1.0 / k.abs())
+            })
+            .collect();
+
+        let mut visited = vec![false; radii.len()];
+        let mut res = Vec::new();
+        for first in 0..radii.len() {
+            if visited[first] || radii[first].is_none() {
+                continue;
+            }
+            visited[first] = true;
+            let param = |index: usize| (params_u[index % count_u], params_v[index / count_u]);
+            let (u, v) = param(first);
+            let mut region = OffsetSelfIntersection {
+                min_u: u,
+                min_v: v,
+                max_u: u,
+                max_v: v,
+                u,
+                v,
+                radius: f32::INFINITY,
+            };
+            let mut stack = vec![first];
+            while let Some(index) = stack.pop() {
+                let (u, v) = param(index);
+                let radius = radii[index].unwrap();
+                region.min_u = region.min_u.min(u);
+                region.min_v = region.min_v.min(v);
+                region.max_u = region.max_u.max(u);
+                region.max_v = region.max_v.max(v);
+                if radius < region.radius {
+                    (region.u, region.v, region.radius) = (u, v, radius);
+                }
+
+                let (i_u, i_v) = (index % count_u, index / count_u);
+                let neighbours = [
+                    (i_u > 0).then(|| index - 1),
+                    (i_u + 1 < count_u).then(|| index + 1),
+                    (i_v > 0).then(|| index - count_u),
+                    (i_v + 1 < params_v.len()).then(|| index + count_u),
+                ];
+                for neighbour in neighbours.into_iter().flatten() {
+                    if !visited[neighbour] && radii[neighbour].is_some() {
+                        visited[neighbour] = true;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+            res.push(region);
+        }
+        res
+    }
+}
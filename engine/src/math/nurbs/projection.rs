@@ -0,0 +1,147 @@
+//! Closest points by Newton iteration, section 6.1.
+
+use crate::math::linear_algebra::vec3::Vec3;
+
+use super::{curve::NurbsCurve, surface::NurbsSurface};
+
+const MAX_ITERATIONS: usize = 32;
+/// Point coincidence, relative to the size of the coordinates
+const DISTANCE_EPSILON: f32 = 1e-6;
+/// Cosine of the angle between the derivatives and the vector to the point, zero at the closest point
+const COSINE_EPSILON: f32 = 1e-5;
+
+/// Moves a parameter by delta, wrapping round on closed curves and stopping at the ends on open ones.
+/// Returns the new parameter and how far it really moved.
+fn step_param(t: f32, delta: f32, start: f32, end: f32, closed: bool) -> (f32, f32) {
+    if closed {
+        (start + (t + delta - start).rem_euclid(end - start), delta)
+    } else {
+        let next = (t + delta).clamp(start, end);
+        (next, next - t)
+    }
+}
+
+impl NurbsCurve {
+    /// Parameter and point of the curve closest to point.
+    /// Starts from the nearest of the seed parameters, which should cover the domain finely
+    /// enough that the nearest one is in the basin of the true closest point.
+    /// On closed curves the iteration can cross the seam.
+    pub fn closest_point(&self, point: &Vec3, seeds: &[f32]) -> (f32, Vec3) {
+        let (start, end) = self.domain();
+        let seed = seeds
+            .iter()
+            .map(|t| (*t, self.point(*t)))
+            .min_by(|a, b| Vec3::distance(&a.1, point).total_cmp(&Vec3::distance(&b.1, point)))
+            .unwrap_or((start, self.point(start)));
+        let epsilon = DISTANCE_EPSILON * (1.0 + point.len());
+        let closed = Vec3::distance(&self.point(start), &self.point(end)) <= epsilon;
+
+        // Eq 6.3, Newton on f(t) = C'(t) . (C(t) - P)
+        let mut t = seed.0;
+        for _ in 0..MAX_ITERATIONS {
+            let derivatives = self.derivatives(t, 2);
+            let to_curve = Vec3::subtract(&derivatives[0], point);
+            let distance = to_curve.len();
+            let speed = derivatives[1].len();
+            if distance <= epsilon || speed == 0.0 {
+                break;
+            }
+            let f = Vec3::dot(&derivatives[1], &to_curve);
+            if f.abs() <= COSINE_EPSILON * speed * distance {
+                break;
+            }
+            let f_prime = Vec3::dot(&derivatives[2], &to_curve) + speed * speed;
+            if f_prime == 0.0 {
+                break;
+            }
+            let (next, moved) = step_param(t, -f / f_prime, start, end, closed);
+            t = next;
+            if (moved * speed).abs() <= epsilon {
+                break;
+            }
+        }
+
+        // Newton can wander off to a worse local minimum, never do worse than the seed
+        let res = self.point(t);
+        if Vec3::distance(&res, point) <= Vec3::distance(&seed.1, point) {
+            (t, res)
+        } else {
+            seed
+        }
+    }
+}
+
+impl NurbsSurface {
+    /// Parameters and point of the surface closest to point.
+    /// Starts from the nearest point of the grid of seed parameters.
+    pub fn closest_point(
+        &self,
+        point: &Vec3,
+        seeds_u: &[f32],
+        seeds_v: &[f32],
+    ) -> (f32, f32, Vec3) {
+        let (start_u, end_u) = self.domain_u();
+        let (start_v, end_v) = self.domain_v();
+        let seed = seeds_v
+            .iter()
+            .flat_map(|v| seeds_u.iter().map(move |u| (*u, *v)))
+            .map(|(u, v)| (u, v, self.point(u, v)))
+            .min_by(|a, b| Vec3::distance(&a.2, point).total_cmp(&Vec3::distance(&b.2, point)))
+            .unwrap_or((start_u, start_v, self.point(start_u, start_v)));
+        let epsilon = DISTANCE_EPSILON * (1.0 + point.len());
+        let closed_u = [start_v, (start_v + end_v) / 2.0, end_v]
+            .iter()
+            .all(|v| Vec3::distance(&self.point(start_u, *v), &self.point(end_u, *v)) <= epsilon);
+        let closed_v = [start_u, (start_u + end_u) / 2.0, end_u]
+            .iter()
+            .all(|u| Vec3::distance(&self.point(*u, start_v), &self.point(*u, end_v)) <= epsilon);
+
+        // Eq 6.6 to 6.8, Newton on f = S_u . r and g = S_v . r with r = S(u, v) - P
+        let (mut u, mut v) = (seed.0, seed.1);
+        for _ in 0..MAX_ITERATIONS {
+            let derivatives = self.derivatives(u, v, 2);
+            let s_u = &derivatives[1][0];
+            let s_v = &derivatives[0][1];
+            let r = Vec3::subtract(&derivatives[0][0], point);
+            let distance = r.len();
+            if distance <= epsilon {
+                break;
+            }
+            let f = Vec3::dot(s_u, &r);
+            let g = Vec3::dot(s_v, &r);
+            if f.abs() <= COSINE_EPSILON * s_u.len() * distance
+                && g.abs() <= COSINE_EPSILON * s_v.len() * distance
+            {
+                break;
+            }
+
+            let a = Vec3::dot(s_u, s_u) + Vec3::dot(&r, &derivatives[2][0]);
+            let b = Vec3::dot(s_u, s_v) + Vec3::dot(&r, &derivatives[1][1]);
+            let d = Vec3::dot(s_v, s_v) + Vec3::dot(&r, &derivatives[0][2]);
+            let determinant = a * d - b * b;
+            if determinant == 0.0 {
+                break;
+            }
+            let delta_u = (-f * d + g * b) / determinant;
+            let delta_v = (-g * a + f * b) / determinant;
+            let (next_u, moved_u) = step_param(u, delta_u, start_u, end_u, closed_u);
+            let (next_v, moved_v) = step_param(v, delta_v, start_v, end_v, closed_v);
+            let step = Vec3::add(
+                &Vec3::to_scaled(s_u, moved_u),
+                &Vec3::to_scaled(s_v, moved_v),
+            );
+            (u, v) = (next_u, next_v);
+            if step.len() <= epsilon {
+                break;
+            }
+        }
+
+        // Never do worse than the seed
+        let res = self.point(u, v);
+        if Vec3::distance(&res, point) <= Vec3::distance(&seed.2, point) {
+            (u, v, res)
+        } else {
+            seed
+        }
+    }
+}
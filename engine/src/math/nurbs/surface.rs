@@ -0,0 +1,261 @@
+use crate::math::linear_algebra::{mat4::Mat4, vec3::Vec3, vec4::Vec4};
+
+use wasm_bindgen::prelude::*;
+
+use super::{
+    basis::{basis_function_derivatives, basis_functions, find_span},
+    binomial,
+    curve::NurbsCurve,
+};
+
+/// One of the two parameter directions of a surface
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SurfaceDirection {
+    U = 0,
+    V = 1,
+}
+
+/// Rational b-spline surface, same representation as geometry::surface::Surface.
+/// Controls are stored with u changing fastest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NurbsSurface {
+    pub degree_u: u32,
+    pub degree_v: u32,
+    pub control_count_u: u32,
+    pub control_count_v: u32,
+    pub weighted_controls: Vec<Vec4>,
+    pub knots_u: Vec<f32>,
+    pub knots_v: Vec<f32>,
+}
+
+impl NurbsSurface {
+    pub fn new(
+        degree_u: u32,
+        degree_v: u32,
+        control_count_u: u32,
+        control_count_v: u32,
+        weighted_controls: Vec<Vec4>,
+        knots_u: Vec<f32>,
+        knots_v: Vec<f32>,
+    ) -> Self {
+        debug_assert_eq!(
+            weighted_controls.len(),
+            (control_count_u * control_count_v) as usize
+        );
+        debug_assert_eq!(knots_u.len(), (control_count_u + degree_u + 1) as usize);
+        debug_assert_eq!(knots_v.len(), (control_count_v + degree_v + 1) as usize);
+        Self {
+            degree_u,
+            degree_v,
+            control_count_u,
+            control_count_v,
+            weighted_controls,
+            knots_u,
+            knots_v,
+        }
+    }
+
+    pub fn get_control(&self, i_u: usize, i_v: usize) -> &Vec4 {
+        &self.weighted_controls[i_u + i_v * self.control_count_u as usize]
+    }
+
+    /// Applies m to every control, exact for affine transforms
+    pub fn transform(&mut self, m: &Mat4) {
+        for control in self.weighted_controls.iter_mut() {
+            *control = m.transform(control);
+        }
+    }
+
+    /// One curve per row of controls, running in u
+    pub fn curves_u(&self) -> Vec<NurbsCurve> {
+        let count_u = self.control_count_u as usize;
+        self.weighted_controls
+            .chunks(count_u)
+            .map(|row| NurbsCurve::new(self.degree_u, row.to_vec(), self.knots_u.clone()))
+            .collect()
+    }
+
+    /// One curve per column of controls, running in v
+    pub fn curves_v(&self) -> Vec<NurbsCurve> {
+        (0..self.control_count_u as usize)
+            .map(|i_u| {
+                let column = (0..self.control_count_v as usize)
+                    .map(|i_v| *self.get_control(i_u, i_v))
+                    .collect();
+                NurbsCurve::new(self.degree_v, column, self.knots_v.clone())
+            })
+            .collect()
+    }
+
+    /// Inverse of curves_u, the curves must share degree and knots
+    pub fn from_curves_u(curves: Vec<NurbsCurve>, degree_v: u32, knots_v: Vec<f32>) -> Self {
+        let control_count_v = curves.len() as u32;
+        let degree_u = curves[0].degree;
+        let knots_u = curves[0].knots.clone();
+        let control_count_u = curves[0].weighted_controls.len() as u32;
+        let weighted_controls = curves
+            .into_iter()
+            .flat_map(|curve| curve.weighted_controls)
+            .collect();
+        Self::new(
+            degree_u,
+            degree_v,
+            control_count_u,
+            control_count_v,
+            weighted_controls,
+            knots_u,
+            knots_v,
+        )
+    }
+
+    /// Inverse of curves_v, the curves must share degree and knots
+    pub fn from_curves_v(curves: Vec<NurbsCurve>, degree_u: u32, knots_u: Vec<f32>) -> Self {
+        let control_count_u = curves.len();
+        let degree_v = curves[0].degree;
+        let knots_v = curves[0].knots.clone();
+        let control_count_v = curves[0].weighted_controls.len();
+        let mut weighted_controls = Vec::with_capacity(control_count_u * control_count_v);
+        for i_v in 0..control_count_v {
+            for curve in curves.iter() {
+                weighted_controls.push(curve.weighted_controls[i_v]);
+            }
+        }
+        Self::new(
+            degree_u,
+            degree_v,
+            control_count_u as u32,
+            control_count_v as u32,
+            weighted_controls,
+            knots_u,
+            knots_v,
+        )
+    }
+
+    /// First and last u parameter
+    pub fn domain_u(&self) -> (f32, f32) {
+        (
+            self.knots_u[self.degree_u as usize],
+            self.knots_u[self.knots_u.len() - self.degree_u as usize - 1],
+        )
+    }
+
+    /// First and last v parameter
+    pub fn domain_v(&self) -> (f32, f32) {
+        (
+            self.knots_v[self.degree_v as usize],
+            self.knots_v[self.knots_v.len() - self.degree_v as usize - 1],
+        )
+    }
+
+    /// Weighted point, A4.3
+    pub fn homogeneous_point(&self, u: f32, v: f32) -> Vec4 {
+        let span_u = find_span(self.degree_u, &self.knots_u, u);
+        let span_v = find_span(self.degree_v, &self.knots_v, v);
+        let basis_u = basis_functions(span_u, u, self.degree_u, &self.knots_u);
+        let basis_v = basis_functions(span_v, v, self.degree_v, &self.knots_v);
+        let first_u = span_u - self.degree_u as usize;
+        let first_v = span_v - self.degree_v as usize;
+        let mut res = Vec4::default();
+        for (j, b_v) in basis_v.iter().enumerate() {
+            for (i, b_u) in basis_u.iter().enumerate() {
+                let control = self.get_control(first_u + i, first_v + j);
+                let b = b_u * b_v;
+                res.x += control.x * b;
+                res.y += control.y * b;
+                res.z += control.z * b;
+                res.w += control.w * b;
+            }
+        }
+        res
+    }
+
+    pub fn point(&self, u: f32, v: f32) -> Vec3 {
+        self.homogeneous_point(u, v).to_vec3_safe()
+    }
+
+    /// Derivatives of the weighted surface.
+    /// Result k, l is differentiated k times in u and l times in v, A3.6
+    pub fn homogeneous_derivatives(
+        &self,
+        u: f32,
+        v: f32,
+        derivative_count: usize,
+    ) -> Vec<Vec<Vec4>> {
+        let span_u = find_span(self.degree_u, &self.knots_u, u);
+        let span_v = find_span(self.degree_v, &self.knots_v, v);
+        let ders_u =
+            basis_function_derivatives(span_u, u, self.degree_u, &self.knots_u, derivative_count);
+        let ders_v =
+            basis_function_derivatives(span_v, v, self.degree_v, &self.knots_v, derivative_count);
+        let first_u = span_u - self.degree_u as usize;
+        let first_v = span_v - self.degree_v as usize;
+
+        let mut res = vec![vec![Vec4::default(); derivative_count + 1]; derivative_count + 1];
+        for k in 0..=derivative_count {
+            for l in 0..=derivative_count - k {
+                let mut sum = Vec4::default();
+                for (j, b_v) in ders_v[l].iter().enumerate() {
+                    for (i, b_u) in ders_u[k].iter().enumerate() {
+                        let control = self.get_control(first_u + i, first_v + j);
+                        let b = b_u * b_v;
+                        sum.x += control.x * b;
+                        sum.y += control.y * b;
+                        sum.z += control.z * b;
+                        sum.w += control.w * b;
+                    }
+                }
+                res[k][l] = sum;
+            }
+        }
+        res
+    }
+
+    /// Point and derivatives.
+    /// Result k, l is differentiated k times in u and l times in v, A4.4.
+    /// Only entries with k + l <= derivative_count are filled.
+    pub fn derivatives(&self, u: f32, v: f32, derivative_count: usize) -> Vec<Vec<Vec3>> {
+        let homogeneous = self.homogeneous_derivatives(u, v, derivative_count);
+        let w = |k: usize, l: usize| homogeneous[k][l].w;
+        let mut res = vec![vec![Vec3::default(); derivative_count + 1]; derivative_count + 1];
+        for k in 0..=derivative_count {
+            for l in 0..=derivative_count - k {
+                let a = &homogeneous[k][l];
+                let mut v = Vec3::new(a.x, a.y, a.z);
+                for j in 1..=l {
+                    v = Vec3::subtract(
+                        &v,
+                        &Vec3::to_scaled(&res[k][l - j], binomial(l, j) * w(0, j)),
+                    );
+                }
+                for i in 1..=k {
+                    v = Vec3::subtract(
+                        &v,
+                        &Vec3::to_scaled(&res[k - i][l], binomial(k, i) * w(i, 0)),
+                    );
+                    let mut v2 = Vec3::default();
+                    for j in 1..=l {
+                        v2 = Vec3::add(
+                            &v2,
+                            &Vec3::to_scaled(&res[k - i][l - j], binomial(l, j) * w(i, j)),
+                        );
+                    }
+                    v = Vec3::subtract(&v, &Vec3::to_scaled(&v2, binomial(k, i)));
+                }
+                res[k][l] = Vec3::to_scaled(&v, 1.0 / w(0, 0));
+            }
+        }
+        res
+    }
+
+    /// Unit normal, the cross product of the u and v partials.
+    /// Zero where the partials are parallel or vanish.
+    pub fn normal(&self, u: f32, v: f32) -> Vec3 {
+        let ders = self.derivatives(u, v, 1);
+        let n = Vec3::cross(&ders[1][0], &ders[0][1]);
+        if n.len() == 0.0 {
+            return n;
+        }
+        n.to_normalized()
+    }
+}
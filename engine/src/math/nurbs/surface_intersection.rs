@@ -0,0 +1,472 @@
+//! Intersection curves of two surfaces by marching.
+//!
+//! Each seed, a guess at parameters on both surfaces, is pulled onto the intersection by Newton iteration.
+//! From there the curve is traced both ways, stepping along the cross product of the normals
+//! and pulling every predicted point back onto both surfaces in the plane across the step.
+//! Steps shrink where the curve turns, so the chords stay within tolerance of it,
+//! and the trace ends on the edge of either domain or when it closes up.
+//! Seeds landing on a curve that was already traced are skipped.
+
+use crate::math::{
+    geometry::bounding_box::BoundingBox,
+    linear_algebra::{vec3::Vec3, vec4::Vec4},
+};
+
+use super::{
+    curve::NurbsCurve,
+    interpolation::{
+        create_params, interpolate, interpolate_closed, point_distances, Parameterization,
+    },
+    solve_linear_system,
+    surface::NurbsSurface,
+};
+
+const MAX_ITERATIONS: usize = 16;
+/// Convergence of Newton iteration, relative to the size of the coordinates
+const DISTANCE_EPSILON: f32 = 1e-6;
+/// Points traced each way from a seed
+const MAX_POINTS: usize = 256;
+/// Largest turn in radians between neighbouring points, keeps the trace on one branch
+const MAX_TURN: f32 = 0.2;
+/// First and largest step, as fractions of the size of the smaller surface
+const FIRST_STEP: f32 = 0.02;
+const MAX_STEP: f32 = 0.1;
+/// Smallest step, as a fraction of the tolerance
+const MIN_STEP: f32 = 1e-2;
+
+/// One intersection curve, with where it runs on each surface
+#[derive(Debug, Clone)]
+pub struct SurfaceIntersectionCurve {
+    pub curve: NurbsCurve,
+    /// (u, v) on the first surface in x and y, with the same parameterization as curve
+    pub curve_a: NurbsCurve,
+    /// (u, v) on the second surface
+    pub curve_b: NurbsCurve,
+    pub closed: bool,
+}
+
+/// u and v on the first surface, then u and v on the second
+pub type SurfaceIntersectionParams = [f32; 4];
+
+enum Solution {
+    Converged(SurfaceIntersectionParams),
+    /// Newton ran into the edge of a domain here before converging
+    Boundary(SurfaceIntersectionParams),
+    Failed,
+}
+
+/// Points along one intersection curve, in order
+struct Trace {
+    points: Vec<Vec3>,
+    params: Vec<SurfaceIntersectionParams>,
+    closed: bool,
+}
+
+struct SurfacePair<'a> {
+    a: &'a NurbsSurface,
+    b: &'a NurbsSurface,
+    lower: SurfaceIntersectionParams,
+    upper: SurfaceIntersectionParams,
+}
+
+/// Point on each surface and the partials of their difference
+struct Evaluation {
+    point_a: Vec3,
+    point_b: Vec3,
+    partials: [Vec3; 4],
+}
+
+impl Evaluation {
+    fn difference(&self) -> Vec3 {
+        Vec3::subtract(&self.point_a, &self.point_b)
+    }
+
+    /// Direction of the intersection curve, None where the surfaces touch
+    fn tangent(&self) -> Option<Vec3> {
+        let normal_a = Vec3::cross(&self.partials[0], &self.partials[1]);
+        let normal_b = Vec3::cross(&self.partials[2], &self.partials[3]);
+        let tangent = Vec3::cross(&normal_a, &normal_b);
+        let scale = normal_a.len() * normal_b.len();
+        (tangent.len() > 1e-4 * scale).then(|| tangent.to_normalized())
+    }
+}
+
+fn components(v: &Vec3) -> [f32; 3] {
+    [v.x, v.y, v.z]
+}
+
+/// Solves small dense systems, one f32 unknown per row
+fn solve(matrix: Vec<Vec<f32>>, rhs: &[f32]) -> Option<Vec<f32>> {
+    let rhs = rhs.iter().map(|r| Vec4::new(*r, 0.0, 0.0, 0.0)).collect();
+    solve_linear_system(matrix, rhs).map(|res| res.iter().map(|r| r.x).collect())
+}
+
+impl<'a> SurfacePair<'a> {
+    fn new(a: &'a NurbsSurface, b: &'a NurbsSurface) -> Self {
+        let (a_u, a_v, b_u, b_v) = (a.domain_u(), a.domain_v(), b.domain_u(), b.domain_v());
+        Self {
+            a,
+            b,
+            lower: [a_u.0, a_v.0, b_u.0, b_v.0],
+            upper: [a_u.1, a_v.1, b_u.1, b_v.1],
+        }
+    }
+
+    fn evaluate(&self, x: &SurfaceIntersectionParams) -> Evaluation {
+        let a = self.a.derivatives(x[0], x[1], 1);
+        let b = self.b.derivatives(x[2], x[3], 1);
+        Evaluation {
+            point_a: a[0][0],
+            point_b: b[0][0],
+            partials: [
+                a[1][0],
+                a[0][1],
+                Vec3::to_scaled(&b[1][0], -1.0),
+                Vec3::to_scaled(&b[0][1], -1.0),
+            ],
+        }
+    }
+
+    /// Newton iteration on S_a - S_b = 0, taking the smallest step that solves the linearized system.
+    /// A plane, given as a point and normal, adds the condition that S_a lies in it.
+    /// A fixed parameter is left where it is.
+    fn solve(
+        &self,
+        mut x: SurfaceIntersectionParams,
+        plane: Option<(Vec3, Vec3)>,
+        fixed: Option<usize>,
+    ) -> Solution {
+        let mut hit_boundary = false;
+        for _ in 0..MAX_ITERATIONS {
+            let evaluation = self.evaluate(&x);
+            let difference = evaluation.difference();
+            let epsilon = DISTANCE_EPSILON * (1.0 + evaluation.point_a.len());
+            let mut rows: Vec<([f32; 4], f32)> = (0..3)
+                .map(|k| {
+                    let row = evaluation.partials.map(|partial| components(&partial)[k]);
+                    (row, components(&difference)[k])
+                })
+                .collect();
+            if let Some((origin, normal)) = plane {
+                let along = Vec3::dot(&normal, &evaluation.partials[0]);
+                let across = Vec3::dot(&normal, &evaluation.partials[1]);
+                let offset = Vec3::dot(&normal, &Vec3::subtract(&evaluation.point_a, &origin));
+                rows.push(([along, across, 0.0, 0.0], offset));
+            }
+            if let Some(fixed) = fixed {
+                rows.iter_mut().for_each(|(row, _)| row[fixed] = 0.0);
+            }
+            if rows.iter().all(|(_, residual)| residual.abs() <= epsilon) {
+                return if hit_boundary {
+                    Solution::Boundary(x)
+                } else {
+                    Solution::Converged(x)
+                };
+            }
+
+            // Minimum norm step, J^T (J J^T)^-1 (-F)
+            let gram = rows
+                .iter()
+                .map(|(a, _)| {
+                    rows.iter()
+                        .map(|(b, _)| (0..4).map(|k| a[k] * b[k]).sum())
+                        .collect()
+                })
+                .collect();
+            let residuals: Vec<f32> = rows.iter().map(|(_, residual)| -residual).collect();
+            let Some(multipliers) = solve(gram, &residuals) else {
+                return Solution::Failed;
+            };
+            let step: [f32; 4] = std::array::from_fn(|k| {
+                rows.iter()
+                    .zip(multipliers.iter())
+                    .map(|((row, _), m)| row[k] * m)
+                    .sum()
+            });
+
+            // Stop at the edge of the domains
+            let mut scale: f32 = 1.0;
+            for k in 0..4 {
+                if x[k] + step[k] < self.lower[k] {
+                    scale = scale.min((self.lower[k] - x[k]) / step[k]);
+                } else if x[k] + step[k] > self.upper[k] {
+                    scale = scale.min((self.upper[k] - x[k]) / step[k]);
+                }
+            }
+            if scale < 1.0 {
+                hit_boundary = true;
+            }
+            for k in 0..4 {
+                x[k] = (x[k] + step[k] * scale).clamp(self.lower[k], self.upper[k]);
+            }
+            if scale <= 0.0 {
+                return Solution::Boundary(x);
+            }
+        }
+        if hit_boundary {
+            Solution::Boundary(x)
+        } else {
+            Solution::Failed
+        }
+    }
+
+    /// Parameter steps that move each surface by offset, least squares on the partials
+    fn predict(
+        &self,
+        x: &SurfaceIntersectionParams,
+        evaluation: &Evaluation,
+        offset: &Vec3,
+    ) -> SurfaceIntersectionParams {
+        let mut res = *x;
+        for (first, partials) in [
+            (0, &evaluation.partials[0..2]),
+            (2, &evaluation.partials[2..4]),
+        ] {
+            // The partials of the second surface are negated in the evaluation
+            let sign = if first == 0 { 1.0 } else { -1.0 };
+            let matrix = vec![
+                vec![
+                    Vec3::dot(&partials[0], &partials[0]),
+                    Vec3::dot(&partials[0], &partials[1]),
+                ],
+                vec![
+                    Vec3::dot(&partials[1], &partials[0]),
+                    Vec3::dot(&partials[1], &partials[1]),
+                ],
+            ];
+            let rhs = [
+                sign * Vec3::dot(&partials[0], offset),
+                sign * Vec3::dot(&partials[1], offset),
+            ];
+            if let Some(step) = solve(matrix, &rhs) {
+                res[first] += step[0];
+                res[first + 1] += step[1];
+            }
+        }
+        std::array::from_fn(|k| res[k].clamp(self.lower[k], self.upper[k]))
+    }
+
+    /// Traces the curve through x both ways
+    fn trace(&self, x: SurfaceIntersectionParams, tolerance: f32, size: f32) -> Trace {
+        let start = self.evaluate(&x).point_a;
+        let (forward, closed) = self.march(x, 1.0, tolerance, size);
+        if closed {
+            let mut params = vec![x];
+            params.extend(forward.iter().map(|(x, _)| *x));
+            let mut points = vec![start];
+            points.extend(forward.iter().map(|(_, point)| *point));
+            return Trace {
+                points,
+                params,
+                closed,
+            };
+        }
+        let (backward, _) = self.march(x, -1.0, tolerance, size);
+        let ordered: Vec<(SurfaceIntersectionParams, Vec3)> = backward
+            .into_iter()
+            .rev()
+            .chain(std::iter::once((x, start)))
+            .chain(forward)
+            .collect();
+        // Both ends on a seam of a closed surface, at the same point
+        let closed = ordered.len() > 3
+            && Vec3::distance(&ordered[0].1, &ordered[ordered.len() - 1].1) <= tolerance;
+        Trace {
+            points: ordered.iter().map(|(_, point)| *point).collect(),
+            params: ordered.iter().map(|(x, _)| *x).collect(),
+            closed,
+        }
+    }
+
+    /// Steps from x along sign times the tangent until the curve leaves a domain or closes up.
+    /// Returns the points after x, and whether the curve closed, in which case the last point is x again.
+    fn march(
+        &self,
+        x: SurfaceIntersectionParams,
+        sign: f32,
+        tolerance: f32,
+        size: f32,
+    ) -> (Vec<(SurfaceIntersectionParams, Vec3)>, bool) {
+        let mut res = Vec::new();
+        let start = x;
+        let start_point = self.evaluate(&x).point_a;
+        let min_step = MIN_STEP * tolerance;
+        let max_step = MAX_STEP * size;
+        let mut step = FIRST_STEP * size;
+        let mut x = x;
+
+        while res.len() < MAX_POINTS && step >= min_step {
+            let evaluation = self.evaluate(&x);
+            let Some(tangent) = evaluation.tangent() else {
+                break;
+            };
+            let tangent = Vec3::to_scaled(&tangent, sign);
+            let point = evaluation.point_a;
+            let offset = Vec3::to_scaled(&tangent, step);
+            let predicted = self.predict(&x, &evaluation, &offset);
+            let plane = (Vec3::add(&point, &offset), tangent);
+
+            let (next, at_end) = match self.solve(predicted, Some(plane), None) {
+                Solution::Converged(next) => (next, false),
+                Solution::Boundary(near) => {
+                    // Pin whichever parameter reached the edge and find where the curve leaves
+                    let pinned =
+                        (0..4).find(|k| near[*k] == self.lower[*k] || near[*k] == self.upper[*k]);
+                    match pinned.map(|k| self.solve(near, None, Some(k))) {
+                        Some(Solution::Converged(next)) | Some(Solution::Boundary(next)) => {
+                            (next, true)
+                        }
+                        _ => {
+                            step /= 2.0;
+                            continue;
+                        }
+                    }
+                }
+                Solution::Failed => {
+                    step /= 2.0;
+                    continue;
+                }
+            };
+
+            let next_evaluation = self.evaluate(&next);
+            let next_point = next_evaluation.point_a;
+            let distance = Vec3::distance(&next_point, &point);
+            let turn = next_evaluation.tangent().map_or(0.0, |next_tangent| {
+                Vec3::angle_between(&tangent, &Vec3::to_scaled(&next_tangent, sign))
+            });
+            // The chord of an arc of length s turning by a is about s * a / 8 away from it
+            let deviation = distance * turn / 8.0;
+            let converged = next_evaluation.difference().len()
+                <= tolerance.max(DISTANCE_EPSILON * (1.0 + next_point.len()));
+            if !converged || distance > 2.0 * step || turn > MAX_TURN || deviation > tolerance {
+                step /= 2.0;
+                continue;
+            }
+            if at_end {
+                if distance > 0.01 * min_step {
+                    res.push((next, next_point));
+                }
+                break;
+            }
+
+            // Closed once the curve comes back round to the start
+            let to_start = Vec3::distance(&next_point, &start_point);
+            if res.len() > 2 && to_start <= distance && to_start < step {
+                res.push((start, start_point));
+                return (res, true);
+            }
+
+            res.push((next, next_point));
+            x = next;
+            if deviation < tolerance / 4.0 && turn < MAX_TURN / 2.0 {
+                step = (step * 1.5).min(max_step);
+            }
+        }
+        if res.len() >= MAX_POINTS {
+            log::warn!(
+                "surface intersection curve cut short after {} points",
+                MAX_POINTS
+            );
+        }
+        (res, false)
+    }
+}
+
+/// Distance from point to the polyline
+fn distance_to_polyline(points: &[Vec3], point: &Vec3) -> f32 {
+    points
+        .windows(2)
+        .map(|pair| {
+            let segment = Vec3::subtract(&pair[1], &pair[0]);
+            let length_squared = Vec3::dot(&segment, &segment);
+            let t = if length_squared == 0.0 {
+                0.0
+            } else {
+                (Vec3::dot(&Vec3::subtract(point, &pair[0]), &segment) / length_squared)
+                    .clamp(0.0, 1.0)
+            };
+            Vec3::distance(point, &Vec3::add(&pair[0], &Vec3::to_scaled(&segment, t)))
+        })
+        .fold(f32::MAX, f32::min)
+}
+
+fn surface_size(surface: &NurbsSurface) -> f32 {
+    BoundingBox::from_points(
+        surface
+            .weighted_controls
+            .iter()
+            .map(|control| control.to_vec3_safe()),
+    )
+    .map_or(0.0, |bounding_box| bounding_box.diagonal())
+}
+
+/// Cubics through the traced points, in space and on each surface, sharing one parameterization.
+/// Closed traces give closed curves, apart from (u, v) curves that cross a seam.
+fn fit_trace(trace: &Trace) -> Option<SurfaceIntersectionCurve> {
+    if trace.points.len() < 2 {
+        return None;
+    }
+    let params = create_params(
+        &point_distances(&trace.points, false),
+        Parameterization::ChordLength,
+    );
+    // The last point of a closed trace is back at the first
+    let closed = trace.closed && trace.points.len() > 3;
+    let fit = |points: &[Vec4], closed: bool| {
+        if closed {
+            interpolate_closed(&points[..points.len() - 1], &params, 3)
+        } else {
+            interpolate(points, &params, 3)
+        }
+    };
+    let (first, last) = (trace.params[0], trace.params[trace.params.len() - 1]);
+
+    let points: Vec<Vec4> = trace.points.iter().map(|point| point.append(1.0)).collect();
+    let on_a: Vec<Vec4> = trace
+        .params
+        .iter()
+        .map(|x| Vec4::new(x[0], x[1], 0.0, 1.0))
+        .collect();
+    let on_b: Vec<Vec4> = trace
+        .params
+        .iter()
+        .map(|x| Vec4::new(x[2], x[3], 0.0, 1.0))
+        .collect();
+    Some(SurfaceIntersectionCurve {
+        curve: fit(&points, closed)?,
+        curve_a: fit(&on_a, closed && first[..2] == last[..2])?,
+        curve_b: fit(&on_b, closed && first[2..] == last[2..])?,
+        closed: trace.closed,
+    })
+}
+
+/// Intersection curves of two surfaces, traced from the seeds so chords between traced points
+/// stay within tolerance of the exact curve.
+/// Seeds that do not converge onto both surfaces are skipped, so they can be rough.
+pub fn intersect_surfaces(
+    a: &NurbsSurface,
+    b: &NurbsSurface,
+    seeds: &[SurfaceIntersectionParams],
+    tolerance: f32,
+) -> Vec<SurfaceIntersectionCurve> {
+    let pair = SurfacePair::new(a, b);
+    let size = surface_size(a).min(surface_size(b));
+    if size == 0.0 || tolerance <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut traces: Vec<Trace> = Vec::new();
+    for seed in seeds {
+        let Solution::Converged(x) = pair.solve(*seed, None, None) else {
+            continue;
+        };
+        let point = pair.evaluate(&x).point_a;
+        let traced = traces
+            .iter()
+            .any(|trace| distance_to_polyline(&trace.points, &point) <= 2.0 * tolerance);
+        if !traced {
+            traces.push(pair.trace(x, tolerance, size));
+        }
+    }
+    traces.iter().filter_map(fit_trace).collect()
+}
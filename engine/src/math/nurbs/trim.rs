@@ -0,0 +1,121 @@
+//! Trimming loops of a surface, closed chains of curves in its (u, v) parameter space.
+//!
+//! Trim curves keep u in x and v in y, z is ignored.
+//! Sampled loops are oriented so the kept region is on their left,
+//! the outer loop counterclockwise and inner loops clockwise.
+
+use super::{curve::NurbsCurve, surface::SurfaceDirection};
+
+/// Curves joined end to end, the last one ending where the first starts
+#[derive(Debug, Clone)]
+pub struct TrimLoop {
+    pub curves: Vec<NurbsCurve>,
+}
+
+impl TrimLoop {
+    pub fn new(curves: Vec<NurbsCurve>) -> Self {
+        Self { curves }
+    }
+}
+
+/// Kept region of a surface, inside the outer loop and outside every inner loop.
+/// Without an outer loop the whole domain is the outer boundary.
+#[derive(Debug, Clone, Default)]
+pub struct SurfaceTrims {
+    pub outer: Option<TrimLoop>,
+    pub inner: Vec<TrimLoop>,
+}
+
+impl SurfaceTrims {
+    pub fn new(outer: Option<TrimLoop>, inner: Vec<TrimLoop>) -> Self {
+        Self { outer, inner }
+    }
+
+    /// Whether (u, v) is in the kept region of the loops from gpu_samplers::trim::sample_trims
+    pub fn contains(&self, polylines: &[Vec<[f32; 2]>], point: [f32; 2]) -> bool {
+        let implicit_outer = if self.outer.is_some() { 0 } else { 1 };
+        let winding: i32 = polylines
+            .iter()
+            .map(|polyline| winding_number(polyline, point))
+            .sum();
+        winding + implicit_outer > 0
+    }
+
+    /// Parts of the iso line in direction, with the other parameter at param, that are in the kept region.
+    /// Intervals run along direction, in order, and are clamped to start and end.
+    pub fn kept_intervals(
+        &self,
+        polylines: &[Vec<[f32; 2]>],
+        direction: SurfaceDirection,
+        param: f32,
+        (start, end): (f32, f32),
+    ) -> Vec<(f32, f32)> {
+        let (along, across) = match direction {
+            SurfaceDirection::U => (0, 1),
+            SurfaceDirection::V => (1, 0),
+        };
+        let mut cuts = vec![start, end];
+        for polyline in polylines {
+            let n = polyline.len();
+            for i in 0..n {
+                let (p0, p1) = (polyline[i], polyline[(i + 1) % n]);
+                if (p0[across] <= param) != (p1[across] <= param) {
+                    let t = (param - p0[across]) / (p1[across] - p0[across]);
+                    let cut = p0[along] + t * (p1[along] - p0[along]);
+                    if cut > start && cut < end {
+                        cuts.push(cut);
+                    }
+                }
+            }
+        }
+        cuts.sort_by(f32::total_cmp);
+        cuts.dedup();
+
+        let mut res: Vec<(f32, f32)> = Vec::new();
+        for pair in cuts.windows(2) {
+            let mut middle = [0.0; 2];
+            middle[along] = (pair[0] + pair[1]) / 2.0;
+            middle[across] = param;
+            if !self.contains(polylines, middle) {
+                continue;
+            }
+            match res.last_mut() {
+                Some(last) if last.1 == pair[0] => last.1 = pair[1],
+                _ => res.push((pair[0], pair[1])),
+            }
+        }
+        res
+    }
+}
+
+/// Positive for counterclockwise loops
+pub fn signed_area(polyline: &[[f32; 2]]) -> f32 {
+    let n = polyline.len();
+    (0..n)
+        .map(|i| {
+            let [x0, y0] = polyline[i];
+            let [x1, y1] = polyline[(i + 1) % n];
+            x0 * y1 - x1 * y0
+        })
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Times the closed polyline winds counterclockwise around the point
+pub fn winding_number(polyline: &[[f32; 2]], point: [f32; 2]) -> i32 {
+    let n = polyline.len();
+    let [x, y] = point;
+    let mut res = 0;
+    for i in 0..n {
+        let [x0, y0] = polyline[i];
+        let [x1, y1] = polyline[(i + 1) % n];
+        // Which side of the edge the point is on, positive for left
+        let side = (x1 - x0) * (y - y0) - (x - x0) * (y1 - y0);
+        if y0 <= y && y1 > y && side > 0.0 {
+            res += 1;
+        } else if y0 > y && y1 <= y && side < 0.0 {
+            res -= 1;
+        }
+    }
+    res
+}
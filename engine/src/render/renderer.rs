@@ -200,6 +200,14 @@ impl Renderer {
                 render_pass.set_vertex_buffer(0, polyline.get_vertex_buffer().slice(..));
                 render_pass.draw(0..polyline.get_vertex_count(), 0..1);
             }
+            // Isocurves take the transform of their surface
+            for surface in scene.get_surfaces().values() {
+                render_pass.set_bind_group(1, surface.get_bind_group(), &[]);
+                for isocurve in surface.get_isocurves() {
+                    render_pass.set_vertex_buffer(0, isocurve.get_vertex_buffer().slice(..));
+                    render_pass.draw(0..isocurve.get_vertex_count(), 0..1);
+                }
+            }
             render_pass.set_pipeline(&self.lines_render_pipeline);
             for lines in scene.get_lines().values() {
                 render_pass.set_bind_group(1, lines.get_bind_group(), &[]);
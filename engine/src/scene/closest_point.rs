@@ -0,0 +1,108 @@
+//! Closest point queries for snapping, trimming and measuring.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    geometry::GeometryId, math::linear_algebra::vec3::Vec3, scene::scene_interface::Scene,
+    utils::get_instance_mut,
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone)]
+pub struct CurveClosestPoint {
+    t: f32,
+    point: Vec3,
+    distance: f32,
+}
+
+#[wasm_bindgen]
+impl CurveClosestPoint {
+    pub fn get_t(&self) -> f32 {
+        self.t
+    }
+    /// World space
+    pub fn get_point(&self) -> Vec3 {
+        self.point
+    }
+    pub fn get_distance(&self) -> f32 {
+        self.distance
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone)]
+pub struct SurfaceClosestPoint {
+    u: f32,
+    v: f32,
+    point: Vec3,
+    normal: Vec3,
+    distance: f32,
+}
+
+#[wasm_bindgen]
+impl SurfaceClosestPoint {
+    pub fn get_u(&self) -> f32 {
+        self.u
+    }
+    pub fn get_v(&self) -> f32 {
+        self.v
+    }
+    /// World space
+    pub fn get_point(&self) -> Vec3 {
+        self.point
+    }
+    /// Unit normal, zero where the surface is degenerate
+    pub fn get_normal(&self) -> Vec3 {
+        self.normal
+    }
+    pub fn get_distance(&self) -> f32 {
+        self.distance
+    }
+}
+
+#[wasm_bindgen]
+impl Scene {
+    /// Point of a curve closest to a world space point.
+    /// None if there is no such curve.
+    #[wasm_bindgen]
+    pub fn closest_point_on_curve(
+        &self,
+        id: GeometryId,
+        point: &[f32],
+    ) -> Option<CurveClosestPoint> {
+        let point: Vec3 = point.into();
+        get_instance_mut!(&self.get_instance_handle())
+            .get_scene_mut(self.get_handle())
+            .get_curves()
+            .get(&id)
+            .map(|curve| {
+                let (t, point, distance) = curve.closest_point(&point);
+                CurveClosestPoint { t, point, distance }
+            })
+    }
+
+    /// Point of a surface closest to a world space point.
+    /// None if there is no such surface.
+    #[wasm_bindgen]
+    pub fn closest_point_on_surface(
+        &self,
+        id: GeometryId,
+        point: &[f32],
+    ) -> Option<SurfaceClosestPoint> {
+        let point: Vec3 = point.into();
+        get_instance_mut!(&self.get_instance_handle())
+            .get_scene_mut(self.get_handle())
+            .get_surfaces()
+            .get(&id)
+            .map(|surface| {
+                let (u, v, point, normal, distance) = surface.closest_point(&point);
+                SurfaceClosestPoint {
+                    u,
+                    v,
+                    point,
+                    normal,
+                    distance,
+                }
+            })
+    }
+}
@@ -0,0 +1,172 @@
+//! Intersections between curves, and between curves and planes.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    geometry::GeometryId,
+    math::{
+        geometry::plane::Plane,
+        linear_algebra::vec3::Vec3,
+        nurbs::intersection::{intersect_curve_plane, intersect_curves},
+    },
+    scene::scene_interface::Scene,
+};
+
+/// A crossing point, or a stretch where the curves coincide.
+/// For points the end equals the start.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone)]
+pub struct CurveIntersectionResult {
+    t_a: f32,
+    t_b: f32,
+    point: Vec3,
+    is_overlap: bool,
+    end_t_a: f32,
+    end_t_b: f32,
+    end_point: Vec3,
+}
+
+#[wasm_bindgen]
+impl CurveIntersectionResult {
+    pub fn get_t_a(&self) -> f32 {
+        self.t_a
+    }
+    pub fn get_t_b(&self) -> f32 {
+        self.t_b
+    }
+    /// World space
+    pub fn get_point(&self) -> Vec3 {
+        self.point
+    }
+    pub fn is_overlap(&self) -> bool {
+        self.is_overlap
+    }
+    pub fn get_end_t_a(&self) -> f32 {
+        self.end_t_a
+    }
+    /// Less than t_b when the curves run opposite ways
+    pub fn get_end_t_b(&self) -> f32 {
+        self.end_t_b
+    }
+    pub fn get_end_point(&self) -> Vec3 {
+        self.end_point
+    }
+}
+
+/// A crossing point, or a stretch of the curve lying in the plane.
+/// For points the end equals the start.
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone)]
+pub struct PlaneIntersectionResult {
+    t: f32,
+    point: Vec3,
+    is_overlap: bool,
+    end_t: f32,
+    end_point: Vec3,
+}
+
+#[wasm_bindgen]
+impl PlaneIntersectionResult {
+    pub fn get_t(&self) -> f32 {
+        self.t
+    }
+    /// World space
+    pub fn get_point(&self) -> Vec3 {
+        self.point
+    }
+    pub fn is_overlap(&self) -> bool {
+        self.is_overlap
+    }
+    pub fn get_end_t(&self) -> f32 {
+        self.end_t
+    }
+    pub fn get_end_point(&self) -> Vec3 {
+        self.end_point
+    }
+}
+
+#[wasm_bindgen]
+impl Scene {
+    /// Where two curves cross or touch, and where they coincide, sorted along curve_a.
+    /// Points closer than tolerance count as the same.
+    /// Empty if a curve is missing.
+    #[wasm_bindgen]
+    pub fn intersect_curves(
+        &self,
+        curve_a: GeometryId,
+        curve_b: GeometryId,
+        tolerance: f32,
+    ) -> Vec<CurveIntersectionResult> {
+        let (Some(a), Some(b)) = (self.get_curve_nurbs(curve_a), self.get_curve_nurbs(curve_b))
+        else {
+            log::info!("curve intersection failed, no curve");
+            return Vec::new();
+        };
+        let (points, overlaps) = intersect_curves(&a, &b, tolerance);
+        let mut res: Vec<CurveIntersectionResult> = points
+            .iter()
+            .map(|hit| CurveIntersectionResult {
+                t_a: hit.t_a,
+                t_b: hit.t_b,
+                point: hit.point,
+                is_overlap: false,
+                end_t_a: hit.t_a,
+                end_t_b: hit.t_b,
+                end_point: hit.point,
+            })
+            .chain(overlaps.iter().map(|overlap| CurveIntersectionResult {
+                t_a: overlap.start.t_a,
+                t_b: overlap.start.t_b,
+                point: overlap.start.point,
+                is_overlap: true,
+                end_t_a: overlap.end.t_a,
+                end_t_b: overlap.end.t_b,
+                end_point: overlap.end.point,
+            }))
+            .collect();
+        res.sort_by(|x, y| x.t_a.total_cmp(&y.t_a));
+        res
+    }
+
+    /// Where a curve crosses or touches the plane through origin with normal, and where it lies in it.
+    /// Sorted along the curve, empty if there is no such curve.
+    #[wasm_bindgen]
+    pub fn intersect_curve_plane(
+        &self,
+        curve: GeometryId,
+        origin: &[f32],
+        normal: &[f32],
+        tolerance: f32,
+    ) -> Vec<PlaneIntersectionResult> {
+        let Some(nurbs) = self.get_curve_nurbs(curve) else {
+            log::info!("plane intersection failed, no curve");
+            return Vec::new();
+        };
+        let normal: Vec3 = normal.into();
+        if normal.len() == 0.0 {
+            log::info!("plane intersection failed, normal has no length");
+            return Vec::new();
+        }
+        let plane = Plane::new(origin.into(), normal);
+        let (points, overlaps) = intersect_curve_plane(&nurbs, &plane, tolerance);
+        let mut res: Vec<PlaneIntersectionResult> = points
+            .iter()
+            .map(|hit| PlaneIntersectionResult {
+                t: hit.t,
+                point: hit.point,
+                is_overlap: false,
+                end_t: hit.t,
+                end_point: hit.point,
+            })
+            .chain(overlaps.iter().map(|overlap| PlaneIntersectionResult {
+                t: overlap.start.t,
+                point: overlap.start.point,
+                is_overlap: true,
+                end_t: overlap.end.t,
+                end_point: overlap.end.point,
+            }))
+            .collect();
+        res.sort_by(|x, y| x.t.total_cmp(&y.t));
+        res
+    }
+}
@@ -1,10 +1,19 @@
+pub mod closest_point;
+pub mod intersection;
 pub mod scene_interface;
+pub mod section;
+pub mod surface_intersection;
+pub mod trim;
 
 use std::collections::HashMap;
 
-use crate::geometry::{
-    curve::Curve, lines::Lines, mesh::Mesh, new_geometry_id, polyline::Polyline, surface::Surface,
-    Geometry, GeometryId,
+use crate::{
+    geometry::{
+        curve::Curve, lines::Lines, mesh::Mesh, new_geometry_id, polyline::Polyline,
+        surface::Surface, Geometry, GeometryId,
+    },
+    gpu_acceleration_structures::mesh_bbh::mesh_bbh_generator::MeshBBHGenerator,
+    gpu_samplers::curve_sampler::CurveSampler,
 };
 
 pub struct SceneInternal {
@@ -110,4 +119,37 @@ impl SceneInternal {
             geo.rotate(center.into(), axis.into(), radians);
         }
     }
+
+    /// Drawing needs the isocurves of every surface at the current density.
+    pub fn build_missing_isocurves(&mut self, curve_sampler: &CurveSampler, density: u32) {
+        for surface in self.surfaces.values_mut() {
+            surface.build_isocurves(curve_sampler, density);
+        }
+    }
+
+    /// Ray and frustum tracing need a bbh on every surface and mesh.
+    pub fn build_missing_bbhs(&mut self, bbh_generator: &MeshBBHGenerator) {
+        for surface in self.surfaces.values_mut() {
+            if surface.get_bbh().is_none() {
+                let bbh = bbh_generator.generate_mesh_bbh_fast_build_2(
+                    surface.get_vertex_buffer(),
+                    surface.get_vertex_count(),
+                    surface.get_index_buffer(),
+                    surface.get_index_count(),
+                );
+                surface.set_bbh(bbh);
+            }
+        }
+        for mesh in self.meshes.values_mut() {
+            if mesh.get_bbh().is_none() {
+                let bbh = bbh_generator.generate_mesh_bbh_fast_build_2(
+                    mesh.get_vertex_buffer(),
+                    mesh.get_vertex_count(),
+                    mesh.get_index_buffer(),
+                    mesh.get_index_count(),
+                );
+                mesh.add_bbh(bbh);
+            }
+        }
+    }
 }
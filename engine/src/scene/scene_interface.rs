@@ -7,11 +7,18 @@ use crate::{
         mesh::{Mesh, MeshVertex},
         polyline::{Polyline, PolylineVertex},
         surface::Surface,
-        GeometryId,
+        Geometry, GeometryId,
     },
     gpu_acceleration_structures::debug::mesh_bbh_to_lines::mesh_bbh_to_lines,
+    gpu_samplers::params::SamplingTolerance,
     instance::Handle,
-    math::linear_algebra::{vec3::Vec3, vec4::Vec4},
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{
+            curve::NurbsCurve,
+            surface::{NurbsSurface, SurfaceDirection},
+        },
+    },
     utils::get_instance_mut,
 };
 
@@ -20,6 +27,43 @@ pub struct Scene {
     instance_handle: Handle,
     scene_handle: Handle,
 }
+impl Scene {
+    /// For generators that build the curve on the CPU
+    pub(crate) fn add_curve_from_nurbs(&self, nurbs: NurbsCurve) -> GeometryId {
+        let curve = Curve::from_nurbs(
+            get_instance_mut!(&self.instance_handle).get_curve_sampler(),
+            nurbs,
+        );
+        get_instance_mut!(&self.instance_handle)
+            .get_scene_mut(self.scene_handle)
+            .add_curve(curve)
+    }
+
+    /// For generators that build the surface on the CPU
+    pub(crate) async fn add_surface_from_nurbs(
+        &self,
+        nurbs: NurbsSurface,
+        with_bbh: bool,
+    ) -> GeometryId {
+        let surface_sampler = get_instance_mut!(&self.instance_handle).get_surface_sampler();
+        let mesh_bbh_generator = get_instance_mut!(&self.instance_handle).get_mesh_bbh_generator();
+        let surface =
+            Surface::from_nurbs(surface_sampler, mesh_bbh_generator, nurbs, with_bbh).await;
+        get_instance_mut!(&self.instance_handle)
+            .get_scene_mut(self.scene_handle)
+            .add_surface(surface)
+    }
+
+    /// World space copy of a curve, None if there is no such curve
+    pub(crate) fn get_curve_nurbs(&self, id: GeometryId) -> Option<NurbsCurve> {
+        get_instance_mut!(&self.instance_handle)
+            .get_scene_mut(self.scene_handle)
+            .get_curves()
+            .get(&id)
+            .map(|curve| curve.to_world_nurbs())
+    }
+}
+
 #[wasm_bindgen]
 impl Scene {
     pub fn new(instance_handle: Handle, scene_handle: Handle) -> Scene {
@@ -230,7 +274,6 @@ impl Scene {
             .delete_geometry(geometry_id);
     }
 
-    // NOTE: this will break once adaptive sampling is added
     /// Control point count cannot change in either dimension.
     #[wasm_bindgen]
     pub async fn update_surface_params(
@@ -266,12 +309,167 @@ impl Scene {
                     knots_u,
                     knots_v,
                     with_bbh,
-                    // BUG:
                 )
                 .await;
         }
     }
 
+    /// Replaces the curve with the parts before and after t.
+    /// Returns the ids of the two parts, or nothing if t is not inside the curve.
+    #[wasm_bindgen]
+    pub fn split_curve(&self, id: GeometryId, t: f32) -> Vec<GeometryId> {
+        let Some((left, right)) = get_instance_mut!(&self.instance_handle)
+            .edit_curve(self.scene_handle, id, |curve, curve_sampler| {
+                curve.split_at(curve_sampler, t)
+            })
+            .flatten()
+        else {
+            return Vec::new();
+        };
+
+        get_instance_mut!(&self.instance_handle)
+            .get_scene_mut(self.scene_handle)
+            .delete_geometry(id);
+        let left = get_instance_mut!(&self.instance_handle)
+            .get_scene_mut(self.scene_handle)
+            .add_curve(left);
+        let right = get_instance_mut!(&self.instance_handle)
+            .get_scene_mut(self.scene_handle)
+            .add_curve(right);
+        vec![left, right]
+    }
+
+    /// Splits the curve at the point of it closest to a world space point, like split_curve.
+    #[wasm_bindgen]
+    pub fn split_curve_at_point(&self, id: GeometryId, point: &[f32]) -> Vec<GeometryId> {
+        let point: Vec3 = point.into();
+        let Some((t, _, _)) = get_instance_mut!(&self.instance_handle)
+            .get_scene(self.scene_handle)
+            .get_curves()
+            .get(&id)
+            .map(|curve| curve.closest_point(&point))
+        else {
+            return Vec::new();
+        };
+        self.split_curve(id, t)
+    }
+
+    /// Raises the degree of a curve without changing its shape
+    #[wasm_bindgen]
+    pub fn elevate_curve_degree(&self, id: GeometryId, times: u32) {
+        get_instance_mut!(&self.instance_handle).edit_curve(
+            self.scene_handle,
+            id,
+            |curve, curve_sampler| curve.elevate_degree(curve_sampler, times),
+        );
+    }
+
+    /// Raises the degree of a surface in each direction without changing its shape
+    #[wasm_bindgen]
+    pub fn elevate_surface_degree(&self, id: GeometryId, times_u: u32, times_v: u32) {
+        if let Some(surface) = get_instance_mut!(&self.instance_handle)
+            .get_scene_mut(self.scene_handle)
+            .get_surfaces_mut()
+            .get_mut(&id)
+        {
+            surface.elevate_degree(times_u, times_v);
+        }
+    }
+
+    /// Removes every knot of a curve that can go without moving it more than tolerance.
+    /// Returns the number of knots removed.
+    #[wasm_bindgen]
+    pub fn remove_curve_knots(&self, id: GeometryId, tolerance: f32) -> u32 {
+        get_instance_mut!(&self.instance_handle)
+            .edit_curve(self.scene_handle, id, |curve, curve_sampler| {
+                curve.remove_knots(curve_sampler, tolerance)
+            })
+            .unwrap_or(0) as u32
+    }
+
+    /// Removes every knot of a surface that can go without moving it more than tolerance.
+    /// Returns the number of knots removed.
+    #[wasm_bindgen]
+    pub fn remove_surface_knots(&self, id: GeometryId, tolerance: f32) -> u32 {
+        get_instance_mut!(&self.instance_handle)
+            .get_scene_mut(self.scene_handle)
+            .get_surfaces_mut()
+            .get_mut(&id)
+            .map_or(0, |surface| surface.remove_knots(tolerance) as u32)
+    }
+
+    /// Adds the exact curve running over a surface in direction, with the other parameter at param.
+    /// Returns the id of the curve, or 0 if there is no such surface or param is outside it.
+    #[wasm_bindgen]
+    pub fn extract_isocurve(
+        &self,
+        id: GeometryId,
+        direction: SurfaceDirection,
+        param: f32,
+    ) -> GeometryId {
+        let Some((nurbs, model)) = get_instance_mut!(&self.instance_handle)
+            .get_scene(self.scene_handle)
+            .get_surfaces()
+            .get(&id)
+            .and_then(|surface| {
+                let model = *surface.get_bind_group_object().get_model();
+                surface
+                    .isocurve(direction, param)
+                    .map(|nurbs| (nurbs, model))
+            })
+        else {
+            log::info!("extract isocurve failed");
+            return 0;
+        };
+        let mut curve = Curve::from_nurbs(
+            get_instance_mut!(&self.instance_handle).get_curve_sampler(),
+            nurbs,
+        );
+        curve.get_bind_group_object_mut().set_model(model);
+        get_instance_mut!(&self.instance_handle)
+            .get_scene_mut(self.scene_handle)
+            .add_curve(curve)
+    }
+
+    /// Overrides the instances sampling tolerance for one curve or surface.
+    /// Angle is in radians.
+    #[wasm_bindgen]
+    pub fn set_sampling_tolerance(&self, id: GeometryId, chordal_deviation: f32, angle: f32) {
+        self.update_sampling_tolerance(
+            id,
+            Some(SamplingTolerance {
+                chordal_deviation,
+                angle,
+            }),
+        );
+    }
+
+    /// Goes back to the instances sampling tolerance
+    #[wasm_bindgen]
+    pub fn clear_sampling_tolerance(&self, id: GeometryId) {
+        self.update_sampling_tolerance(id, None);
+    }
+
+    /// How many samples the surface was last sampled with, 0 if there is no such surface.
+    #[wasm_bindgen]
+    pub fn get_surface_sample_count(&self, id: GeometryId) -> u32 {
+        get_instance_mut!(&self.instance_handle)
+            .get_scene(self.scene_handle)
+            .get_surfaces()
+            .get(&id)
+            .map_or(0, |surface| {
+                surface.get_sample_count_u() * surface.get_sample_count_v()
+            })
+    }
+
+    fn update_sampling_tolerance(&self, id: GeometryId, tolerance: Option<SamplingTolerance>) {
+        get_instance_mut!(&self.instance_handle).set_geometry_sampling_tolerance(
+            self.scene_handle,
+            id,
+            tolerance,
+        );
+    }
+
     pub async fn add_surface_bbh_debug_lines(&self, surface: GeometryId) -> GeometryId {
         // TODO: remove unwrap
         let renderer = get_instance_mut!(&self.instance_handle).get_renderer();
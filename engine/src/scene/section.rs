@@ -0,0 +1,185 @@
+//! Plane sections and contours of surfaces and meshes.
+//!
+//! Cuts come from the sampled triangles, so they are as close to a surface as its sampling tolerance.
+//! Each cut is added to the scene as a polyline, or as a curve fitted through the polylines points.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    geometry::{Geometry, GeometryId},
+    gpu_mesh_intersection::{
+        section::{
+            chain_segments, read_section_segments, SectionChain, SectionLevels, SectionSegment,
+        },
+        WorldMesh,
+    },
+    instance::INSTANCES,
+    math::{linear_algebra::vec3::Vec3, nurbs::fitting::fit_curve},
+    scene::scene_interface::Scene,
+    utils::PendingReadback,
+};
+
+/// Degree of fitted cuts
+const FIT_DEGREE: u32 = 3;
+/// Fitting stops adding controls here even if the cut is not within tolerance
+const MAX_FIT_CONTROLS: usize = 128;
+
+/// One cut through a surface or mesh, added to the scene
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone)]
+pub struct SectionCut {
+    id: GeometryId,
+    source: GeometryId,
+    level: i32,
+    closed: bool,
+}
+
+#[wasm_bindgen]
+impl SectionCut {
+    /// The polyline or curve in the scene
+    pub fn get_id(&self) -> GeometryId {
+        self.id
+    }
+    /// The surface or mesh that was cut
+    pub fn get_source(&self) -> GeometryId {
+        self.source
+    }
+    /// Which contour plane the cut lies in, counted in spacings from the base point. 0 for sections.
+    pub fn get_level(&self) -> i32 {
+        self.level
+    }
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+#[wasm_bindgen]
+impl Scene {
+    /// Cuts the surfaces and meshes among ids with the plane through origin with normal.
+    /// With a positive fit tolerance, cuts that a cubic curve can follow within that distance become curves.
+    /// The other cuts, and all of them without a fit tolerance, are added as polylines.
+    /// Ids of other geometry are ignored.
+    pub async fn section(
+        &self,
+        ids: &[GeometryId],
+        origin: &[f32],
+        normal: &[f32],
+        fit_tolerance: f32,
+    ) -> Vec<SectionCut> {
+        let Some(levels) = SectionLevels::plane(origin.into(), normal.into()) else {
+            log::info!("section failed, normal has no length");
+            return Vec::new();
+        };
+        self.cut(ids, &levels, fit_tolerance).await
+    }
+
+    /// Cuts the surfaces and meshes among ids with planes normal to direction,
+    /// spacing apart and through base point, as far as the geometry reaches.
+    /// Fit tolerance works like in section.
+    pub async fn contour(
+        &self,
+        ids: &[GeometryId],
+        base_point: &[f32],
+        direction: &[f32],
+        spacing: f32,
+        fit_tolerance: f32,
+    ) -> Vec<SectionCut> {
+        let Some(levels) = SectionLevels::contours(base_point.into(), direction.into(), spacing)
+        else {
+            log::info!("contour failed, direction has no length or spacing is not positive");
+            return Vec::new();
+        };
+        self.cut(ids, &levels, fit_tolerance).await
+    }
+}
+
+impl Scene {
+    async fn cut(
+        &self,
+        ids: &[GeometryId],
+        levels: &SectionLevels,
+        fit_tolerance: f32,
+    ) -> Vec<SectionCut> {
+        // Every mesh is submitted while the instance is locked,
+        // the segments are awaited without holding it.
+        let mut pending: Vec<(GeometryId, PendingReadback)> = Vec::new();
+        {
+            let mut instances = INSTANCES.lock().unwrap();
+            let instance = instances.get_mut(&self.get_instance_handle()).unwrap();
+
+            let mesh_sectioner = instance.get_mesh_sectioner();
+            let bbh_generator = instance.get_mesh_bbh_generator();
+
+            let scene = instance.get_scene_mut(self.get_handle());
+            scene.build_missing_bbhs(&bbh_generator);
+
+            for id in ids {
+                let mesh = if let Some(surface) = scene.get_surfaces().get(id) {
+                    WorldMesh {
+                        bbh: surface.get_bbh().unwrap(),
+                        vertex_buffer: surface.get_vertex_buffer(),
+                        index_buffer: surface.get_index_buffer(),
+                        model: surface.get_bind_group_object().get_model(),
+                    }
+                } else if let Some(mesh) = scene.get_meshes().get(id) {
+                    WorldMesh {
+                        bbh: mesh.get_bbh().unwrap(),
+                        vertex_buffer: mesh.get_vertex_buffer(),
+                        index_buffer: mesh.get_index_buffer(),
+                        model: mesh.get_bind_group_object().get_model(),
+                    }
+                } else {
+                    continue;
+                };
+                pending.push((*id, mesh_sectioner.submit_section_mesh(&mesh, levels)));
+            }
+        }
+
+        let mut cuts: Vec<(GeometryId, Vec<SectionSegment>)> = Vec::new();
+        for (id, readback) in pending {
+            cuts.push((id, read_section_segments(readback).await));
+        }
+
+        let mut res = Vec::new();
+        for (source, segments) in cuts {
+            for chain in chain_segments(&segments) {
+                res.push(SectionCut {
+                    id: self.add_cut(&chain, fit_tolerance),
+                    source,
+                    level: chain.level,
+                    closed: chain.closed,
+                });
+            }
+        }
+        res
+    }
+
+    /// Adds the chain as a curve when it can be fitted within tolerance, otherwise as a polyline
+    fn add_cut(&self, chain: &SectionChain, fit_tolerance: f32) -> GeometryId {
+        let mut points = chain.points.clone();
+        if chain.closed {
+            points.push(points[0]);
+        }
+        if fit_tolerance > 0.0 {
+            let max_control_count = points.len().min(MAX_FIT_CONTROLS);
+            let mut control_count = FIT_DEGREE as usize + 1;
+            while control_count <= max_control_count {
+                let Some((curve, deviation)) = fit_curve(&points, FIT_DEGREE, control_count) else {
+                    break;
+                };
+                if deviation.max <= fit_tolerance {
+                    return self.add_curve_from_nurbs(curve);
+                }
+                if control_count == max_control_count {
+                    break;
+                }
+                control_count = (control_count * 2).min(max_control_count);
+            }
+        }
+        let vertices: Vec<f32> = points
+            .iter()
+            .flat_map(|point: &Vec3| [point.x, point.y, point.z])
+            .collect();
+        self.add_polyline(&vertices)
+    }
+}
@@ -0,0 +1,149 @@
+//! Intersection curves between surfaces, for trimming, sections and clash checks.
+//!
+//! Crossing triangles of the two sampled surfaces are found on the GPU through their bbhs,
+//! then the exact curves are traced on the CPU from the (u, v) at the middle of each pair.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    geometry::{Geometry, GeometryId},
+    gpu_mesh_intersection::{overlap::read_overlap_pairs, WorldMesh},
+    instance::INSTANCES,
+    math::{
+        linear_algebra::vec3::Vec3,
+        nurbs::{
+            curve::NurbsCurve,
+            surface_intersection::{intersect_surfaces, SurfaceIntersectionParams},
+        },
+    },
+    scene::{scene_interface::Scene, trim::write_trim_curve},
+};
+
+/// One intersection curve, added to the scene, with where it runs on both surfaces
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct SurfaceIntersectionResult {
+    id: GeometryId,
+    closed: bool,
+    uv_curve_a: NurbsCurve,
+    uv_curve_b: NurbsCurve,
+}
+
+#[wasm_bindgen]
+impl SurfaceIntersectionResult {
+    /// The world space curve in the scene
+    pub fn get_id(&self) -> GeometryId {
+        self.id
+    }
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+    /// (u, v) curve on the first surface, in the curve layout of Scene::set_surface_trims.
+    /// Shares its parameterization with the world space curve.
+    pub fn get_uv_curve_a(&self) -> Vec<f32> {
+        let mut res = Vec::new();
+        write_trim_curve(&self.uv_curve_a, &mut res);
+        res
+    }
+    /// (u, v) curve on the second surface
+    pub fn get_uv_curve_b(&self) -> Vec<f32> {
+        let mut res = Vec::new();
+        write_trim_curve(&self.uv_curve_b, &mut res);
+        res
+    }
+}
+
+#[wasm_bindgen]
+impl Scene {
+    /// Adds every curve where the two surfaces cross to the scene.
+    /// Tolerance is the world space distance the curves may stray from the exact intersection.
+    /// Surfaces that only touch may be missed.
+    pub async fn intersect_surfaces(
+        &self,
+        a: GeometryId,
+        b: GeometryId,
+        tolerance: f32,
+    ) -> Vec<SurfaceIntersectionResult> {
+        if a == b || tolerance <= 0.0 {
+            log::info!("surface intersection failed");
+            return Vec::new();
+        }
+
+        // The instance is locked only to submit and to look the surfaces up again,
+        // the pairs are awaited without holding it.
+        let readback = {
+            let mut instances = INSTANCES.lock().unwrap();
+            let instance = instances.get_mut(&self.get_instance_handle()).unwrap();
+
+            let mesh_overlapper = instance.get_mesh_overlapper();
+            let bbh_generator = instance.get_mesh_bbh_generator();
+
+            let scene = instance.get_scene_mut(self.get_handle());
+            scene.build_missing_bbhs(&bbh_generator);
+
+            let surfaces = scene.get_surfaces();
+            let (Some(surface_a), Some(surface_b)) = (surfaces.get(&a), surfaces.get(&b)) else {
+                log::info!("surface intersection failed");
+                return Vec::new();
+            };
+
+            mesh_overlapper.submit_overlap_meshes(
+                &WorldMesh {
+                    bbh: surface_a.get_bbh().unwrap(),
+                    vertex_buffer: surface_a.get_vertex_buffer(),
+                    index_buffer: surface_a.get_index_buffer(),
+                    model: surface_a.get_bind_group_object().get_model(),
+                },
+                &WorldMesh {
+                    bbh: surface_b.get_bbh().unwrap(),
+                    vertex_buffer: surface_b.get_vertex_buffer(),
+                    index_buffer: surface_b.get_index_buffer(),
+                    model: surface_b.get_bind_group_object().get_model(),
+                },
+            )
+        };
+
+        let pairs = read_overlap_pairs(readback).await;
+
+        let (nurbs_a, nurbs_b, seeds) = {
+            let instances = INSTANCES.lock().unwrap();
+            let surfaces = instances
+                .get(&self.get_instance_handle())
+                .unwrap()
+                .get_scene(self.get_handle())
+                .get_surfaces();
+
+            // Either surface may have been deleted while reading back
+            let (Some(surface_a), Some(surface_b)) = (surfaces.get(&a), surfaces.get(&b)) else {
+                log::info!("surface intersection failed");
+                return Vec::new();
+            };
+
+            let center = Vec3::new(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0);
+            let seeds: Vec<SurfaceIntersectionParams> = pairs
+                .iter()
+                .map(|(triangle_a, triangle_b)| {
+                    let (u_a, v_a) = surface_a.get_triangle_uv(*triangle_a, &center);
+                    let (u_b, v_b) = surface_b.get_triangle_uv(*triangle_b, &center);
+                    [u_a, v_a, u_b, v_b]
+                })
+                .collect();
+
+            (
+                surface_a.to_world_nurbs(),
+                surface_b.to_world_nurbs(),
+                seeds,
+            )
+        };
+
+        intersect_surfaces(&nurbs_a, &nurbs_b, &seeds, tolerance)
+            .into_iter()
+            .map(|curve| SurfaceIntersectionResult {
+                id: self.add_curve_from_nurbs(curve.curve),
+                closed: curve.closed,
+                uv_curve_a: curve.curve_a,
+                uv_curve_b: curve.curve_b,
+            })
+            .collect()
+    }
+}
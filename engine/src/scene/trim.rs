@@ -0,0 +1,120 @@
+//! Trimming surfaces down to faces with loops of curves in their parameter space.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    geometry::GeometryId,
+    math::{
+        linear_algebra::vec4::Vec4,
+        nurbs::{
+            curve::NurbsCurve,
+            trim::{SurfaceTrims, TrimLoop},
+        },
+    },
+    scene::scene_interface::Scene,
+    utils::get_instance_mut,
+};
+
+#[wasm_bindgen]
+impl Scene {
+    /// Trims a surface to the region inside the outer loop and outside the inner loops.
+    /// Leave outer empty to keep the whole domain apart from the inner loops.
+    ///
+    /// Each argument is a list of loops laid out as:
+    ///
+    /// ```text
+    /// curve count, curve 0, curve 1, ...
+    /// ```
+    ///
+    /// and each curve, which should end where the next one starts, as:
+    ///
+    /// ```text
+    /// degree, control count,
+    /// u0, v0, weight0,
+    /// u1, v1, weight1,
+    /// ...
+    /// control count + degree + 1 knots
+    /// ```
+    ///
+    /// outer holds at most one loop. Loop direction does not matter.
+    /// Returns false and leaves the surface as it was if there is no such surface,
+    /// the loops are malformed or they keep nothing.
+    #[wasm_bindgen]
+    pub fn set_surface_trims(&self, id: GeometryId, outer: &[f32], inner: &[f32]) -> bool {
+        let (Some(mut outer), Some(inner)) = (read_trim_loops(outer), read_trim_loops(inner))
+        else {
+            log::info!("trimming failed, malformed loops");
+            return false;
+        };
+        if outer.len() > 1 {
+            log::info!("trimming failed, more than one outer loop");
+            return false;
+        }
+        self.update_surface_trims(id, Some(SurfaceTrims::new(outer.pop(), inner)))
+    }
+
+    /// Goes back to the whole untrimmed surface
+    #[wasm_bindgen]
+    pub fn clear_surface_trims(&self, id: GeometryId) {
+        self.update_surface_trims(id, None);
+    }
+
+    fn update_surface_trims(&self, id: GeometryId, trims: Option<SurfaceTrims>) -> bool {
+        get_instance_mut!(&self.get_instance_handle())
+            .get_scene_mut(self.get_handle())
+            .get_surfaces_mut()
+            .get_mut(&id)
+            .is_some_and(|surface| surface.set_trims(trims))
+    }
+}
+
+/// Loops in the layout of Scene::set_surface_trims, None if the data runs out early or a curve is malformed
+pub(crate) fn read_trim_loops(data: &[f32]) -> Option<Vec<TrimLoop>> {
+    let mut data = data.iter().copied();
+    let mut res = Vec::new();
+    while let Some(curve_count) = data.next() {
+        let curves = (0..curve_count as usize)
+            .map(|_| read_trim_curve(&mut data))
+            .collect::<Option<Vec<_>>>()?;
+        if curves.is_empty() {
+            return None;
+        }
+        res.push(TrimLoop::new(curves));
+    }
+    Some(res)
+}
+
+/// None if the curve is malformed: weights that are not positive, values that are not finite,
+/// or knots that decrease or leave no domain
+fn read_trim_curve(data: &mut impl Iterator<Item = f32>) -> Option<NurbsCurve> {
+    let degree = data.next()? as u32;
+    let control_count = data.next()? as usize;
+    if degree == 0 || control_count <= degree as usize {
+        return None;
+    }
+    let weighted_controls = (0..control_count)
+        .map(|_| {
+            let (u, v, weight) = (data.next()?, data.next()?, data.next()?);
+            let finite = u.is_finite() && v.is_finite() && weight.is_finite();
+            (finite && weight > 0.0).then(|| Vec4::new(u * weight, v * weight, 0.0, weight))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let knots = (0..control_count + degree as usize + 1)
+        .map(|_| data.next().filter(|knot| knot.is_finite()))
+        .collect::<Option<Vec<_>>>()?;
+    // Decreasing knots would keep find_span from ever finishing
+    if knots.windows(2).any(|w| w[0] > w[1]) || knots[degree as usize] >= knots[control_count] {
+        return None;
+    }
+    Some(NurbsCurve::new(degree, weighted_controls, knots))
+}
+
+/// Appends a (u, v) curve in the layout read by read_trim_curve, z is dropped
+pub(crate) fn write_trim_curve(curve: &NurbsCurve, data: &mut Vec<f32>) {
+    data.push(curve.degree as f32);
+    data.push(curve.weighted_controls.len() as f32);
+    for control in curve.weighted_controls.iter() {
+        data.extend([control.x / control.w, control.y / control.w, control.w]);
+    }
+    data.extend(curve.knots.iter());
+}
@@ -0,0 +1,449 @@
+use crate::{
+    geometry::curve_generators::{
+        arc::create_arc_nurbs,
+        blend::{create_blend_nurbs, Continuity, CurveEnd},
+        circle::{circle_through_points, create_circle_nurbs, create_ellipse_nurbs},
+        interpolated::create_interpolated_nurbs,
+        offset::create_offset_nurbs,
+    },
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{curve::NurbsCurve, interpolation::Parameterization},
+    },
+};
+
+use crate::tests::utils::assert_close;
+
+use wasm_bindgen_test::*;
+
+const RADIUS_TOLERANCE: f32 = 1e-5;
+
+/// Largest difference between the distance to center and radius.
+/// Relative to the size of the coordinates, since that is what f32 error scales with.
+fn max_radius_error(curve: &NurbsCurve, center: &Vec3, radius: f32) -> f32 {
+    let scale = radius + center.len();
+    let (start, end) = curve.domain();
+    (0..=200)
+        .map(|i| {
+            let t = start + (end - start) * i as f32 / 200.0;
+            (Vec3::distance(&curve.point(t), center) - radius).abs() / scale
+        })
+        .fold(0.0, f32::max)
+}
+
+#[wasm_bindgen_test]
+pub fn test_circle_radius() {
+    let center = Vec3::new(1.0, -2.0, 3.0);
+    for normal in [
+        Vec3::new(0.0, 0.0, 1.0),
+        Vec3::new(1.0, 1.0, 0.0),
+        Vec3::new(-0.3, 2.0, 0.5),
+    ] {
+        for radius in [0.01, 1.0, 250.0] {
+            let circle = create_circle_nurbs(center, normal, radius);
+            assert!(max_radius_error(&circle, &center, radius) < RADIUS_TOLERANCE);
+            // Closed and in the plane
+            assert_eq!(circle.point(0.0), circle.point(1.0));
+            let to_point = Vec3::subtract(&circle.point(0.3), &center);
+            assert!(Vec3::dot(&to_point, &normal.to_normalized()).abs() < 1e-4 * radius);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_arc_radius() {
+    let center = Vec3::new(0.0, 0.0, 0.0);
+    let x_axis = Vec3::new(1.0, 0.0, 0.0);
+    let y_axis = Vec3::new(0.0, 1.0, 0.0);
+    for (start, end) in [(0.0, 1.0), (0.5, 3.0), (1.0, 5.5), (5.0, 1.0)] {
+        let arc = create_arc_nurbs(center, x_axis, y_axis, 2.0, start, end);
+        assert!(max_radius_error(&arc, &center, 2.0) < RADIUS_TOLERANCE);
+        assert_close(
+            &arc.point(0.0),
+            &Vec3::new(2.0 * f32::cos(start), 2.0 * f32::sin(start), 0.0),
+            1e-5,
+        );
+        assert_close(
+            &arc.point(1.0),
+            &Vec3::new(2.0 * f32::cos(end), 2.0 * f32::sin(end), 0.0),
+            1e-5,
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_arc_goes_counter_clockwise() {
+    let arc = create_arc_nurbs(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        1.0,
+        0.0,
+        std::f32::consts::PI,
+    );
+    assert_close(&arc.point(0.5), &Vec3::new(0.0, 1.0, 0.0), 1e-5);
+}
+
+#[wasm_bindgen_test]
+pub fn test_circle_through_points() {
+    let a = Vec3::new(3.0, 1.0, 0.0);
+    let b = Vec3::new(1.0, 3.0, 0.0);
+    let c = Vec3::new(-1.0, 1.0, 0.0);
+    let (center, normal, radius) = circle_through_points(&a, &b, &c).unwrap();
+    assert_close(&center, &Vec3::new(1.0, 1.0, 0.0), 1e-5);
+    assert_close(&normal, &Vec3::new(0.0, 0.0, 1.0), 1e-5);
+    assert!((radius - 2.0).abs() < 1e-5);
+
+    let circle = create_circle_nurbs(center, normal, radius);
+    assert!(max_radius_error(&circle, &center, radius) < RADIUS_TOLERANCE);
+
+    assert!(circle_through_points(&a, &Vec3::new(2.0, 1.0, 0.0), &c).is_none());
+}
+
+#[wasm_bindgen_test]
+pub fn test_ellipse() {
+    let center = Vec3::new(1.0, 1.0, 1.0);
+    let axis_x = Vec3::new(3.0, 0.0, 0.0);
+    let axis_y = Vec3::new(0.0, 0.0, 1.5);
+    let ellipse = create_ellipse_nurbs(center, axis_x, axis_y);
+    for i in 0..=100 {
+        let p = Vec3::subtract(&ellipse.point(i as f32 / 100.0), &center);
+        // x^2 / a^2 + z^2 / b^2 = 1
+        let error = (p.x / 3.0).powi(2) + (p.z / 1.5).powi(2) - 1.0;
+        assert!(error.abs() < 2.0 * RADIUS_TOLERANCE);
+        assert!(p.y.abs() < 1e-6);
+    }
+    assert_close(&ellipse.point(0.0), &Vec3::add(&center, &axis_x), 1e-6);
+    assert_close(&ellipse.point(0.25), &Vec3::add(&center, &axis_y), 1e-5);
+}
+
+fn blend_inputs() -> (NurbsCurve, NurbsCurve) {
+    // Quarter circle ending at (0, 2, 0) and a rational cubic off to the side
+    let a = create_arc_nurbs(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        2.0,
+        0.0,
+        std::f32::consts::PI / 2.0,
+    );
+    let b = NurbsCurve::new(
+        3,
+        vec![
+            Vec4::new(-4.0, 5.0, 0.0, 1.0),
+            Vec4::new(-6.0, 12.0, 2.0, 2.0),
+            Vec4::new(-5.0, 7.0, 1.0, 1.0),
+            Vec4::new(-8.0, 9.0, 0.0, 1.0),
+        ],
+        vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+    );
+    (a, b)
+}
+
+#[wasm_bindgen_test]
+pub fn test_blend_continuity() {
+    let (a, b) = blend_inputs();
+    for continuity in [Continuity::G1, Continuity::G2, Continuity::G3] {
+        for end_b in [CurveEnd::Start, CurveEnd::End] {
+            let blend =
+                create_blend_nurbs(&a, CurveEnd::End, &b, end_b, continuity, 1.0, 0.5).unwrap();
+            assert_eq!(blend.degree, 2 * continuity as u32 + 1);
+            let t_b = if end_b == CurveEnd::Start { 0.0 } else { 1.0 };
+            // Going forward along the blend continues onto b
+            let b_direction = if end_b == CurveEnd::Start { 1.0 } else { -1.0 };
+
+            assert_close(&blend.point(0.0), &a.point(1.0), 1e-5);
+            assert_close(&blend.point(1.0), &b.point(t_b), 1e-5);
+            assert_close(&blend.tangent(0.0), &a.tangent(1.0), 1e-4);
+            assert_close(
+                &blend.tangent(1.0),
+                &Vec3::to_scaled(&b.tangent(t_b), b_direction),
+                1e-4,
+            );
+
+            if continuity != Continuity::G1 {
+                assert_close(&blend.curvature(0.0), &a.curvature(1.0), 1e-3);
+                assert_close(&blend.curvature(1.0), &b.curvature(t_b), 1e-3);
+            }
+
+            if continuity == Continuity::G3 {
+                // The blend matches the curve exactly after scaling its parameter
+                let ders_blend = blend.derivatives(0.0, 3);
+                let ders_a = a.derivatives(1.0, 3);
+                let speed = ders_blend[1].len() / ders_a[1].len();
+                let expected = Vec3::to_scaled(&ders_a[3], speed.powi(3));
+                assert_close(&ders_blend[3], &expected, 1e-3 * expected.len());
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_blend_bulge() {
+    let (a, b) = blend_inputs();
+    let chord = Vec3::subtract(&b.point(0.0), &a.point(1.0)).len();
+    for bulge in [0.5, 1.0, 2.0] {
+        let blend = create_blend_nurbs(
+            &a,
+            CurveEnd::End,
+            &b,
+            CurveEnd::Start,
+            Continuity::G1,
+            bulge,
+            1.0,
+        )
+        .unwrap();
+        let speed = blend.derivatives(0.0, 1)[1].len();
+        assert!((speed - bulge * chord).abs() < 1e-3 * chord);
+    }
+    // Ends that touch cannot be blended
+    assert!(create_blend_nurbs(
+        &a,
+        CurveEnd::End,
+        &a,
+        CurveEnd::End,
+        Continuity::G1,
+        1.0,
+        1.0
+    )
+    .is_none());
+}
+
+#[wasm_bindgen_test]
+pub fn test_interpolated_curve() {
+    let points = vec![
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 2.0, 0.0),
+        Vec3::new(3.0, 2.5, 0.0),
+        Vec3::new(4.0, 0.0, 1.0),
+    ];
+    let passes_through = |curve: &NurbsCurve, points: &[Vec3]| {
+        for point in points {
+            let closest = (0..=2000)
+                .map(|i| Vec3::distance(&curve.point(i as f32 / 2000.0), point))
+                .fold(f32::MAX, f32::min);
+            assert!(closest < 1e-2, "missed {} by {}", point, closest);
+        }
+    };
+
+    let start_tangent = Vec3::new(0.0, 1.0, 0.0);
+    let end_tangent = Vec3::new(1.0, 0.0, 0.0);
+    let curve = create_interpolated_nurbs(
+        &points,
+        3,
+        Parameterization::Centripetal,
+        Some(start_tangent),
+        Some(end_tangent),
+        false,
+    )
+    .unwrap();
+    passes_through(&curve, &points);
+    assert_close(&curve.point(0.0), &points[0], 1e-5);
+    assert_close(&curve.point(1.0), &points[3], 1e-5);
+    assert_close(&curve.tangent(0.0), &start_tangent, 1e-4);
+    assert_close(&curve.tangent(1.0), &end_tangent, 1e-4);
+
+    // Closed curves accept the first point repeated at the end
+    let mut loop_points = points.clone();
+    loop_points.push(points[0]);
+    let closed = create_interpolated_nurbs(
+        &loop_points,
+        3,
+        Parameterization::ChordLength,
+        None,
+        None,
+        true,
+    )
+    .unwrap();
+    passes_through(&closed, &points);
+    assert_close(&closed.point(0.0), &closed.point(1.0), 1e-4);
+    assert_close(&closed.tangent(0.0), &closed.tangent(1.0), 1e-3);
+
+    // Repeated points cannot be interpolated
+    let repeated = vec![points[0], points[1], points[1], points[2]];
+    assert!(create_interpolated_nurbs(
+        &repeated,
+        3,
+        Parameterization::ChordLength,
+        None,
+        None,
+        false
+    )
+    .is_none());
+}
+
+/// Closest distance from the point to the curve, by dense sampling then ternary search
+fn distance_to_curve(curve: &NurbsCurve, point: &Vec3) -> f32 {
+    let (start, end) = curve.domain();
+    let at = |t: f32| Vec3::distance(&curve.point(t), point);
+    let samples = 400;
+    let step = (end - start) / samples as f32;
+    let nearest = (0..=samples)
+        .min_by(|a, b| at(start + step * *a as f32).total_cmp(&at(start + step * *b as f32)))
+        .unwrap();
+    let mut low = (start + step * (nearest as f32 - 1.0)).max(start);
+    let mut high = (start + step * (nearest as f32 + 1.0)).min(end);
+    for _ in 0..40 {
+        let a = low + (high - low) / 3.0;
+        let b = high - (high - low) / 3.0;
+        if at(a) < at(b) {
+            high = b;
+        } else {
+            low = a;
+        }
+    }
+    at((low + high) / 2.0)
+}
+
+#[wasm_bindgen_test]
+pub fn test_offset_arc_is_exact() {
+    let center = Vec3::new(1.0, 2.0, 0.0);
+    let normal = Vec3::new(0.0, 0.0, 1.0);
+    let arc = create_arc_nurbs(
+        center,
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        2.0,
+        0.3,
+        4.0,
+    );
+    // Counter clockwise arcs have their center on the left
+    let inside = create_offset_nurbs(&arc, 0.5, &normal, 1e-3).unwrap();
+    assert!(max_radius_error(&inside, &center, 1.5) < RADIUS_TOLERANCE);
+    assert_eq!(inside.weighted_controls.len(), arc.weighted_controls.len());
+    let outside = create_offset_nurbs(&arc, -0.5, &normal, 1e-3).unwrap();
+    assert!(max_radius_error(&outside, &center, 2.5) < RADIUS_TOLERANCE);
+
+    // Offsetting past the center leaves nothing
+    assert!(create_offset_nurbs(&arc, 2.5, &normal, 1e-3).is_none());
+}
+
+#[wasm_bindgen_test]
+pub fn test_offset_polyline() {
+    let normal = Vec3::new(0.0, 0.0, 1.0);
+    let line = NurbsCurve::new(
+        1,
+        vec![
+            Vec4::new_point(0.0, 0.0, 0.0),
+            Vec4::new_point(4.0, 0.0, 0.0),
+        ],
+        vec![0.0, 0.0, 1.0, 1.0],
+    );
+    let offset = create_offset_nurbs(&line, 1.0, &normal, 1e-3).unwrap();
+    assert_eq!(offset.degree, 1);
+    assert_close(&offset.point(0.0), &Vec3::new(0.0, 1.0, 0.0), 1e-5);
+    assert_close(&offset.point(1.0), &Vec3::new(4.0, 1.0, 0.0), 1e-5);
+
+    // Right angle turning left, the inner corner stays a sharp corner
+    let corner = NurbsCurve::new(
+        1,
+        vec![
+            Vec4::new_point(0.0, 0.0, 0.0),
+            Vec4::new_point(4.0, 0.0, 0.0),
+            Vec4::new_point(4.0, 4.0, 0.0),
+        ],
+        vec![0.0, 0.0, 0.5, 1.0, 1.0],
+    );
+    let inner = create_offset_nurbs(&corner, 1.0, &normal, 1e-3).unwrap();
+    assert_eq!(inner.weighted_controls.len(), 3);
+    assert_close(
+        &inner.weighted_controls[1].to_vec3_safe(),
+        &Vec3::new(3.0, 1.0, 0.0),
+        1e-5,
+    );
+
+    // Two inner corners close together, the offset of the short middle segment forms a loop that is cut away
+    let notch = NurbsCurve::new(
+        1,
+        vec![
+            Vec4::new_point(0.0, 0.0, 0.0),
+            Vec4::new_point(4.0, 0.0, 0.0),
+            Vec4::new_point(4.0, 0.5, 0.0),
+            Vec4::new_point(0.0, 4.0, 0.0),
+        ],
+        vec![0.0, 0.0, 1.0, 2.0, 3.0, 3.0],
+    );
+    let trimmed = create_offset_nurbs(&notch, 1.0, &normal, 1e-3).unwrap();
+    assert_eq!(trimmed.weighted_controls.len(), 3);
+    assert_close(
+        &trimmed.weighted_controls[1].to_vec3_safe(),
+        &Vec3::new(1.90998, 1.0, 0.0),
+        1e-4,
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_offset_freeform_within_tolerance() {
+    let normal = Vec3::new(0.0, 0.0, 1.0);
+    let curve = NurbsCurve::new(
+        3,
+        vec![
+            Vec4::new_point(0.0, 0.0, 0.0),
+            Vec4::new_point(1.0, 2.0, 0.0),
+            Vec4::new_point(3.0, -1.0, 0.0),
+            Vec4::new_point(5.0, 1.0, 0.0),
+            Vec4::new_point(6.0, 0.0, 0.0),
+        ],
+        vec![0.0, 0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0, 1.0],
+    );
+    let tolerance = 1e-3;
+    let offset = create_offset_nurbs(&curve, 0.2, &normal, tolerance).unwrap();
+    for i in 0..=50 {
+        let t = i as f32 / 50.0;
+        let derivatives = curve.derivatives(t, 1);
+        let left = Vec3::cross(&normal, &derivatives[1]).to_normalized();
+        let expected = Vec3::add(&derivatives[0], &Vec3::to_scaled(&left, 0.2));
+        let distance = distance_to_curve(&offset, &expected);
+        assert!(distance < 2.0 * tolerance, "off by {} at {}", distance, t);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_offset_out_of_tolerance() {
+    let normal = Vec3::new(0.0, 0.0, 1.0);
+    let curve = NurbsCurve::new(
+        3,
+        vec![
+            Vec4::new_point(0.0, 0.0, 0.0),
+            Vec4::new_point(1.0, 2.0, 0.0),
+            Vec4::new_point(3.0, -1.0, 0.0),
+            Vec4::new_point(5.0, 1.0, 0.0),
+        ],
+        vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+    );
+    // Beyond what f32 can fit
+    assert!(create_offset_nurbs(&curve, 0.2, &normal, 1e-9).is_none());
+}
+
+#[wasm_bindgen_test]
+pub fn test_offset_trims_concave_loops() {
+    // Sharp V, offsetting inward would cross itself near the tip
+    let normal = Vec3::new(0.0, 0.0, 1.0);
+    let curve = NurbsCurve::new(
+        2,
+        vec![
+            Vec4::new_point(-2.0, 2.0, 0.0),
+            Vec4::new_point(0.0, -2.0, 0.0),
+            Vec4::new_point(2.0, 2.0, 0.0),
+        ],
+        vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+    );
+    let distance = 0.5;
+    let offset = create_offset_nurbs(&curve, distance, &normal, 1e-3).unwrap();
+
+    // Every point of the trimmed offset is at least the distance from the original
+    let (start, end) = offset.domain();
+    for i in 0..=200 {
+        let point = offset.point(start + (end - start) * i as f32 / 200.0);
+        let closest = distance_to_curve(&curve, &point);
+        assert!(
+            closest > distance - 2e-2,
+            "{} is only {} away",
+            point,
+            closest
+        );
+    }
+    // Symmetric curve, so the cut lands on the axis
+    let middle = offset.point((start + end) / 2.0);
+    assert!(middle.x.abs() < 1e-3, "cut at {}", middle);
+}
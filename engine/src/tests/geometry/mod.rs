@@ -0,0 +1,2 @@
+pub mod curve_generators;
+pub mod surface_generators;
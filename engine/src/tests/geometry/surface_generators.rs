@@ -0,0 +1,600 @@
+use crate::{
+    geometry::{
+        curve_generators::arc::create_arc_nurbs,
+        surface_generators::{
+            cylinder::{create_cone_nurbs, create_cylinder_nurbs},
+            extrude::{create_cap_nurbs, create_extrusion_nurbs, create_extrusion_to_point_nurbs},
+            loft::{create_loft_nurbs, LoftOptions, LoftStyle},
+            rectangle::create_rectangle_nurbs,
+            revolve::create_revolve_nurbs,
+            sphere::create_sphere_nurbs,
+            sweep1::{create_sweep1_nurbs, SweepFrame},
+            sweep2::{create_sweep2_nurbs, Sweep2Options},
+            torus::create_torus_nurbs,
+        },
+        utils::placement,
+    },
+    math::{
+        linear_algebra::{mat4::Mat4, vec3::Vec3, vec4::Vec4},
+        nurbs::{curve::NurbsCurve, surface::NurbsSurface},
+    },
+};
+
+use crate::tests::utils::assert_close;
+
+use wasm_bindgen_test::*;
+
+/// Points on an 11 by 11 grid over the domain
+fn grid_points(surface: &NurbsSurface) -> Vec<(f32, f32, Vec3)> {
+    let (u0, u1) = surface.domain_u();
+    let (v0, v1) = surface.domain_v();
+    let mut res = Vec::new();
+    for i in 0..=10 {
+        for j in 0..=10 {
+            let u = u0 + (u1 - u0) * i as f32 / 10.0;
+            let v = v0 + (v1 - v0) * j as f32 / 10.0;
+            res.push((u, v, surface.point(u, v)));
+        }
+    }
+    res
+}
+
+fn line(a: Vec3, b: Vec3) -> NurbsCurve {
+    NurbsCurve::new(
+        1,
+        vec![a.append(1.0), b.append(1.0)],
+        vec![0.0, 0.0, 1.0, 1.0],
+    )
+}
+
+#[wasm_bindgen_test]
+pub fn test_revolve_cylinder() {
+    let profile = line(Vec3::new(1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 2.0));
+    let surface = create_revolve_nurbs(
+        &profile,
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        0.0,
+        2.0 * std::f32::consts::PI,
+    );
+    assert_eq!(
+        surface.weighted_controls.len(),
+        (surface.control_count_u * surface.control_count_v) as usize
+    );
+    for (u, _, point) in grid_points(&surface) {
+        let radius = Vec3::new(point.x, point.y, 0.0).len();
+        assert!((radius - 1.0).abs() < 1e-5);
+        assert!((point.z - 2.0 * u).abs() < 1e-5);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_revolve_sphere() {
+    // Half circle in the xz plane, from the south pole to the north pole
+    let profile = create_arc_nurbs(
+        Vec3::new(0.0, 0.0, 1.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        2.0,
+        std::f32::consts::PI,
+        2.0 * std::f32::consts::PI,
+    );
+    let surface = create_revolve_nurbs(
+        &profile,
+        Vec3::new(0.0, 0.0, 5.0),
+        Vec3::new(0.0, 0.0, 3.0),
+        0.5,
+        4.0,
+    );
+    for (_, _, point) in grid_points(&surface) {
+        let radius = Vec3::distance(&point, &Vec3::new(0.0, 0.0, 1.0));
+        assert!((radius - 2.0).abs() < 1e-5);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_revolve_starts_at_start_angle() {
+    let profile = NurbsCurve::new(
+        2,
+        vec![
+            Vec4::new(1.0, 0.0, 0.0, 1.0),
+            Vec4::new(4.0, 0.0, 2.0, 2.0),
+            Vec4::new(1.0, 0.0, 2.0, 1.0),
+        ],
+        vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+    );
+    let angle = std::f32::consts::FRAC_PI_2;
+    let surface = create_revolve_nurbs(
+        &profile,
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        angle,
+        std::f32::consts::PI,
+    );
+    for i in 0..=10 {
+        let t = i as f32 / 10.0;
+        let p = profile.point(t);
+        // Rotated a quarter turn counter clockwise around z
+        assert_close(&surface.point(t, 0.0), &Vec3::new(-p.y, p.x, p.z), 1e-5);
+        assert_close(&surface.point(t, 1.0), &Vec3::new(-p.x, -p.y, p.z), 1e-5);
+    }
+}
+
+fn circle_section(z: f32, radius: f32) -> NurbsCurve {
+    create_arc_nurbs(
+        Vec3::new(0.0, 0.0, z),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        radius,
+        0.0,
+        2.0 * std::f32::consts::PI,
+    )
+}
+
+/// Closest distance from point to the surface, by searching finer grids around the best sample
+fn distance_to_surface(surface: &NurbsSurface, point: &Vec3) -> f32 {
+    let (mut u0, mut u1) = surface.domain_u();
+    let (mut v0, mut v1) = surface.domain_v();
+    let (domain_u, domain_v) = (surface.domain_u(), surface.domain_v());
+    let mut closest = f32::MAX;
+    for _ in 0..6 {
+        let (mut best_u, mut best_v) = (u0, v0);
+        for i in 0..=40 {
+            for j in 0..=40 {
+                let u = u0 + (u1 - u0) * i as f32 / 40.0;
+                let v = v0 + (v1 - v0) * j as f32 / 40.0;
+                let distance = Vec3::subtract(&surface.point(u, v), point).len();
+                if distance < closest {
+                    closest = distance;
+                    (best_u, best_v) = (u, v);
+                }
+            }
+        }
+        let (step_u, step_v) = ((u1 - u0) / 20.0, (v1 - v0) / 20.0);
+        (u0, u1) = (
+            (best_u - step_u).max(domain_u.0),
+            (best_u + step_u).min(domain_u.1),
+        );
+        (v0, v1) = (
+            (best_v - step_v).max(domain_v.0),
+            (best_v + step_v).min(domain_v.1),
+        );
+    }
+    closest
+}
+
+#[wasm_bindgen_test]
+pub fn test_loft_passes_through_sections() {
+    let sections = vec![
+        circle_section(0.0, 1.0),
+        line(Vec3::new(-1.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 1.0)),
+        circle_section(3.0, 2.0),
+    ];
+    for style in [LoftStyle::Normal, LoftStyle::Tight] {
+        let options = LoftOptions {
+            style,
+            ..Default::default()
+        };
+        let surface = create_loft_nurbs(&sections, &options).unwrap();
+        assert_eq!(
+            surface.weighted_controls.len(),
+            (surface.control_count_u * surface.control_count_v) as usize
+        );
+        assert_eq!(surface.degree_u, 2);
+        assert_eq!(surface.degree_v, 2);
+        for section in sections.iter() {
+            for i in 0..=8 {
+                let point = section.point(i as f32 / 8.0);
+                assert!(distance_to_surface(&surface, &point) < 1e-3);
+            }
+        }
+        // The first and last sections are the v edges
+        for (u, v, point) in grid_points(&surface) {
+            if v == 0.0 || v == 1.0 {
+                let expected = sections[if v == 0.0 { 0 } else { 2 }].point(u);
+                assert_close(&point, &expected, 1e-4);
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_loose_loft_uses_section_controls() {
+    let sections: Vec<NurbsCurve> = (0..5)
+        .map(|i| circle_section(i as f32, 1.0 + (i % 2) as f32))
+        .collect();
+    let options = LoftOptions {
+        style: LoftStyle::Loose,
+        ..Default::default()
+    };
+    let surface = create_loft_nurbs(&sections, &options).unwrap();
+    assert_eq!(surface.degree_v, 3);
+    assert_eq!(surface.control_count_v, 5);
+    assert_eq!(
+        &surface.weighted_controls[..surface.control_count_u as usize],
+        &sections[0].weighted_controls[..]
+    );
+    // Stays inside the hull of the sections
+    for (_, _, point) in grid_points(&surface) {
+        let radius = Vec3::new(point.x, point.y, 0.0).len();
+        assert!(radius > 1.0 - 1e-4 && radius < 2.0 + 1e-4);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_closed_loft() {
+    // Four lines around a square, the loft is a closed tube
+    let corners = [
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Vec3::new(-1.0, 0.0, 0.0),
+        Vec3::new(0.0, -1.0, 0.0),
+    ];
+    let sections: Vec<NurbsCurve> = corners
+        .iter()
+        .map(|c| line(*c, Vec3::add(c, &Vec3::new(0.0, 0.0, 2.0))))
+        .collect();
+    for style in [LoftStyle::Normal, LoftStyle::Loose, LoftStyle::Tight] {
+        let options = LoftOptions {
+            style,
+            closed: true,
+            ..Default::default()
+        };
+        let surface = create_loft_nurbs(&sections, &options).unwrap();
+        let (v0, v1) = surface.domain_v();
+        for i in 0..=10 {
+            let u = i as f32 / 10.0;
+            assert_close(&surface.point(u, v0), &surface.point(u, v1), 1e-4);
+            // Smooth across the seam
+            let start = surface.derivatives(u, v0, 1)[0][1].to_normalized();
+            let end = surface.derivatives(u, v1, 1)[0][1].to_normalized();
+            assert_close(&start, &end, 1e-3);
+        }
+        if style != LoftStyle::Loose {
+            for corner in corners.iter() {
+                assert!(distance_to_surface(&surface, corner) < 1e-3);
+            }
+        }
+    }
+    let too_few = LoftOptions {
+        closed: true,
+        ..Default::default()
+    };
+    assert!(create_loft_nurbs(&sections[..2], &too_few).is_none());
+}
+
+#[wasm_bindgen_test]
+pub fn test_rebuilt_loft() {
+    let sections = vec![circle_section(0.0, 1.0), circle_section(1.0, 1.5)];
+    let options = LoftOptions {
+        rebuild_point_count: 12,
+        ..Default::default()
+    };
+    let surface = create_loft_nurbs(&sections, &options).unwrap();
+    assert_eq!(surface.degree_u, 3);
+    assert_eq!(surface.control_count_u, 12);
+    assert_eq!(surface.degree_v, 1);
+    for (u, v, point) in grid_points(&surface) {
+        let radius = Vec3::new(point.x, point.y, 0.0).len();
+        assert!(
+            (radius - (1.0 + 0.5 * v)).abs() < 2e-2,
+            "{} at {} {}",
+            radius,
+            u,
+            v
+        );
+    }
+}
+
+/// Bump from a to a + (2, 0, 0) with height 1 along up
+fn bump(a: Vec3, up: Vec3) -> NurbsCurve {
+    let peak = Vec3::add(
+        &Vec3::add(&a, &Vec3::new(1.0, 0.0, 0.0)),
+        &Vec3::to_scaled(&up, 2.0),
+    );
+    NurbsCurve::new(
+        2,
+        vec![
+            a.append(1.0),
+            peak.append(1.0),
+            Vec3::add(&a, &Vec3::new(2.0, 0.0, 0.0)).append(1.0),
+        ],
+        vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+    )
+}
+
+#[wasm_bindgen_test]
+pub fn test_sweep2_follows_rails() {
+    let rail_a = line(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 4.0));
+    let rail_b = line(Vec3::new(2.0, 0.0, 0.0), Vec3::new(4.0, 0.0, 4.0));
+    let section = bump(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+
+    for keep_height in [false, true] {
+        let options = Sweep2Options {
+            keep_height,
+            closed: false,
+        };
+        let surface =
+            create_sweep2_nurbs(&rail_a, &rail_b, std::slice::from_ref(&section), &options)
+                .unwrap();
+        let (u0, u1) = surface.domain_u();
+        for (u, v, point) in grid_points(&surface) {
+            if u == u0 {
+                assert_close(&point, &rail_a.point(v), 1e-3);
+            }
+            if u == u1 {
+                assert_close(&point, &rail_b.point(v), 1e-3);
+            }
+            if v == 0.0 {
+                assert_close(&point, &section.point(u), 1e-4);
+            }
+        }
+        // The rails are twice as far apart at the end
+        let height = surface.point((u0 + u1) / 2.0, 1.0).y;
+        let expected = if keep_height { 1.0 } else { 2.0 };
+        assert!((height - expected).abs() < 1e-3, "height {}", height);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_sweep2_rails_meet() {
+    let rail_a = line(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 4.0));
+    let rail_b = line(Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 4.0));
+    let section = bump(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+
+    for keep_height in [false, true] {
+        let options = Sweep2Options {
+            keep_height,
+            closed: false,
+        };
+        let surface =
+            create_sweep2_nurbs(&rail_a, &rail_b, std::slice::from_ref(&section), &options)
+                .unwrap();
+        // The section shrinks to the point where the rails meet
+        for (_, v, point) in grid_points(&surface) {
+            if v == 1.0 {
+                assert_close(&point, &Vec3::new(0.0, 0.0, 4.0), 1e-3);
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_sweep2_blends_sections() {
+    let rail_a = line(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 4.0));
+    let rail_b = line(Vec3::new(2.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 4.0));
+    let low = bump(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    let high = bump(Vec3::new(0.0, 0.0, 4.0), Vec3::new(0.0, 3.0, 0.0));
+    // Given out of order on purpose
+    let surface =
+        create_sweep2_nurbs(&rail_a, &rail_b, &[high, low], &Sweep2Options::default()).unwrap();
+    let (u0, u1) = surface.domain_u();
+    let middle = (u0 + u1) / 2.0;
+    for (v, expected) in [(0.0, 1.0), (0.5, 2.0), (1.0, 3.0)] {
+        assert!((surface.point(middle, v).y - expected).abs() < 1e-3);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_sweep2_closed_rails() {
+    let circle = |radius: f32| {
+        create_arc_nurbs(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            radius,
+            0.0,
+            2.0 * std::f32::consts::PI,
+        )
+    };
+    let section = bump(Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+    let options = Sweep2Options {
+        keep_height: false,
+        closed: true,
+    };
+    let surface = create_sweep2_nurbs(
+        &circle(1.0),
+        &circle(3.0),
+        std::slice::from_ref(&section),
+        &options,
+    )
+    .unwrap();
+    let (u0, u1) = surface.domain_u();
+    for (u, v, point) in grid_points(&surface) {
+        let radius = Vec3::new(point.x, point.y, 0.0).len();
+        if u == u0 {
+            assert!((radius - 1.0).abs() < 1e-2);
+        }
+        if u == u1 {
+            assert!((radius - 3.0).abs() < 1e-2);
+        }
+        // Same bump all the way around
+        assert!(
+            (point.z - section.point(u).z).abs() < 1e-2,
+            "{} at {} {}",
+            point.z,
+            u,
+            v
+        );
+        assert_close(&surface.point(u, 0.0), &surface.point(u, 1.0), 1e-4);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_extrusions() {
+    let curve = bump(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    let direction = Vec3::new(0.0, 0.5, 3.0);
+    let surface = create_extrusion_nurbs(&curve, &direction);
+    for (u, v, point) in grid_points(&surface) {
+        let expected = Vec3::add(&curve.point(u), &Vec3::to_scaled(&direction, v));
+        assert_close(&point, &expected, 1e-5);
+    }
+
+    let apex = Vec3::new(1.0, 1.0, 4.0);
+    let surface = create_extrusion_to_point_nurbs(&curve, &apex);
+    for (u, v, point) in grid_points(&surface) {
+        let expected = Vec3::add(
+            &curve.point(u),
+            &Vec3::to_scaled(&Vec3::subtract(&apex, &curve.point(u)), v),
+        );
+        assert_close(&point, &expected, 1e-5);
+    }
+
+    // Only closed curves get caps
+    assert!(create_cap_nurbs(&curve).is_none());
+    let circle = circle_section(2.0, 1.5);
+    let cap = create_cap_nurbs(&circle).unwrap();
+    for (u, v, point) in grid_points(&cap) {
+        assert!((point.z - 2.0).abs() < 1e-5);
+        let radius = Vec3::new(point.x, point.y, 0.0).len();
+        assert!(
+            (radius - 1.5 * (1.0 - v)).abs() < 1e-4,
+            "{} at {} {}",
+            radius,
+            u,
+            v
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_sweep1_pipe() {
+    let rail = create_arc_nurbs(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        5.0,
+        0.0,
+        std::f32::consts::PI,
+    );
+    // Circle around the start of the rail, facing along it
+    let section = create_arc_nurbs(
+        Vec3::new(5.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        1.0,
+        0.0,
+        2.0 * std::f32::consts::PI,
+    );
+    for frame in [SweepFrame::Frenet, SweepFrame::RotationMinimizing] {
+        let surface = create_sweep1_nurbs(&rail, &section, frame);
+        for (_, _, point) in grid_points(&surface) {
+            // Distance from the rail circle
+            let ring = Vec3::new(point.x, point.y, 0.0);
+            let to_rail = Vec3::subtract(&point, &Vec3::to_scaled(&ring, 5.0 / ring.len()));
+            assert!((to_rail.len() - 1.0).abs() < 1e-2, "{}", to_rail.len());
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_sweep1_frames() {
+    // Planar rail that changes which way it bends
+    let rail = NurbsCurve::new(
+        3,
+        vec![
+            Vec4::new_point(0.0, 0.0, 0.0),
+            Vec4::new_point(3.0, 3.0, 0.0),
+            Vec4::new_point(6.0, -3.0, 0.0),
+            Vec4::new_point(9.0, 0.0, 0.0),
+        ],
+        vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+    );
+    let section = line(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+
+    // Rotation minimizing frames never twist out of the plane, Frenet frames flip at the inflection
+    let minimizing = create_sweep1_nurbs(&rail, &section, SweepFrame::RotationMinimizing);
+    let frenet = create_sweep1_nurbs(&rail, &section, SweepFrame::Frenet);
+    for (u, v, point) in grid_points(&minimizing) {
+        let expected = Vec3::add(&rail.point(v), &Vec3::new(0.0, 0.0, u));
+        assert_close(&point, &expected, 1e-3);
+    }
+    assert_close(&frenet.point(1.0, 1.0), &Vec3::new(9.0, 0.0, -1.0), 1e-3);
+}
+
+/// Tilted placement with the local axes in world space
+fn tilted_placement() -> (Mat4, Vec3, Vec3, Vec3, Vec3) {
+    let origin = Vec3::new(1.0, -2.0, 3.0);
+    let axis_z = Vec3::new(0.0, 1.0, 1.0);
+    // Not perpendicular to axis_z on purpose
+    let axis_x = Vec3::new(1.0, 1.0, 0.0);
+    let m = placement(&origin, &axis_x, &axis_z).unwrap();
+    let x = m.transform_vector(&Vec3::new(1.0, 0.0, 0.0));
+    let y = m.transform_vector(&Vec3::new(0.0, 1.0, 0.0));
+    let z = m.transform_vector(&Vec3::new(0.0, 0.0, 1.0));
+    (m, origin, x, y, z)
+}
+
+#[wasm_bindgen_test]
+pub fn test_placement() {
+    let (_, _, x, y, z) = tilted_placement();
+    for (a, b) in [(&x, &y), (&y, &z), (&z, &x)] {
+        assert!(Vec3::dot(a, b).abs() < 1e-6);
+    }
+    for axis in [&x, &y, &z] {
+        assert!((axis.len() - 1.0).abs() < 1e-6);
+    }
+    assert_close(&z, &Vec3::new(0.0, 1.0, 1.0).to_normalized(), 1e-6);
+    assert!(placement(&x, &y, &Vec3::new(0.0, 0.0, 0.0)).is_none());
+}
+
+#[wasm_bindgen_test]
+pub fn test_sphere_and_torus() {
+    let (m, origin, x, _, z) = tilted_placement();
+    let sphere = create_sphere_nurbs(&m, 2.0);
+    for (_, _, point) in grid_points(&sphere) {
+        assert!((Vec3::subtract(&point, &origin).len() - 2.0).abs() < 1e-4);
+    }
+    let (u0, u1) = sphere.domain_u();
+    assert_close(
+        &sphere.point(u0, 0.0),
+        &Vec3::add(&origin, &Vec3::to_scaled(&z, -2.0)),
+        1e-4,
+    );
+    assert_close(
+        &sphere.point(u1, 0.0),
+        &Vec3::add(&origin, &Vec3::to_scaled(&z, 2.0)),
+        1e-4,
+    );
+    // The seam is toward axis_x
+    assert_close(
+        &sphere.point((u0 + u1) / 2.0, 0.0),
+        &Vec3::add(&origin, &Vec3::to_scaled(&x, 2.0)),
+        1e-4,
+    );
+
+    let torus = create_torus_nurbs(&m, 3.0, 1.0);
+    for (_, _, point) in grid_points(&torus) {
+        let d = Vec3::subtract(&point, &origin);
+        let height = Vec3::dot(&d, &z);
+        let ring = Vec3::subtract(&d, &Vec3::to_scaled(&z, height)).len();
+        let tube = ((ring - 3.0).powi(2) + height.powi(2)).sqrt();
+        assert!((tube - 1.0).abs() < 1e-4);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_cylinder_cone_and_rectangle() {
+    let (m, origin, x, y, z) = tilted_placement();
+    let check_cone = |surface: &NurbsSurface, base: f32, top: f32, height: f32| {
+        for (u, _, point) in grid_points(surface) {
+            let d = Vec3::subtract(&point, &origin);
+            let along = Vec3::dot(&d, &z);
+            let radius = Vec3::subtract(&d, &Vec3::to_scaled(&z, along)).len();
+            assert!((along - height * u).abs() < 1e-4);
+            assert!((radius - (base + (top - base) * u)).abs() < 1e-4);
+        }
+    };
+    check_cone(&create_cylinder_nurbs(&m, 1.5, 4.0), 1.5, 1.5, 4.0);
+    check_cone(&create_cone_nurbs(&m, 2.0, 0.0, 3.0), 2.0, 0.0, 3.0);
+    check_cone(&create_cone_nurbs(&m, 2.0, 1.0, 3.0), 2.0, 1.0, 3.0);
+
+    let rectangle = create_rectangle_nurbs(&m, 2.0, 3.0);
+    for (u, v, point) in grid_points(&rectangle) {
+        let expected = Vec3::add(
+            &origin,
+            &Vec3::add(&Vec3::to_scaled(&x, 2.0 * u), &Vec3::to_scaled(&y, 3.0 * v)),
+        );
+        assert_close(&point, &expected, 1e-5);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    gpu_mesh_intersection::section::{chain_segments, SectionLevels, SectionSegment},
+    math::linear_algebra::vec3::Vec3,
+};
+
+use wasm_bindgen_test::*;
+
+fn segment(level: i32, a: [f32; 3], b: [f32; 3]) -> SectionSegment {
+    SectionSegment {
+        a,
+        level,
+        b,
+        triangle: 0,
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_chain_closed_loop() {
+    // A square with its segments shuffled and some of them reversed
+    let corners = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0],
+    ];
+    let segments = [
+        segment(0, corners[2], corners[1]),
+        segment(0, corners[3], corners[0]),
+        segment(0, corners[0], corners[1]),
+        segment(0, corners[3], corners[2]),
+    ];
+    let chains = chain_segments(&segments);
+    assert_eq!(chains.len(), 1);
+    assert!(chains[0].closed);
+    assert_eq!(chains[0].points.len(), 4);
+    // Neighbours in the chain are neighbours on the square
+    let points = &chains[0].points;
+    for i in 0..4 {
+        let distance = Vec3::distance(&points[i], &points[(i + 1) % 4]);
+        assert_eq!(distance, 1.0);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_chain_open_and_levels() {
+    let segments = [
+        segment(0, [2.0, 0.0, 0.0], [3.0, 0.0, 0.0]),
+        segment(0, [0.0, 0.0, 0.0], [1.0, 0.0, 0.0]),
+        // Nothing left of a vertex on the plane
+        segment(0, [5.0, 0.0, 0.0], [5.0, 0.0, 0.0]),
+        segment(0, [2.0, 0.0, 0.0], [1.0, 0.0, 0.0]),
+        // Same points one level up stay apart
+        segment(1, [1.0, 0.0, 0.0], [2.0, 0.0, 0.0]),
+    ];
+    let mut chains = chain_segments(&segments);
+    chains.sort_by_key(|chain| chain.level);
+    assert_eq!(chains.len(), 2);
+
+    assert!(!chains[0].closed);
+    let xs: Vec<f32> = chains[0].points.iter().map(|point| point.x).collect();
+    assert!(xs == [0.0, 1.0, 2.0, 3.0] || xs == [3.0, 2.0, 1.0, 0.0]);
+
+    assert_eq!(chains[1].level, 1);
+    assert_eq!(chains[1].points.len(), 2);
+}
+
+#[wasm_bindgen_test]
+fn test_section_levels() {
+    let origin = Vec3::new(1.0, 2.0, 3.0);
+    assert!(SectionLevels::plane(origin, Vec3::new(0.0, 0.0, 0.0)).is_none());
+    assert!(SectionLevels::contours(origin, Vec3::new(0.0, 0.0, 1.0), 0.0).is_none());
+
+    let levels = SectionLevels::contours(origin, Vec3::new(0.0, 0.0, 2.0), 0.5).unwrap();
+    assert_eq!(*levels.get_direction(), Vec3::new(0.0, 0.0, 1.0));
+    assert_eq!(levels.get_origin(-2), Vec3::new(1.0, 2.0, 2.0));
+}
@@ -0,0 +1,45 @@
+use crate::{
+    gpu_acceleration_structures::mesh_bbh::NODE_SIZE,
+    gpu_ray_tracing::{intersect_mesh::GpuRay, intersection::Intersection},
+    math::{geometry::ray::Ray, linear_algebra::vec3::Vec3},
+    tests::utils::{read_f32s, read_u32, wgsl_struct_layout},
+};
+
+use wasm_bindgen_test::*;
+
+const SHADER: &str = include_str!("../../gpu_ray_tracing/intersect_mesh.wgsl");
+
+#[wasm_bindgen_test]
+fn test_hit_layout() {
+    let (offsets, size) = wgsl_struct_layout(SHADER, "Hit");
+    let hit = Intersection::new(Vec3::new(1.0, 2.0, 3.0), 4.0, Vec3::new(5.0, 6.0, 7.0), 8);
+    let bytes = bytemuck::bytes_of(&hit);
+    assert_eq!(bytes.len(), size);
+    assert_eq!(read_f32s(bytes, offsets["point"], 3), [1.0, 2.0, 3.0]);
+    assert_eq!(read_f32s(bytes, offsets["t"], 1), [4.0]);
+    assert_eq!(read_f32s(bytes, offsets["barycentric"], 3), [5.0, 6.0, 7.0]);
+    assert_eq!(read_u32(bytes, offsets["triangle"]), 8);
+}
+
+#[wasm_bindgen_test]
+fn test_ray_layout() {
+    let (offsets, size) = wgsl_struct_layout(SHADER, "Ray");
+    let ray = GpuRay::from(&Ray::new(
+        Vec3::new(1.0, 2.0, 3.0),
+        Vec3::new(0.0, 0.0, 2.0),
+    ));
+    let bytes = bytemuck::bytes_of(&ray);
+    assert_eq!(bytes.len(), size);
+    assert_eq!(read_f32s(bytes, offsets["origin"], 3), [1.0, 2.0, 3.0]);
+    assert_eq!(read_f32s(bytes, offsets["direction"], 3), [0.0, 0.0, 1.0]);
+}
+
+#[wasm_bindgen_test]
+fn test_node_layout() {
+    // The generators write nodes NODE_SIZE apart
+    let (offsets, size) = wgsl_struct_layout(SHADER, "Node");
+    assert_eq!(size, NODE_SIZE as usize);
+    assert_eq!(offsets["min_corner"], 0);
+    assert_eq!(offsets["max_corner"], 16);
+    assert_eq!(offsets["left_child"], 36);
+}
@@ -0,0 +1 @@
+pub mod intersect_mesh;
@@ -0,0 +1,103 @@
+use crate::{
+    gpu_samplers::{
+        adaptive::{create_curve_sample_params, create_surface_sample_params},
+        params::{SamplingTolerance, MAX_SEGMENTS_PER_SPAN},
+    },
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{curve::NurbsCurve, surface::NurbsSurface},
+    },
+    tests::utils::quarter_circle,
+};
+
+use wasm_bindgen_test::*;
+
+fn max_deviation(curve: &NurbsCurve, params: &[f32]) -> f32 {
+    params
+        .windows(2)
+        .map(|w| {
+            let mid = curve.point((w[0] + w[1]) / 2.0);
+            let chord = Vec3::add(&curve.point(w[0]), &curve.point(w[1]));
+            // Distance from the circle to the chord, measured at the middle of the chord
+            mid.len() - chord.len() / 2.0
+        })
+        .fold(0.0, f32::max)
+}
+
+#[wasm_bindgen_test]
+fn test_straight_curve_uses_one_segment_per_span() {
+    let line = NurbsCurve::new(
+        1,
+        vec![
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+            Vec4::new(1.0, 0.0, 0.0, 1.0),
+            Vec4::new(3.0, 0.0, 0.0, 1.0),
+        ],
+        vec![0.0, 0.0, 1.0, 2.0, 2.0],
+    );
+    let params = create_curve_sample_params(&line, &SamplingTolerance::default());
+    assert_eq!(params, vec![0.0, 1.0, 2.0]);
+}
+
+#[wasm_bindgen_test]
+fn test_params_cover_domain_in_order() {
+    let circle = quarter_circle();
+    let params = create_curve_sample_params(&circle, &SamplingTolerance::default());
+    assert_eq!(params[0], 0.0);
+    assert_eq!(*params.last().unwrap(), 1.0);
+    assert!(params.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[wasm_bindgen_test]
+fn test_tighter_tolerance_adds_samples() {
+    let circle = quarter_circle();
+    let loose = SamplingTolerance {
+        chordal_deviation: 0.01,
+        angle: std::f32::consts::PI,
+    };
+    let tight = SamplingTolerance {
+        chordal_deviation: 0.0001,
+        angle: std::f32::consts::PI,
+    };
+    let loose_params = create_curve_sample_params(&circle, &loose);
+    let tight_params = create_curve_sample_params(&circle, &tight);
+    assert!(tight_params.len() > loose_params.len());
+    assert!(max_deviation(&circle, &loose_params) <= loose.chordal_deviation);
+    assert!(max_deviation(&circle, &tight_params) <= tight.chordal_deviation);
+}
+
+#[wasm_bindgen_test]
+fn test_segment_count_is_bounded() {
+    let circle = quarter_circle();
+    let tolerance = SamplingTolerance {
+        chordal_deviation: 1e-9,
+        angle: 1e-6,
+    };
+    let params = create_curve_sample_params(&circle, &tolerance);
+    assert_eq!(params.len() as u32, MAX_SEGMENTS_PER_SPAN + 1);
+}
+
+#[wasm_bindgen_test]
+fn test_surface_only_refines_curved_direction() {
+    // Quarter cylinder, curved in u and straight in v
+    let w = std::f32::consts::FRAC_1_SQRT_2;
+    let mut weighted_controls = Vec::new();
+    for z in [0.0, 1.0] {
+        weighted_controls.push(Vec4::new(1.0, 0.0, z, 1.0));
+        weighted_controls.push(Vec4::new(w, w, z * w, w));
+        weighted_controls.push(Vec4::new(0.0, 1.0, z, 1.0));
+    }
+    let surface = NurbsSurface::new(
+        2,
+        1,
+        3,
+        2,
+        weighted_controls,
+        vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        vec![0.0, 0.0, 1.0, 1.0],
+    );
+    let (params_u, params_v) =
+        create_surface_sample_params(&surface, &SamplingTolerance::default());
+    assert!(params_u.len() > 2);
+    assert_eq!(params_v, vec![0.0, 1.0]);
+}
@@ -0,0 +1,3 @@
+pub mod adaptive;
+pub mod surface_sampler;
+pub mod trim;
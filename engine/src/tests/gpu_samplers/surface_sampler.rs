@@ -0,0 +1,154 @@
+use std::rc::Rc;
+
+use crate::{
+    geometry::{surface_generators::sphere::create_sphere_nurbs, utils::placement},
+    gpu_samplers::surface_sampler::{SurfaceDirectionInput, SurfaceSampler},
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::surface::NurbsSurface,
+    },
+    render::renderer::Renderer,
+    tests::utils::assert_close,
+    utils::PendingReadback,
+};
+
+use wasm_bindgen_test::*;
+
+/// Positions, normals and (u, v) of each sample, u running fastest
+struct Samples {
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<[f32; 2]>,
+}
+
+async fn read_buffer(renderer: &Renderer, buffer: &wgpu::Buffer) -> Vec<f32> {
+    let device = renderer.get_device();
+    let encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("surface sampler test readback"),
+    });
+    PendingReadback::submit(device, renderer.get_queue(), encoder, buffer, buffer.size())
+        .read()
+        .await
+}
+
+async fn sample(nurbs: &NurbsSurface, params_u: &[f32], params_v: &[f32]) -> Samples {
+    let renderer = Rc::new(Renderer::new().await);
+    let sampler = SurfaceSampler::new(renderer.clone());
+    let (_, vertex_buffer, uv_buffer) = sampler.sample_surface(
+        &nurbs.weighted_controls,
+        &SurfaceDirectionInput::u(nurbs, params_u),
+        &SurfaceDirectionInput::v(nurbs, params_v),
+    );
+    let vertices = read_buffer(&renderer, &vertex_buffer).await;
+    let uvs = read_buffer(&renderer, &uv_buffer).await;
+    Samples {
+        positions: vertices
+            .chunks(8)
+            .map(|vertex| Vec3::new(vertex[0], vertex[1], vertex[2]))
+            .collect(),
+        normals: vertices
+            .chunks(8)
+            .map(|vertex| Vec3::new(vertex[4], vertex[5], vertex[6]))
+            .collect(),
+        uvs: uvs.chunks(2).map(|uv| [uv[0], uv[1]]).collect(),
+    }
+}
+
+fn uniform_params((start, end): (f32, f32), count: usize) -> Vec<f32> {
+    (0..count)
+        .map(|i| start + (end - start) * i as f32 / (count - 1) as f32)
+        .collect()
+}
+
+/// Rational quadratic by linear patch of the plane z = x + 2y
+fn plane() -> NurbsSurface {
+    let mut controls = Vec::new();
+    for (j, y) in [0.0, 1.0].into_iter().enumerate() {
+        for (i, x) in [0.0, 0.5, 2.0].into_iter().enumerate() {
+            let w = 1.0 + 0.5 * (i + j) as f32;
+            controls.push(Vec4::new(x * w, y * w, (x + 2.0 * y) * w, w));
+        }
+    }
+    NurbsSurface::new(
+        2,
+        1,
+        3,
+        2,
+        controls,
+        vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        vec![0.0, 0.0, 1.0, 1.0],
+    )
+}
+
+#[wasm_bindgen_test]
+async fn test_sample_plane() {
+    let nurbs = plane();
+    let params_u = [0.0, 0.1, 0.5, 0.75, 1.0];
+    let params_v = [0.0, 0.3, 1.0];
+    let samples = sample(&nurbs, &params_u, &params_v).await;
+    let expected_normal = Vec3::new(-1.0, -2.0, 1.0).to_normalized();
+    for (i, (position, normal)) in samples.positions.iter().zip(&samples.normals).enumerate() {
+        let (u, v) = (params_u[i % params_u.len()], params_v[i / params_u.len()]);
+        assert_close(position, &nurbs.point(u, v), 1e-5);
+        assert_close(normal, &nurbs.normal(u, v), 1e-4);
+        assert!(Vec3::dot(normal, &expected_normal).abs() > 1.0 - 1e-4);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_sample_sphere_normals() {
+    let center = Vec3::new(1.0, -2.0, 0.5);
+    let radius = 2.0;
+    let placement = placement(
+        &center,
+        &Vec3::new(1.0, 0.0, 0.0),
+        &Vec3::new(0.0, 1.0, 1.0),
+    )
+    .unwrap();
+    let nurbs = create_sphere_nurbs(&placement, radius);
+    // u runs between the poles, where the whole row of samples collapses to a point
+    let params_u = uniform_params(nurbs.domain_u(), 9);
+    let params_v = uniform_params(nurbs.domain_v(), 8);
+    let samples = sample(&nurbs, &params_u, &params_v).await;
+
+    let (start_u, end_u) = nurbs.domain_u();
+    let mut poles = 0;
+    for (i, (position, normal)) in samples.positions.iter().zip(&samples.normals).enumerate() {
+        let (u, v) = (params_u[i % params_u.len()], params_v[i / params_u.len()]);
+        assert_close(position, &nurbs.point(u, v), 1e-4);
+
+        if u == start_u || u == end_u {
+            // The partials vanish, the sampler borrows them from the neighbouring samples
+            poles += 1;
+        } else {
+            assert_close(normal, &nurbs.normal(u, v), 1e-3);
+        }
+        let outwards = Vec3::subtract(position, &center).to_normalized();
+        assert!(Vec3::dot(normal, &outwards).abs() > 1.0 - 1e-3);
+    }
+    assert!(poles > 0);
+
+    // Normals at the poles point the same way as the rest, all in or all out
+    let outwards_count = samples
+        .positions
+        .iter()
+        .zip(&samples.normals)
+        .filter(|(position, normal)| Vec3::dot(normal, &Vec3::subtract(position, &center)) > 0.0)
+        .count();
+    assert!(outwards_count == 0 || outwards_count == samples.normals.len());
+}
+
+#[wasm_bindgen_test]
+async fn test_sample_uvs() {
+    let nurbs = plane();
+    let params_u = [0.0, 0.2, 0.25, 0.9, 1.0];
+    let params_v = [0.0, 0.5, 0.6, 1.0];
+    let samples = sample(&nurbs, &params_u, &params_v).await;
+    assert_eq!(samples.uvs.len(), params_u.len() * params_v.len());
+    for (i, uv) in samples.uvs.iter().enumerate() {
+        assert_eq!(
+            *uv,
+            [params_u[i % params_u.len()], params_v[i / params_u.len()]]
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+
+use crate::{
+    gpu_samplers::trim::{
+        add_crossing_params, sample_trims, tessellate_trimmed_grid, TrimmedTessellation,
+    },
+    math::{
+        linear_algebra::vec4::Vec4,
+        nurbs::{
+            curve::NurbsCurve,
+            surface::SurfaceDirection,
+            trim::{SurfaceTrims, TrimLoop},
+        },
+    },
+};
+
+use wasm_bindgen_test::*;
+
+fn uniform_params(segments: usize) -> Vec<f32> {
+    (0..=segments).map(|i| i as f32 / segments as f32).collect()
+}
+
+/// Closed degree 1 loop through the (u, v) points
+fn polygon_loop(points: &[[f32; 2]]) -> TrimLoop {
+    let mut controls: Vec<Vec4> = points
+        .iter()
+        .map(|[u, v]| Vec4::new(*u, *v, 0.0, 1.0))
+        .collect();
+    controls.push(controls[0]);
+    let mut knots = vec![0.0];
+    knots.extend((0..controls.len()).map(|i| i as f32));
+    knots.push((controls.len() - 1) as f32);
+    TrimLoop::new(vec![NurbsCurve::new(1, controls, knots)])
+}
+
+/// Full rational quadratic circle
+fn circle_loop(center: [f32; 2], radius: f32) -> TrimLoop {
+    let w = std::f32::consts::FRAC_1_SQRT_2;
+    let corners = [
+        (1.0, 0.0, 1.0),
+        (1.0, 1.0, w),
+        (0.0, 1.0, 1.0),
+        (-1.0, 1.0, w),
+        (-1.0, 0.0, 1.0),
+        (-1.0, -1.0, w),
+        (0.0, -1.0, 1.0),
+        (1.0, -1.0, w),
+        (1.0, 0.0, 1.0),
+    ];
+    let controls = corners
+        .iter()
+        .map(|(x, y, weight)| {
+            Vec4::new(
+                (center[0] + x * radius) * weight,
+                (center[1] + y * radius) * weight,
+                0.0,
+                *weight,
+            )
+        })
+        .collect();
+    let knots = vec![
+        0.0, 0.0, 0.0, 0.25, 0.25, 0.5, 0.5, 0.75, 0.75, 1.0, 1.0, 1.0,
+    ];
+    TrimLoop::new(vec![NurbsCurve::new(2, controls, knots)])
+}
+
+fn tessellate(
+    params_u: &mut Vec<f32>,
+    params_v: &[f32],
+    trims: &SurfaceTrims,
+) -> TrimmedTessellation {
+    let loops = sample_trims(trims, 1.0);
+    add_crossing_params(params_u, params_v, &loops);
+    tessellate_trimmed_grid(params_u, params_v, trims, &loops)
+}
+
+fn vertex_uvs(
+    params_u: &[f32],
+    params_v: &[f32],
+    tessellation: &TrimmedTessellation,
+) -> Vec<[f32; 2]> {
+    params_v
+        .iter()
+        .flat_map(|v| params_u.iter().map(move |u| [*u, *v]))
+        .chain(tessellation.extra_uvs.iter().copied())
+        .collect()
+}
+
+fn triangle_area(uvs: &[[f32; 2]], triangle: &[u32]) -> f32 {
+    let [a, b, c] = [0, 1, 2].map(|i| uvs[triangle[i] as usize]);
+    ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])) / 2.0
+}
+
+/// Total length of edges used by only one triangle.
+/// Cracks and T junctions add edges there, so this is the length of the kept regions border only when the mesh is watertight.
+fn boundary_length(uvs: &[[f32; 2]], indices: &[u32]) -> f32 {
+    let mut edges: HashMap<(u32, u32), u32> = HashMap::new();
+    for triangle in indices.chunks(3) {
+        for i in 0..3 {
+            let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+            *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+        }
+    }
+    edges
+        .iter()
+        .filter(|(_, count)| **count == 1)
+        .map(|((a, b), _)| {
+            let (a, b) = (uvs[*a as usize], uvs[*b as usize]);
+            ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt()
+        })
+        .sum()
+}
+
+#[wasm_bindgen_test]
+fn test_untrimmed_grid_keeps_every_cell() {
+    let mut params_u = uniform_params(4);
+    let params_v = uniform_params(3);
+    let tessellation = tessellate(&mut params_u, &params_v, &SurfaceTrims::default());
+    assert!(tessellation.extra_uvs.is_empty());
+    assert_eq!(tessellation.indices.len(), 4 * 3 * 6);
+}
+
+#[wasm_bindgen_test]
+fn test_square_hole() {
+    let mut params_u = uniform_params(4);
+    let params_v = uniform_params(4);
+    let trims = SurfaceTrims::new(
+        None,
+        vec![polygon_loop(&[
+            [0.3, 0.3],
+            [0.7, 0.3],
+            [0.7, 0.7],
+            [0.3, 0.7],
+        ])],
+    );
+    let tessellation = tessellate(&mut params_u, &params_v, &trims);
+    let uvs = vertex_uvs(&params_u, &params_v, &tessellation);
+
+    let areas: Vec<f32> = tessellation
+        .indices
+        .chunks(3)
+        .map(|triangle| triangle_area(&uvs, triangle))
+        .collect();
+    assert!(areas.iter().all(|area| *area > 0.0));
+    assert!((areas.iter().sum::<f32>() - 0.84).abs() < 1e-5);
+    for triangle in tessellation.indices.chunks(3) {
+        let center =
+            [0, 1].map(|k| triangle.iter().map(|i| uvs[*i as usize][k]).sum::<f32>() / 3.0);
+        assert!(!(0.3..0.7).contains(&center[0]) || !(0.3..0.7).contains(&center[1]));
+    }
+    assert!((boundary_length(&uvs, &tessellation.indices) - 5.6).abs() < 1e-4);
+}
+
+#[wasm_bindgen_test]
+fn test_circular_outer_loop() {
+    let mut params_u = uniform_params(5);
+    let params_v = uniform_params(5);
+    let radius = 0.4;
+    let trims = SurfaceTrims::new(Some(circle_loop([0.5, 0.5], radius)), Vec::new());
+    let tessellation = tessellate(&mut params_u, &params_v, &trims);
+    let uvs = vertex_uvs(&params_u, &params_v, &tessellation);
+
+    for [u, v] in tessellation.extra_uvs.iter() {
+        let distance = ((u - 0.5).powi(2) + (v - 0.5).powi(2)).sqrt();
+        assert!((distance - radius).abs() < 2e-3);
+    }
+    let area: f32 = tessellation
+        .indices
+        .chunks(3)
+        .map(|triangle| triangle_area(&uvs, triangle))
+        .sum();
+    let exact = std::f32::consts::PI * radius * radius;
+    assert!(area < exact && area > exact * 0.99);
+    let perimeter = 2.0 * std::f32::consts::PI * radius;
+    let boundary = boundary_length(&uvs, &tessellation.indices);
+    assert!(boundary < perimeter && boundary > perimeter * 0.99);
+}
+
+#[wasm_bindgen_test]
+fn test_loop_on_grid_lines() {
+    // The outer loop runs along the domain border and the hole along grid lines
+    let mut params_u = uniform_params(4);
+    let params_v = uniform_params(4);
+    let trims = SurfaceTrims::new(
+        Some(polygon_loop(&[
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [1.0, 1.0],
+            [0.0, 1.0],
+        ])),
+        vec![polygon_loop(&[
+            [0.25, 0.25],
+            [0.25, 0.75],
+            [0.75, 0.75],
+            [0.75, 0.25],
+        ])],
+    );
+    let tessellation = tessellate(&mut params_u, &params_v, &trims);
+    let uvs = vertex_uvs(&params_u, &params_v, &tessellation);
+    assert_eq!(params_u.len(), 5);
+    assert!(tessellation.extra_uvs.is_empty());
+    assert_eq!(tessellation.indices.len(), 12 * 6);
+    assert!((boundary_length(&uvs, &tessellation.indices) - 6.0).abs() < 1e-5);
+}
+
+#[wasm_bindgen_test]
+fn test_hole_inside_one_cell() {
+    let mut params_u = uniform_params(2);
+    let params_v = uniform_params(2);
+    let trims = SurfaceTrims::new(
+        None,
+        vec![polygon_loop(&[
+            [0.1, 0.1],
+            [0.2, 0.1],
+            [0.2, 0.2],
+            [0.1, 0.2],
+        ])],
+    );
+    let tessellation = tessellate(&mut params_u, &params_v, &trims);
+    let uvs = vertex_uvs(&params_u, &params_v, &tessellation);
+    assert_eq!(params_u.len(), 4);
+    let area: f32 = tessellation
+        .indices
+        .chunks(3)
+        .map(|triangle| triangle_area(&uvs, triangle))
+        .sum();
+    assert!((area - 0.99).abs() < 1e-5);
+    assert!((boundary_length(&uvs, &tessellation.indices) - 4.4).abs() < 1e-4);
+}
+
+#[wasm_bindgen_test]
+fn test_loop_through_grid_corners() {
+    let mut params_u = uniform_params(4);
+    let params_v = uniform_params(4);
+    let trims = SurfaceTrims::new(
+        Some(polygon_loop(&[
+            [0.5, 0.0],
+            [1.0, 0.5],
+            [0.5, 1.0],
+            [0.0, 0.5],
+        ])),
+        Vec::new(),
+    );
+    let tessellation = tessellate(&mut params_u, &params_v, &trims);
+    let uvs = vertex_uvs(&params_u, &params_v, &tessellation);
+    let area: f32 = tessellation
+        .indices
+        .chunks(3)
+        .map(|triangle| triangle_area(&uvs, triangle))
+        .sum();
+    assert!((area - 0.5).abs() < 1e-5);
+    let perimeter = 4.0 * 0.5_f32.sqrt();
+    assert!((boundary_length(&uvs, &tessellation.indices) - perimeter).abs() < 1e-4);
+}
+
+#[wasm_bindgen_test]
+fn test_kept_intervals() {
+    let hole = polygon_loop(&[[0.3, 0.3], [0.7, 0.3], [0.7, 0.7], [0.3, 0.7]]);
+    let trims = SurfaceTrims::new(None, vec![hole.clone()]);
+    let loops = sample_trims(&trims, 1.0);
+    let assert_intervals = |intervals: Vec<(f32, f32)>, expected: &[(f32, f32)]| {
+        assert_eq!(intervals.len(), expected.len(), "{:?}", intervals);
+        for ((start, end), (expected_start, expected_end)) in intervals.iter().zip(expected) {
+            assert!((start - expected_start).abs() < 1e-5);
+            assert!((end - expected_end).abs() < 1e-5);
+        }
+    };
+
+    // Through the hole, then past it
+    assert_intervals(
+        trims.kept_intervals(&loops, SurfaceDirection::U, 0.5, (0.0, 1.0)),
+        &[(0.0, 0.3), (0.7, 1.0)],
+    );
+    assert_intervals(
+        trims.kept_intervals(&loops, SurfaceDirection::V, 0.4, (0.0, 1.0)),
+        &[(0.0, 0.3), (0.7, 1.0)],
+    );
+    assert_intervals(
+        trims.kept_intervals(&loops, SurfaceDirection::U, 0.9, (0.0, 1.0)),
+        &[(0.0, 1.0)],
+    );
+
+    // The same square as the outer loop keeps only its inside
+    let trims = SurfaceTrims::new(Some(hole), Vec::new());
+    let loops = sample_trims(&trims, 1.0);
+    assert_intervals(
+        trims.kept_intervals(&loops, SurfaceDirection::U, 0.5, (0.0, 1.0)),
+        &[(0.3, 0.7)],
+    );
+    assert!(trims
+        .kept_intervals(&loops, SurfaceDirection::V, 0.9, (0.0, 1.0))
+        .is_empty());
+}
@@ -0,0 +1,99 @@
+use crate::math::{
+    geometry::{bounding_box::BoundingBox, frustum::Frustum, ray::Ray},
+    linear_algebra::{mat4::Mat4, vec3::Vec3},
+};
+
+use wasm_bindgen_test::*;
+
+/// Looks down -z from the origin with a 90 degree field of view
+fn create_frustum() -> Frustum {
+    let origin = Vec3::new(0.0, 0.0, 0.0);
+    Frustum::new(
+        &Ray::new(origin, Vec3::new(-1.0, 1.0, -1.0)),
+        &Ray::new(origin, Vec3::new(1.0, 1.0, -1.0)),
+        &Ray::new(origin, Vec3::new(1.0, -1.0, -1.0)),
+        &Ray::new(origin, Vec3::new(-1.0, -1.0, -1.0)),
+    )
+}
+
+#[wasm_bindgen_test]
+pub fn test_contains_point() {
+    let frustum = create_frustum();
+    assert!(frustum.contains_point(&Vec3::new(0.0, 0.0, -1.0)));
+    assert!(frustum.contains_point(&Vec3::new(0.9, -0.9, -1.0)));
+    assert!(!frustum.contains_point(&Vec3::new(1.1, 0.0, -1.0)));
+    assert!(!frustum.contains_point(&Vec3::new(0.0, -1.1, -1.0)));
+    // Behind the origin
+    assert!(!frustum.contains_point(&Vec3::new(0.0, 0.0, 1.0)));
+}
+
+#[wasm_bindgen_test]
+pub fn test_winding_does_not_matter() {
+    let origin = Vec3::new(0.0, 0.0, 0.0);
+    let frustum = Frustum::new(
+        &Ray::new(origin, Vec3::new(-1.0, -1.0, -1.0)),
+        &Ray::new(origin, Vec3::new(1.0, -1.0, -1.0)),
+        &Ray::new(origin, Vec3::new(1.0, 1.0, -1.0)),
+        &Ray::new(origin, Vec3::new(-1.0, 1.0, -1.0)),
+    );
+    assert!(frustum.contains_point(&Vec3::new(0.0, 0.0, -1.0)));
+    assert!(!frustum.contains_point(&Vec3::new(0.0, 0.0, 1.0)));
+}
+
+#[wasm_bindgen_test]
+pub fn test_contains_line() {
+    let frustum = create_frustum();
+
+    let inside_start = Vec3::new(-0.5, 0.0, -1.0);
+    let inside_end = Vec3::new(0.5, 0.0, -1.0);
+    assert!(frustum.contains_line_fully(&inside_start, &inside_end));
+    assert!(frustum.contains_line_partially(&inside_start, &inside_end));
+
+    // Crosses the whole frustum, both ends outside
+    let crossing_start = Vec3::new(-5.0, 0.0, -1.0);
+    let crossing_end = Vec3::new(5.0, 0.0, -1.0);
+    assert!(!frustum.contains_line_fully(&crossing_start, &crossing_end));
+    assert!(frustum.contains_line_partially(&crossing_start, &crossing_end));
+
+    // Passes by the corner without entering
+    let outside_start = Vec3::new(0.5, 2.0, -1.0);
+    let outside_end = Vec3::new(2.0, 0.5, -1.0);
+    assert!(!frustum.contains_line_partially(&outside_start, &outside_end));
+
+    // Behind the origin
+    let behind_start = Vec3::new(-5.0, 0.0, 1.0);
+    let behind_end = Vec3::new(5.0, 0.0, 1.0);
+    assert!(!frustum.contains_line_partially(&behind_start, &behind_end));
+}
+
+#[wasm_bindgen_test]
+pub fn test_contains_bounding_box() {
+    let frustum = create_frustum();
+
+    let inside = BoundingBox::new(&Vec3::new(-0.5, -0.5, -2.0), &Vec3::new(0.5, 0.5, -1.0));
+    assert!(frustum.contains_bounding_box_fully(&inside));
+    assert!(frustum.contains_bounding_box_partially(&inside));
+
+    let overlapping = BoundingBox::new(&Vec3::new(0.5, 0.5, -2.0), &Vec3::new(3.0, 3.0, -1.0));
+    assert!(!frustum.contains_bounding_box_fully(&overlapping));
+    assert!(frustum.contains_bounding_box_partially(&overlapping));
+
+    let outside = BoundingBox::new(&Vec3::new(3.0, 3.0, -2.0), &Vec3::new(4.0, 4.0, -1.0));
+    assert!(!frustum.contains_bounding_box_fully(&outside));
+    assert!(!frustum.contains_bounding_box_partially(&outside));
+
+    // Box swallows the frustum near the origin, no corner is inside
+    let around = BoundingBox::new(&Vec3::new(-1.0, -1.0, -1.0), &Vec3::new(1.0, 1.0, 1.0));
+    assert!(frustum.contains_bounding_box_partially(&around));
+
+    let behind = BoundingBox::new(&Vec3::new(-1.0, -1.0, 1.0), &Vec3::new(1.0, 1.0, 2.0));
+    assert!(!frustum.contains_bounding_box_partially(&behind));
+}
+
+#[wasm_bindgen_test]
+pub fn test_transform() {
+    let mut frustum = create_frustum();
+    frustum.transform(Mat4::translation(&Vec3::new(0.0, 0.0, 10.0)));
+    assert!(frustum.contains_point(&Vec3::new(0.0, 0.0, 9.0)));
+    assert!(!frustum.contains_point(&Vec3::new(0.0, 0.0, 11.0)));
+}
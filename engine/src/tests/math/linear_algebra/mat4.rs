@@ -53,3 +53,25 @@ fn test_rotate_point_axis() {
 fn translate() {
     assert!(false);
 }
+
+#[wasm_bindgen_test]
+fn test_inverse() {
+    let m_t = Mat4::multiply(
+        &Mat4::translation(&crate::math::linear_algebra::vec3::Vec3::new(
+            1.0, -2.0, 3.0,
+        )),
+        &Mat4::rotate_axis(
+            crate::math::linear_algebra::vec3::Vec3::new(1.0, 1.0, 0.0),
+            0.7,
+        ),
+    );
+    let product = Mat4::multiply(&m_t, &m_t.inverse().unwrap());
+    let identity = Mat4::identity();
+    for (a, b) in bytemuck::cast_slice::<Mat4, f32>(&[product])
+        .iter()
+        .zip(bytemuck::cast_slice::<Mat4, f32>(&[identity]).iter())
+    {
+        assert!((a - b).abs() < 0.0001);
+    }
+    assert!(Mat4::new(&[0.0; 16]).inverse().is_none());
+}
@@ -1,2 +1,3 @@
+pub mod geometry;
 pub mod linear_algebra;
-
+pub mod nurbs;
@@ -0,0 +1,53 @@
+use crate::math::nurbs::basis::*;
+
+use wasm_bindgen_test::*;
+
+// Example 2.3 from The NURBS Book
+const KNOTS: [f32; 11] = [0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 4.0, 4.0, 5.0, 5.0, 5.0];
+
+#[wasm_bindgen_test]
+pub fn test_find_span() {
+    assert_eq!(find_span(2, &KNOTS, 0.0), 2);
+    assert_eq!(find_span(2, &KNOTS, 0.5), 2);
+    assert_eq!(find_span(2, &KNOTS, 2.5), 4);
+    assert_eq!(find_span(2, &KNOTS, 4.0), 7);
+    // End of the domain belongs to the last span
+    assert_eq!(find_span(2, &KNOTS, 5.0), 7);
+    assert_eq!(find_span(2, &KNOTS, 6.0), 7);
+}
+
+#[wasm_bindgen_test]
+pub fn test_basis_functions() {
+    let basis = basis_functions(4, 2.5, 2, &KNOTS);
+    assert!((basis[0] - 1.0 / 8.0).abs() < 1e-6);
+    assert!((basis[1] - 6.0 / 8.0).abs() < 1e-6);
+    assert!((basis[2] - 1.0 / 8.0).abs() < 1e-6);
+}
+
+#[wasm_bindgen_test]
+pub fn test_partition_of_unity() {
+    for i in 0..=50 {
+        let t = i as f32 / 10.0;
+        let span = find_span(2, &KNOTS, t);
+        let sum: f32 = basis_functions(span, t, 2, &KNOTS).iter().sum();
+        assert!((sum - 1.0).abs() < 1e-5, "sum at {} is {}", t, sum);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_basis_function_derivatives() {
+    let ders = basis_function_derivatives(4, 2.5, 2, &KNOTS, 3);
+    let basis = basis_functions(4, 2.5, 2, &KNOTS);
+    for i in 0..3 {
+        assert!((ders[0][i] - basis[i]).abs() < 1e-6);
+    }
+    // Example 2.4
+    assert!((ders[1][0] + 0.5).abs() < 1e-6);
+    assert!(ders[1][1].abs() < 1e-6);
+    assert!((ders[1][2] - 0.5).abs() < 1e-6);
+    assert!((ders[2][0] - 1.0).abs() < 1e-6);
+    assert!((ders[2][1] + 2.0).abs() < 1e-6);
+    assert!((ders[2][2] - 1.0).abs() < 1e-6);
+    // Above the degree
+    assert!(ders[3].iter().all(|d| *d == 0.0));
+}
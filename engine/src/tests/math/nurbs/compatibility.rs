@@ -0,0 +1,102 @@
+use crate::{
+    geometry::curve_generators::arc::create_arc_nurbs,
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{
+            compatibility::{join_curves, make_compatible},
+            curve::NurbsCurve,
+        },
+    },
+};
+
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+pub fn test_make_compatible() {
+    let arc = create_arc_nurbs(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        2.0,
+        0.0,
+        3.0,
+    );
+    let cubic = NurbsCurve::new(
+        3,
+        vec![
+            Vec4::new_point(0.0, 0.0, 1.0),
+            Vec4::new_point(1.0, 1.0, 1.0),
+            Vec4::new_point(2.0, 0.0, 1.0),
+            Vec4::new_point(3.0, 1.0, 1.0),
+            Vec4::new_point(4.0, 0.0, 1.0),
+        ],
+        vec![0.0, 0.0, 0.0, 0.0, 2.5, 5.0, 5.0, 5.0, 5.0],
+    );
+    let originals = [arc, cubic];
+    let mut curves = originals.to_vec();
+    make_compatible(&mut curves);
+
+    assert_eq!(curves[0].degree, 3);
+    assert_eq!(curves[1].degree, 3);
+    assert_eq!(curves[0].knots, curves[1].knots);
+    assert_eq!(
+        curves[0].weighted_controls.len(),
+        curves[1].weighted_controls.len()
+    );
+
+    for (original, compatible) in originals.iter().zip(curves.iter()) {
+        let (start, end) = original.domain();
+        for i in 0..=20 {
+            let t = i as f32 / 20.0;
+            let distance = Vec3::subtract(
+                &original.point(start + (end - start) * t),
+                &compatible.point(t),
+            )
+            .len();
+            assert!(distance < 1e-4, "shape changed by {} at {}", distance, t);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_join_curves() {
+    // Arc ending at (-2, 0), weighted so the shared control needs rescaling
+    let arc = create_arc_nurbs(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        2.0,
+        0.0,
+        std::f32::consts::PI,
+    );
+    let mut line = NurbsCurve::new(
+        1,
+        vec![
+            Vec4::new_point(-2.0, 0.0, 0.0),
+            Vec4::new_point(-2.0, -3.0, 0.0),
+        ],
+        vec![0.0, 0.0, 1.0, 1.0],
+    );
+    line.weighted_controls = line
+        .weighted_controls
+        .iter()
+        .map(|control| Vec4::to_scaled(control, 2.0))
+        .collect();
+    let originals = [arc, line];
+    let joined = join_curves(&originals).unwrap();
+
+    assert_eq!(joined.degree, 2);
+    assert_eq!(joined.domain(), (0.0, 2.0));
+    for (i, original) in originals.iter().enumerate() {
+        let (start, end) = original.domain();
+        for k in 0..=20 {
+            let t = k as f32 / 20.0;
+            let distance = Vec3::subtract(
+                &original.point(start + (end - start) * t),
+                &joined.point(i as f32 + t),
+            )
+            .len();
+            assert!(distance < 1e-4, "shape changed by {} at {}", distance, t);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::math::{
+    linear_algebra::{vec3::Vec3, vec4::Vec4},
+    nurbs::curve::NurbsCurve,
+};
+
+use crate::tests::utils::{assert_close, quarter_circle};
+
+use wasm_bindgen_test::*;
+
+/// Non rational cubic with two spans
+fn cubic() -> NurbsCurve {
+    NurbsCurve::new(
+        3,
+        vec![
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+            Vec4::new(1.0, 2.0, 0.0, 1.0),
+            Vec4::new(3.0, 2.0, 1.0, 1.0),
+            Vec4::new(4.0, 0.0, 1.0, 1.0),
+            Vec4::new(5.0, 1.0, 0.0, 1.0),
+        ],
+        vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 2.0, 2.0, 2.0],
+    )
+}
+
+#[wasm_bindgen_test]
+pub fn test_end_points() {
+    let curve = cubic();
+    assert_close(&curve.point(0.0), &Vec3::new(0.0, 0.0, 0.0), 1e-6);
+    assert_close(&curve.point(2.0), &Vec3::new(5.0, 1.0, 0.0), 1e-6);
+    assert_eq!(curve.domain(), (0.0, 2.0));
+}
+
+#[wasm_bindgen_test]
+pub fn test_circle_radius() {
+    let curve = quarter_circle();
+    for i in 0..=20 {
+        let t = i as f32 / 20.0;
+        assert!((curve.point(t).len() - 1.0).abs() < 1e-5);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_circle_derivatives() {
+    let curve = quarter_circle();
+    for i in 0..=10 {
+        let t = i as f32 / 10.0;
+        let ders = curve.derivatives(t, 2);
+        // Tangent is perpendicular to the radius
+        assert!(Vec3::dot(&ders[0], &ders[1]).abs() < 1e-4);
+        // Curvature of a unit circle points to the center with length 1
+        assert_close(&curve.curvature(t), &Vec3::to_scaled(&ders[0], -1.0), 1e-3);
+    }
+    assert_close(&curve.tangent(0.0), &Vec3::new(0.0, 1.0, 0.0), 1e-6);
+}
+
+#[wasm_bindgen_test]
+pub fn test_derivatives_match_finite_differences() {
+    let h = 1e-2;
+    for curve in [cubic(), quarter_circle()] {
+        let (start, end) = curve.domain();
+        for i in 1..10 {
+            let t = start + (end - start) * i as f32 / 10.0;
+            let ders = curve.derivatives(t, 2);
+            assert_close(&ders[0], &curve.point(t), 1e-6);
+
+            let before = curve.point(t - h);
+            let after = curve.point(t + h);
+            let first = Vec3::to_scaled(&Vec3::subtract(&after, &before), 0.5 / h);
+            assert_close(&ders[1], &first, 1e-2);
+
+            let second = Vec3::to_scaled(
+                &Vec3::add(
+                    &Vec3::subtract(&after, &Vec3::to_scaled(&ders[0], 2.0)),
+                    &before,
+                ),
+                1.0 / (h * h),
+            );
+            // Second derivative jumps at the knot of the cubic
+            if (t - 1.0).abs() > h {
+                assert_close(&ders[2], &second, 5e-2);
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{curve::NurbsCurve, knot_insertion::knot_multiplicity, surface::NurbsSurface},
+    },
+    tests::utils::{assert_same_shape, rational_cubic},
+};
+
+use wasm_bindgen_test::*;
+
+/// Rational cubic with a double knot
+fn rational_cubic_double_knot() -> NurbsCurve {
+    let mut curve = rational_cubic();
+    curve.insert_knot(1.0, 1);
+    curve
+}
+
+fn line() -> NurbsCurve {
+    NurbsCurve::new(
+        1,
+        vec![
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+            Vec4::new(1.0, 1.0, 0.0, 1.0),
+            Vec4::new(3.0, 0.0, 0.0, 1.0),
+        ],
+        vec![0.0, 0.0, 0.5, 1.0, 1.0],
+    )
+}
+
+#[wasm_bindgen_test]
+pub fn test_elevate_curve() {
+    for (curve, times) in [
+        (rational_cubic_double_knot(), 1),
+        (rational_cubic_double_knot(), 2),
+        (line(), 2),
+    ] {
+        let mut elevated = curve.clone();
+        elevated.elevate_degree(times);
+        assert_eq!(elevated.degree, curve.degree + times);
+        assert_eq!(
+            elevated.knots.len(),
+            elevated.weighted_controls.len() + elevated.degree as usize + 1
+        );
+        let (start, end) = curve.domain();
+        assert_eq!(
+            knot_multiplicity(&elevated.knots, start),
+            elevated.degree as usize + 1
+        );
+        assert_eq!(
+            knot_multiplicity(&elevated.knots, end),
+            elevated.degree as usize + 1
+        );
+        assert_same_shape(&curve, &elevated, 1e-4);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_elevate_keeps_continuity() {
+    let mut curve = rational_cubic_double_knot();
+    curve.elevate_degree(1);
+    assert_eq!(knot_multiplicity(&curve.knots, 1.0), 3);
+
+    let mut curve = line();
+    curve.elevate_degree(2);
+    assert_eq!(knot_multiplicity(&curve.knots, 0.5), 3);
+}
+
+#[wasm_bindgen_test]
+pub fn test_elevate_surface() {
+    let w = std::f32::consts::FRAC_1_SQRT_2;
+    let mut controls = Vec::new();
+    for z in [0.0, 1.0] {
+        controls.push(Vec4::new(1.0, 0.0, z, 1.0));
+        controls.push(Vec4::new(w, w, z * w, w));
+        controls.push(Vec4::new(0.0, 1.0, z, 1.0));
+    }
+    let surface = NurbsSurface::new(
+        2,
+        1,
+        3,
+        2,
+        controls,
+        vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        vec![0.0, 0.0, 1.0, 1.0],
+    );
+    let mut elevated = surface.clone();
+    elevated.elevate_degree_u(1);
+    elevated.elevate_degree_v(2);
+    assert_eq!((elevated.degree_u, elevated.degree_v), (3, 3));
+    assert_eq!((elevated.control_count_u, elevated.control_count_v), (4, 4));
+    for i in 0..=10 {
+        for j in 0..=10 {
+            let (u, v) = (i as f32 / 10.0, j as f32 / 10.0);
+            let distance = Vec3::subtract(&surface.point(u, v), &elevated.point(u, v)).len();
+            assert!(distance < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::math::{
+    linear_algebra::vec3::Vec3,
+    nurbs::fitting::{fit_curve, fit_surface},
+};
+
+use wasm_bindgen_test::*;
+
+fn sine_points(count: usize) -> Vec<Vec3> {
+    (0..count)
+        .map(|i| {
+            let x = 6.0 * i as f32 / (count - 1) as f32;
+            Vec3::new(x, x.sin(), 0.2 * x)
+        })
+        .collect()
+}
+
+#[wasm_bindgen_test]
+pub fn test_fit_curve() {
+    let points = sine_points(100);
+    let (curve, deviation) = fit_curve(&points, 3, 10).unwrap();
+    assert_eq!(curve.degree, 3);
+    assert_eq!(curve.weighted_controls.len(), 10);
+    assert!(Vec3::subtract(&curve.point(0.0), &points[0]).len() < 1e-5);
+    assert!(Vec3::subtract(&curve.point(1.0), &points[99]).len() < 1e-5);
+    assert!(deviation.max < 2e-2, "max deviation {}", deviation.max);
+    assert!(deviation.average <= deviation.max);
+
+    // More controls fit more closely
+    let (_, coarse) = fit_curve(&points, 3, 5).unwrap();
+    assert!(coarse.max > deviation.max);
+
+    // As many controls as points interpolates
+    let few = sine_points(6);
+    let (_, exact) = fit_curve(&few, 3, 6).unwrap();
+    assert!(exact.max < 1e-4);
+
+    assert!(fit_curve(&few, 3, 7).is_none());
+    assert!(fit_curve(&few, 3, 3).is_none());
+}
+
+#[wasm_bindgen_test]
+pub fn test_fit_surface() {
+    let (count_u, count_v) = (30, 20);
+    let mut points = Vec::with_capacity(count_u * count_v);
+    for j in 0..count_v {
+        for i in 0..count_u {
+            let x = 3.0 * i as f32 / (count_u - 1) as f32;
+            let y = 2.0 * j as f32 / (count_v - 1) as f32;
+            points.push(Vec3::new(x, y, x.sin() * y.cos()));
+        }
+    }
+    let (surface, deviation) = fit_surface(&points, count_u, count_v, 3, 3, 8, 6).unwrap();
+    assert_eq!(surface.control_count_u, 8);
+    assert_eq!(surface.control_count_v, 6);
+    assert!(deviation.max < 1e-2, "max deviation {}", deviation.max);
+    assert!(deviation.average <= deviation.max);
+    for (u, v, index) in [
+        (0.0, 0.0, 0),
+        (1.0, 0.0, count_u - 1),
+        (0.0, 1.0, count_u * (count_v - 1)),
+        (1.0, 1.0, count_u * count_v - 1),
+    ] {
+        assert!(Vec3::subtract(&surface.point(u, v), &points[index]).len() < 1e-5);
+    }
+
+    assert!(fit_surface(&points, count_u, count_v, 3, 3, 8, 21).is_none());
+    assert!(fit_surface(&points[1..], count_u, count_v, 3, 3, 8, 6).is_none());
+}
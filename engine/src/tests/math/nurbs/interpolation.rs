@@ -0,0 +1,121 @@
+use crate::math::{
+    linear_algebra::{vec3::Vec3, vec4::Vec4},
+    nurbs::interpolation::{
+        create_params, interpolate, interpolate_closed, interpolate_with_derivatives,
+        point_distances, Parameterization,
+    },
+};
+
+use wasm_bindgen_test::*;
+
+fn points() -> Vec<Vec3> {
+    vec![
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(1.0, 2.0, 0.0),
+        Vec3::new(3.0, 3.0, 1.0),
+        Vec3::new(4.0, 0.0, 1.0),
+        Vec3::new(2.0, -1.0, 0.5),
+    ]
+}
+
+#[wasm_bindgen_test]
+pub fn test_interpolate_passes_through_points() {
+    let points = points();
+    let homogeneous: Vec<Vec4> = points.iter().map(|p| p.append(1.0)).collect();
+    for parameterization in [
+        Parameterization::Uniform,
+        Parameterization::ChordLength,
+        Parameterization::Centripetal,
+    ] {
+        let params = create_params(&point_distances(&points, false), parameterization);
+        assert_eq!(params.len(), points.len());
+        let curve = interpolate(&homogeneous, &params, 3).unwrap();
+        assert_eq!(curve.degree, 3);
+        assert_eq!(curve.domain(), (0.0, 1.0));
+        for (point, t) in points.iter().zip(params.iter()) {
+            assert!(Vec3::subtract(&curve.point(*t), point).len() < 1e-4);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_interpolate_lowers_degree() {
+    let points = [
+        Vec4::new_point(0.0, 0.0, 0.0),
+        Vec4::new_point(2.0, 0.0, 0.0),
+    ];
+    let curve = interpolate(&points, &[0.0, 1.0], 3).unwrap();
+    assert_eq!(curve.degree, 1);
+    assert!(Vec3::subtract(&curve.point(0.5), &Vec3::new(1.0, 0.0, 0.0)).len() < 1e-6);
+}
+
+#[wasm_bindgen_test]
+pub fn test_interpolate_closed() {
+    let points = points();
+    let homogeneous: Vec<Vec4> = points.iter().map(|p| p.append(1.0)).collect();
+    for degree in [2, 3] {
+        let params = create_params(
+            &point_distances(&points, true),
+            Parameterization::ChordLength,
+        );
+        assert_eq!(params.len(), points.len() + 1);
+        let curve = interpolate_closed(&homogeneous, &params, degree).unwrap();
+        assert_eq!(curve.degree, degree);
+        assert_eq!(curve.domain(), (0.0, 1.0));
+
+        // Every point is hit somewhere along the curve
+        for point in points.iter() {
+            let closest = (0..=2000)
+                .map(|i| Vec3::subtract(&curve.point(i as f32 / 2000.0), point).len())
+                .fold(f32::MAX, f32::min);
+            assert!(closest < 1e-2, "missed {} by {}", point, closest);
+        }
+
+        // Closed and smooth where it meets itself
+        assert!(Vec3::subtract(&curve.point(0.0), &curve.point(1.0)).len() < 1e-4);
+        let start = curve.derivatives(0.0, 2);
+        let end = curve.derivatives(1.0, 2);
+        assert!(Vec3::subtract(&start[1], &end[1]).len() < 1e-2 * start[1].len());
+        if degree == 3 {
+            assert!(Vec3::subtract(&start[2], &end[2]).len() < 1e-2 * start[2].len());
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_interpolate_with_derivatives() {
+    let points = points();
+    let homogeneous: Vec<Vec4> = points.iter().map(|p| p.append(1.0)).collect();
+    let params = create_params(
+        &point_distances(&points, false),
+        Parameterization::ChordLength,
+    );
+    let start = Vec4::new_vec(0.0, 0.0, 8.0);
+    let end = Vec4::new_vec(-5.0, 0.0, 0.0);
+    for (start_derivative, end_derivative) in [
+        (Some(start), Some(end)),
+        (Some(start), None),
+        (None, Some(end)),
+        (None, None),
+    ] {
+        let curve = interpolate_with_derivatives(
+            &homogeneous,
+            &params,
+            3,
+            start_derivative,
+            end_derivative,
+        )
+        .unwrap();
+        let extra = start_derivative.is_some() as usize + end_derivative.is_some() as usize;
+        assert_eq!(curve.weighted_controls.len(), points.len() + extra);
+        for (point, t) in points.iter().zip(params.iter()) {
+            assert!(Vec3::subtract(&curve.point(*t), point).len() < 1e-4);
+        }
+        for (derivative, t) in [(start_derivative, 0.0), (end_derivative, 1.0)] {
+            if let Some(d) = derivative {
+                let actual = curve.derivatives(t, 1)[1];
+                assert!(Vec3::subtract(&actual, &Vec3::new(d.x, d.y, d.z)).len() < 1e-3);
+            }
+        }
+    }
+}
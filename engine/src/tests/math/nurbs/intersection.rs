@@ -0,0 +1,148 @@
+use crate::{
+    geometry::curve_generators::circle::create_circle_nurbs,
+    math::{
+        geometry::plane::Plane,
+        linear_algebra::vec3::Vec3,
+        nurbs::{
+            curve::NurbsCurve,
+            intersection::{intersect_curve_plane, intersect_curves},
+        },
+    },
+};
+
+use crate::tests::utils::assert_close;
+
+use wasm_bindgen_test::*;
+
+const TOLERANCE: f32 = 1e-4;
+
+/// Degree 1 curve through the points with uniform knots
+fn polyline(points: &[Vec3]) -> NurbsCurve {
+    let count = points.len();
+    let mut knots = vec![0.0];
+    knots.extend((0..count).map(|i| i as f32 / (count - 1) as f32));
+    knots.push(1.0);
+    NurbsCurve::new(
+        1,
+        points.iter().map(|point| point.append(1.0)).collect(),
+        knots,
+    )
+}
+
+#[wasm_bindgen_test]
+pub fn test_curve_curve_crossings() {
+    let z = Vec3::new(0.0, 0.0, 1.0);
+    let circle = create_circle_nurbs(Vec3::new(0.0, 0.0, 0.0), z, 2.0);
+
+    let line = polyline(&[Vec3::new(-3.0, 1.0, 0.0), Vec3::new(3.0, 1.0, 0.0)]);
+    let (points, overlaps) = intersect_curves(&line, &circle, TOLERANCE);
+    assert!(overlaps.is_empty());
+    assert_eq!(points.len(), 2);
+    // Sorted along the first curve
+    assert_close(
+        &points[0].point,
+        &Vec3::new(-f32::sqrt(3.0), 1.0, 0.0),
+        1e-4,
+    );
+    assert_close(&points[1].point, &Vec3::new(f32::sqrt(3.0), 1.0, 0.0), 1e-4);
+    for hit in points.iter() {
+        assert_close(&line.point(hit.t_a), &hit.point, 1e-4);
+        assert_close(&circle.point(hit.t_b), &hit.point, 1e-4);
+    }
+
+    let other = create_circle_nurbs(Vec3::new(2.0, 0.0, 0.0), z, 2.0);
+    let (points, _) = intersect_curves(&circle, &other, TOLERANCE);
+    assert_eq!(points.len(), 2);
+    for hit in points.iter() {
+        assert!((hit.point.x - 1.0).abs() < 1e-4);
+        assert!((hit.point.y.abs() - f32::sqrt(3.0)).abs() < 1e-4);
+    }
+
+    // Touching counts once
+    let tangent = polyline(&[Vec3::new(-3.0, 2.0, 0.0), Vec3::new(3.0, 2.0, 0.0)]);
+    let (points, _) = intersect_curves(&tangent, &circle, TOLERANCE);
+    assert_eq!(points.len(), 1);
+    assert_close(&points[0].point, &Vec3::new(0.0, 2.0, 0.0), 1e-2);
+
+    // Passing above the circle
+    let missing = polyline(&[Vec3::new(-3.0, 2.5, 0.0), Vec3::new(3.0, 2.5, 0.0)]);
+    let (points, overlaps) = intersect_curves(&missing, &circle, TOLERANCE);
+    assert!(points.is_empty() && overlaps.is_empty());
+
+    // Skew lines in 3d only meet if they come within tolerance
+    let skew = polyline(&[Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 0.0, 1.0)]);
+    let (points, _) = intersect_curves(&line, &skew, TOLERANCE);
+    assert!(points.is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_curve_curve_overlaps() {
+    let corner = polyline(&[
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(2.0, 2.0, 0.0),
+    ]);
+    let line = polyline(&[Vec3::new(3.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0)]);
+    let (points, overlaps) = intersect_curves(&corner, &line, TOLERANCE);
+    assert!(points.is_empty());
+    assert_eq!(overlaps.len(), 1);
+    let overlap = overlaps[0];
+    assert!(overlap.start.t_a.abs() < 1e-5);
+    assert!((overlap.end.t_a - 0.5).abs() < 1e-5);
+    // The line runs the other way
+    assert!((overlap.start.t_b - 0.75).abs() < 1e-4);
+    assert!((overlap.end.t_b - 0.25).abs() < 1e-4);
+
+    // The same circle twice coincides all the way round
+    let circle = create_circle_nurbs(Vec3::new(1.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0), 1.0);
+    let (points, overlaps) = intersect_curves(&circle, &circle.clone(), TOLERANCE);
+    assert!(points.is_empty());
+    assert_eq!(overlaps.len(), 1);
+    assert_eq!(overlaps[0].start.t_a, 0.0);
+    assert_eq!(overlaps[0].end.t_a, 1.0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_curve_plane() {
+    let circle = create_circle_nurbs(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0), 2.0);
+
+    let plane = Plane::new(Vec3::new(1.0, 5.0, -2.0), Vec3::new(3.0, 0.0, 0.0));
+    let (points, overlaps) = intersect_curve_plane(&circle, &plane, TOLERANCE);
+    assert!(overlaps.is_empty());
+    assert_eq!(points.len(), 2);
+    for hit in points.iter() {
+        assert!((hit.point.x - 1.0).abs() < 1e-4);
+        assert!((hit.point.y.abs() - f32::sqrt(3.0)).abs() < 1e-4);
+        assert_close(&circle.point(hit.t), &hit.point, 1e-5);
+    }
+
+    let tangent = Plane::new(Vec3::new(0.0, 2.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    let (points, _) = intersect_curve_plane(&circle, &tangent, TOLERANCE);
+    assert_eq!(points.len(), 1);
+    assert_close(&points[0].point, &Vec3::new(0.0, 2.0, 0.0), 1e-2);
+
+    let containing = Plane::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+    let (points, overlaps) = intersect_curve_plane(&circle, &containing, TOLERANCE);
+    assert!(points.is_empty());
+    assert_eq!(overlaps.len(), 1);
+    assert_eq!((overlaps[0].start.t, overlaps[0].end.t), (0.0, 1.0));
+
+    // Dips into the plane for the middle segment only, and crosses it once more on the way out
+    let dip = polyline(&[
+        Vec3::new(0.0, 0.0, 1.0),
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(2.0, 0.0, -1.0),
+    ]);
+    let (points, overlaps) = intersect_curve_plane(&dip, &containing, TOLERANCE);
+    assert!(points.is_empty());
+    assert_eq!(overlaps.len(), 1);
+    assert!((overlaps[0].start.t - 1.0 / 3.0).abs() < 1e-5);
+    assert!((overlaps[0].end.t - 2.0 / 3.0).abs() < 1e-5);
+
+    let across = Plane::new(Vec3::new(0.0, 0.0, 0.5), Vec3::new(0.0, 0.0, -1.0));
+    let (points, overlaps) = intersect_curve_plane(&dip, &across, TOLERANCE);
+    assert!(overlaps.is_empty());
+    assert_eq!(points.len(), 1);
+    assert_close(&points[0].point, &Vec3::new(0.0, 0.0, 0.5), 1e-5);
+}
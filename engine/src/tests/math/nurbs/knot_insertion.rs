@@ -0,0 +1,162 @@
+use crate::{
+    geometry::{surface_generators::sphere::create_sphere_nurbs, utils::placement},
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{curve::NurbsCurve, knot_insertion::knot_multiplicity, surface::SurfaceDirection},
+    },
+    tests::utils::{assert_same_shape, rational_cubic},
+};
+
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+pub fn test_insert_knot() {
+    let curve = rational_cubic();
+    for (t, times) in [(0.5, 1), (1.0, 1), (1.0, 2), (1.5, 3)] {
+        let mut refined = curve.clone();
+        assert_eq!(refined.insert_knot(t, times), times);
+        assert_eq!(refined.weighted_controls.len(), 5 + times);
+        assert_eq!(
+            knot_multiplicity(&refined.knots, t),
+            knot_multiplicity(&curve.knots, t) + times
+        );
+        assert_same_shape(&curve, &refined, 1e-5);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_knot_caps_multiplicity() {
+    let mut curve = rational_cubic();
+    assert_eq!(curve.insert_knot(1.0, 5), 2);
+    assert_eq!(curve.insert_knot(1.0, 1), 0);
+    assert_eq!(curve.insert_knot(2.0, 1), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_refine_knots() {
+    let curve = rational_cubic();
+    let new_knots = [0.25, 0.5, 0.5, 1.0, 1.75];
+    let mut refined = curve.clone();
+    refined.refine_knots(&new_knots);
+    assert_eq!(
+        refined.knots,
+        vec![0.0, 0.0, 0.0, 0.0, 0.25, 0.5, 0.5, 1.0, 1.0, 1.75, 2.0, 2.0, 2.0, 2.0]
+    );
+    assert_eq!(refined.weighted_controls.len(), 10);
+    assert_same_shape(&curve, &refined, 1e-5);
+}
+
+#[wasm_bindgen_test]
+pub fn test_split_at() {
+    let curve = rational_cubic();
+    for t in [0.3, 1.0, 1.7] {
+        let (left, right) = curve.split_at(t).unwrap();
+        assert_eq!(left.domain(), (0.0, t));
+        assert_eq!(right.domain(), (t, 2.0));
+        for i in 0..=20 {
+            let s = t * i as f32 / 20.0;
+            assert!(Vec3::subtract(&left.point(s), &curve.point(s)).len() < 1e-5);
+            let s = t + (2.0 - t) * i as f32 / 20.0;
+            assert!(Vec3::subtract(&right.point(s), &curve.point(s)).len() < 1e-5);
+        }
+    }
+    assert!(curve.split_at(0.0).is_none());
+    assert!(curve.split_at(2.0).is_none());
+}
+
+#[wasm_bindgen_test]
+pub fn test_clamp() {
+    // Uniform unclamped cubic, domain is 0 to 2
+    let unclamped = NurbsCurve::new(
+        3,
+        vec![
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+            Vec4::new(2.0, 4.0, 0.0, 2.0),
+            Vec4::new(3.0, 2.0, 1.0, 1.0),
+            Vec4::new(2.0, 0.0, 0.5, 0.5),
+            Vec4::new(5.0, 1.0, 0.0, 1.0),
+        ],
+        vec![-3.0, -2.0, -1.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+    );
+    let mut clamped = unclamped.clone();
+    clamped.clamp();
+    assert_eq!(
+        clamped.knots,
+        vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 2.0, 2.0, 2.0]
+    );
+    assert_same_shape(&unclamped, &clamped, 1e-5);
+
+    // Already clamped curves are left alone
+    let curve = rational_cubic();
+    let mut clamped = curve.clone();
+    clamped.clamp();
+    assert_eq!(curve, clamped);
+}
+
+#[wasm_bindgen_test]
+pub fn test_sub_curve() {
+    let curve = rational_cubic();
+    let part = curve.sub_curve(0.5, 1.5).unwrap();
+    assert_eq!(part.domain(), (0.5, 1.5));
+    for i in 0..=20 {
+        let t = 0.5 + i as f32 / 20.0;
+        assert!(Vec3::subtract(&part.point(t), &curve.point(t)).len() < 1e-5);
+    }
+
+    // Clamped to the domain, nothing left outside it
+    assert_eq!(curve.sub_curve(-1.0, 3.0).unwrap(), curve);
+    assert_eq!(curve.sub_curve(1.0, 2.0).unwrap().domain(), (1.0, 2.0));
+    assert!(curve.sub_curve(2.0, 3.0).is_none());
+    assert!(curve.sub_curve(1.0, 1.0).is_none());
+}
+
+#[wasm_bindgen_test]
+pub fn test_isocurve() {
+    let sphere = create_sphere_nurbs(
+        &placement(
+            &Vec3::new(1.0, 2.0, 3.0),
+            &Vec3::new(1.0, 0.0, 0.0),
+            &Vec3::new(0.0, 0.0, 1.0),
+        )
+        .unwrap(),
+        2.0,
+    );
+    let (start_u, end_u) = sphere.domain_u();
+    let (start_v, end_v) = sphere.domain_v();
+    let lerp = |start: f32, end: f32, i: usize| start + (end - start) * i as f32 / 10.0;
+
+    // Both ends of the domain, knots and params between them
+    for i in 0..=10 {
+        let v = lerp(start_v, end_v, i);
+        let isocurve = sphere.isocurve(SurfaceDirection::U, v);
+        assert_eq!(isocurve.degree, sphere.degree_u);
+        assert_eq!(isocurve.knots, sphere.knots_u);
+        for j in 0..=10 {
+            let u = lerp(start_u, end_u, j);
+            let distance = Vec3::subtract(&isocurve.point(u), &sphere.point(u, v)).len();
+            assert!(
+                distance < 1e-4,
+                "u isocurve off by {} at {}, {}",
+                distance,
+                u,
+                v
+            );
+        }
+
+        let u = lerp(start_u, end_u, i);
+        let isocurve = sphere.isocurve(SurfaceDirection::V, u);
+        assert_eq!(isocurve.degree, sphere.degree_v);
+        assert_eq!(isocurve.knots, sphere.knots_v);
+        for j in 0..=10 {
+            let v = lerp(start_v, end_v, j);
+            let distance = Vec3::subtract(&isocurve.point(v), &sphere.point(u, v)).len();
+            assert!(
+                distance < 1e-4,
+                "v isocurve off by {} at {}, {}",
+                distance,
+                u,
+                v
+            );
+        }
+    }
+}
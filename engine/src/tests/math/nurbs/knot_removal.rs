@@ -0,0 +1,126 @@
+use crate::{
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::{curve::NurbsCurve, surface::NurbsSurface},
+    },
+    tests::utils::rational_cubic,
+};
+
+use wasm_bindgen_test::*;
+
+fn max_distance(a: &NurbsCurve, b: &NurbsCurve) -> f32 {
+    (0..=50)
+        .map(|i| {
+            let t = 2.0 * i as f32 / 50.0;
+            Vec3::subtract(&a.point(t), &b.point(t)).len()
+        })
+        .fold(0.0, f32::max)
+}
+
+#[wasm_bindgen_test]
+pub fn test_remove_inserted_knot() {
+    let curve = rational_cubic();
+    let mut refined = curve.clone();
+    refined.insert_knot(0.5, 2);
+    refined.insert_knot(1.5, 1);
+    assert_eq!(refined.remove_knot(0.5, 2, 1e-4), 2);
+    assert_eq!(refined.remove_knot(1.5, 1, 1e-4), 1);
+    assert_eq!(refined.knots, curve.knots);
+    assert_eq!(
+        refined.weighted_controls.len(),
+        curve.weighted_controls.len()
+    );
+    assert!(max_distance(&curve, &refined) < 1e-4);
+}
+
+#[wasm_bindgen_test]
+pub fn test_keeps_needed_knots() {
+    let mut curve = rational_cubic();
+    assert_eq!(curve.remove_knot(1.0, 1, 1e-4), 0);
+    assert_eq!(curve.remove_knots(1e-4), 0);
+    assert_eq!(curve, rational_cubic());
+}
+
+#[wasm_bindgen_test]
+pub fn test_remove_knots() {
+    let curve = rational_cubic();
+    let mut refined = curve.clone();
+    refined.refine_knots(&[0.25, 0.5, 1.0, 1.0, 1.5]);
+    assert_eq!(refined.remove_knots(1e-4), 5);
+    assert_eq!(refined.knots, curve.knots);
+    assert!(max_distance(&curve, &refined) < 1e-4);
+}
+
+#[wasm_bindgen_test]
+pub fn test_remove_knots_error_does_not_add_up() {
+    let tolerance = 1e-2;
+    let knots: Vec<f32> = (1..20).map(|i| i as f32 / 20.0).collect();
+    let mut seed: u32 = 1;
+    // Lines with noisy controls, where the removals each stay within tolerance
+    // but for some of them would not together
+    for _ in 0..500 {
+        let mut curve = NurbsCurve::new(
+            3,
+            (0..4)
+                .map(|i| Vec4::new(i as f32 / 3.0, 0.0, 0.0, 1.0))
+                .collect(),
+            vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+        );
+        curve.refine_knots(&knots);
+        for control in curve.weighted_controls.iter_mut() {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            control.y += 0.2 * tolerance * ((seed >> 8) as f32 / (1 << 24) as f32 - 0.5);
+        }
+        let noisy = curve.clone();
+        curve.remove_knots(tolerance);
+        let deviation = (0..=100)
+            .map(|i| {
+                let t = i as f32 / 100.0;
+                Vec3::subtract(&noisy.point(t), &curve.point(t)).len()
+            })
+            .fold(0.0, f32::max);
+        assert!(deviation <= tolerance);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_remove_surface_knots() {
+    let mut controls = Vec::new();
+    for j in 0..3 {
+        for i in 0..4 {
+            let height = ((i + j) % 2) as f32;
+            controls.push(Vec4::new(i as f32, j as f32, height, 1.0));
+        }
+    }
+    let surface = NurbsSurface::new(
+        3,
+        2,
+        4,
+        3,
+        controls,
+        vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+        vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+    );
+
+    let mut curves = surface.curves_u();
+    for curve in curves.iter_mut() {
+        curve.insert_knot(0.5, 1);
+    }
+    let mut refined = NurbsSurface::from_curves_u(curves, 2, surface.knots_v.clone());
+    let mut curves = refined.curves_v();
+    for curve in curves.iter_mut() {
+        curve.insert_knot(0.25, 2);
+    }
+    refined = NurbsSurface::from_curves_v(curves, 3, refined.knots_u.clone());
+    assert_eq!((refined.control_count_u, refined.control_count_v), (5, 5));
+
+    assert_eq!(refined.remove_knots(1e-4), 3);
+    assert_eq!(refined.knots_u, surface.knots_u);
+    assert_eq!(refined.knots_v, surface.knots_v);
+    for i in 0..=10 {
+        for j in 0..=10 {
+            let (u, v) = (i as f32 / 10.0, j as f32 / 10.0);
+            assert!(Vec3::subtract(&surface.point(u, v), &refined.point(u, v)).len() < 1e-4);
+        }
+    }
+}
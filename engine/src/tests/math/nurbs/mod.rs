@@ -0,0 +1,13 @@
+pub mod basis;
+pub mod compatibility;
+pub mod curve;
+pub mod degree_elevation;
+pub mod fitting;
+pub mod interpolation;
+pub mod intersection;
+pub mod knot_insertion;
+pub mod knot_removal;
+pub mod offset;
+pub mod projection;
+pub mod surface;
+pub mod surface_intersection;
@@ -0,0 +1,112 @@
+use crate::{
+    geometry::{
+        surface_generators::{
+            cylinder::{create_cone_nurbs, create_cylinder_nurbs},
+            rectangle::create_rectangle_nurbs,
+        },
+        utils::placement,
+    },
+    math::{
+        linear_algebra::{mat4::Mat4, vec3::Vec3},
+        nurbs::surface::NurbsSurface,
+    },
+};
+
+use wasm_bindgen_test::*;
+
+fn upright() -> Mat4 {
+    placement(
+        &Vec3::new(0.0, 0.0, 0.0),
+        &Vec3::new(1.0, 0.0, 0.0),
+        &Vec3::new(0.0, 0.0, 1.0),
+    )
+    .unwrap()
+}
+
+fn grid(surface: &NurbsSurface, count: usize) -> (Vec<f32>, Vec<f32>) {
+    let params = |(start, end): (f32, f32)| -> Vec<f32> {
+        (0..count)
+            .map(|i| start + (end - start) * i as f32 / (count - 1) as f32)
+            .collect()
+    };
+    (params(surface.domain_u()), params(surface.domain_v()))
+}
+
+/// Sign of the distance that offsets towards the axis of a surface of revolution around z
+fn inwards(surface: &NurbsSurface, u: f32, v: f32) -> f32 {
+    let point = surface.point(u, v);
+    let normal = surface.normal(u, v);
+    -(point.x * normal.x + point.y * normal.y).signum()
+}
+
+#[wasm_bindgen_test]
+pub fn test_principal_curvatures() {
+    let cylinder = create_cylinder_nurbs(&upright(), 2.0, 3.0);
+    for (u, v) in [(0.2, 0.3), (0.5, 1.7), (0.9, 4.0)] {
+        let (k1, k2) = cylinder.principal_curvatures(u, v).unwrap();
+        // One direction is straight, the other curves with radius 2 towards the axis
+        let (around, along) = if k1.abs() > k2.abs() {
+            (k1, k2)
+        } else {
+            (k2, k1)
+        };
+        assert!(along.abs() < 1e-3, "straight curvature {}", along);
+        assert!(
+            (around.abs() - 0.5).abs() < 1e-3,
+            "round curvature {}",
+            around
+        );
+        let center = Vec3::add(
+            &cylinder.point(u, v),
+            &Vec3::to_scaled(&cylinder.normal(u, v), 1.0 / around),
+        );
+        assert!(center.x.hypot(center.y) < 1e-3, "center {}", center);
+        assert!(k1 >= k2);
+    }
+
+    let plane = create_rectangle_nurbs(&upright(), 2.0, 3.0);
+    let (k1, k2) = plane.principal_curvatures(0.3, 0.6).unwrap();
+    assert!(k1.abs() < 1e-6 && k2.abs() < 1e-6);
+}
+
+#[wasm_bindgen_test]
+pub fn test_offset_self_intersections() {
+    let cylinder = create_cylinder_nurbs(&upright(), 2.0, 3.0);
+    let (params_u, params_v) = grid(&cylinder, 11);
+    let inwards = inwards(&cylinder, 0.5, 1.0);
+
+    // Outwards and less than the radius inwards are fine
+    assert!(cylinder
+        .offset_self_intersections(&params_u, &params_v, -inwards * 2.5)
+        .is_empty());
+    assert!(cylinder
+        .offset_self_intersections(&params_u, &params_v, inwards * 1.5)
+        .is_empty());
+
+    // Further in than the radius the whole cylinder folds
+    let folds = cylinder.offset_self_intersections(&params_u, &params_v, inwards * 2.5);
+    assert_eq!(folds.len(), 1);
+    let fold = &folds[0];
+    assert_eq!((fold.min_u, fold.max_u), (params_u[0], params_u[10]));
+    assert_eq!((fold.min_v, fold.max_v), (params_v[0], params_v[10]));
+    assert!((fold.radius - 2.0).abs() < 1e-3);
+
+    // A cone narrows going up, only the part thinner than the distance folds
+    let cone = create_cone_nurbs(&upright(), 2.0, 0.5, 3.0);
+    let (params_u, params_v) = grid(&cone, 21);
+    let distance = inwards * 1.5;
+    let folds = cone.offset_self_intersections(&params_u, &params_v, distance);
+    assert_eq!(folds.len(), 1);
+    let fold = &folds[0];
+    // The radius of curvature is r / cos of the slope, 1.5 is reached just below half way up
+    let cos_slope = 1.0 / 1.25_f32.sqrt();
+    let fold_height = (2.0 - 1.5 * cos_slope) / 0.5;
+    assert!(
+        (fold.min_u * 3.0 - fold_height).abs() < 0.16,
+        "folds from {}",
+        fold.min_u
+    );
+    assert_eq!(fold.max_u, params_u[20]);
+    assert_eq!(fold.u, params_u[20]);
+    assert!((fold.radius - 0.5 / cos_slope).abs() < 1e-3);
+}
@@ -0,0 +1,89 @@
+use crate::{
+    geometry::{
+        curve_generators::circle::create_circle_nurbs,
+        surface_generators::sphere::create_sphere_nurbs, utils::placement,
+    },
+    math::{
+        linear_algebra::{vec3::Vec3, vec4::Vec4},
+        nurbs::curve::NurbsCurve,
+    },
+};
+
+use crate::tests::utils::assert_close;
+
+use wasm_bindgen_test::*;
+
+/// Coarse seeds, so Newton has to do the work
+fn seeds(start: f32, end: f32, count: usize) -> Vec<f32> {
+    (0..=count)
+        .map(|i| start + (end - start) * i as f32 / count as f32)
+        .collect()
+}
+
+#[wasm_bindgen_test]
+pub fn test_curve_closest_point() {
+    let center = Vec3::new(1.0, 2.0, 0.0);
+    let circle = create_circle_nurbs(center, Vec3::new(0.0, 0.0, 1.0), 2.0);
+    let (start, end) = circle.domain();
+    for query in [
+        Vec3::new(4.0, 3.0, 0.0),
+        Vec3::new(-0.5, 1.9, 0.0),
+        Vec3::new(1.2, -5.0, 3.0),
+    ] {
+        let (t, point) = circle.closest_point(&query, &seeds(start, end, 8));
+        let direction = Vec3::subtract(&Vec3::new(query.x, query.y, 0.0), &center).to_normalized();
+        let expected = Vec3::add(&center, &Vec3::to_scaled(&direction, 2.0));
+        assert_close(&point, &expected, 1e-4);
+        assert_close(&circle.point(t), &point, 1e-6);
+    }
+
+    // Just before the seam, where the nearest seed is the start
+    let query = circle.point(end - 0.01);
+    let (t, point) = circle.closest_point(&query, &seeds(start, end, 8));
+    assert_close(&point, &query, 1e-5);
+    assert!((t - (end - 0.01)).abs() < 1e-4);
+
+    // Points past the end of an open curve project to the end
+    let line = NurbsCurve::new(
+        1,
+        vec![
+            Vec4::new_point(0.0, 0.0, 0.0),
+            Vec4::new_point(2.0, 0.0, 0.0),
+        ],
+        vec![0.0, 0.0, 1.0, 1.0],
+    );
+    let (t, point) = line.closest_point(&Vec3::new(3.0, 1.0, 0.0), &seeds(0.0, 1.0, 4));
+    assert_eq!(t, 1.0);
+    assert_close(&point, &Vec3::new(2.0, 0.0, 0.0), 1e-6);
+}
+
+#[wasm_bindgen_test]
+pub fn test_surface_closest_point() {
+    let center = Vec3::new(0.5, -1.0, 2.0);
+    let sphere = create_sphere_nurbs(
+        &placement(
+            &center,
+            &Vec3::new(1.0, 0.0, 0.0),
+            &Vec3::new(0.0, 0.0, 1.0),
+        )
+        .unwrap(),
+        3.0,
+    );
+    let (start_u, end_u) = sphere.domain_u();
+    let (start_v, end_v) = sphere.domain_v();
+    for query in [
+        Vec3::new(5.0, 1.0, 3.0),
+        Vec3::new(0.0, -1.5, 1.0),
+        Vec3::new(-2.0, 4.0, -3.0),
+    ] {
+        let (u, v, point) =
+            sphere.closest_point(&query, &seeds(start_u, end_u, 6), &seeds(start_v, end_v, 6));
+        let direction = Vec3::subtract(&query, &center).to_normalized();
+        let expected = Vec3::add(&center, &Vec3::to_scaled(&direction, 3.0));
+        assert_close(&point, &expected, 1e-3);
+        assert_close(&sphere.point(u, v), &point, 1e-6);
+        // The normal is along the radius, either way round
+        let normal = sphere.normal(u, v);
+        assert!(Vec3::dot(&normal, &direction).abs() > 1.0 - 1e-4);
+    }
+}
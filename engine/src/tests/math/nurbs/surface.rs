@@ -0,0 +1,111 @@
+use crate::math::{
+    linear_algebra::{vec3::Vec3, vec4::Vec4},
+    nurbs::surface::NurbsSurface,
+};
+
+use crate::tests::utils::assert_close;
+
+use wasm_bindgen_test::*;
+
+/// Degree 1 by 2 patch, u changes fastest in the controls
+fn create_surface() -> NurbsSurface {
+    let mut controls = Vec::new();
+    for j in 0..3 {
+        for i in 0..2 {
+            let height = if j == 1 { 1.0 } else { 0.0 };
+            controls.push(Vec4::new(i as f32 * 2.0, j as f32, height, 1.0));
+        }
+    }
+    NurbsSurface::new(
+        1,
+        2,
+        2,
+        3,
+        controls,
+        vec![0.0, 0.0, 1.0, 1.0],
+        vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+    )
+}
+
+/// Rational quadratic quarter cylinder of radius 1 along z
+fn quarter_cylinder() -> NurbsSurface {
+    let w = std::f32::consts::FRAC_1_SQRT_2;
+    let mut controls = Vec::new();
+    for z in [0.0, 1.0] {
+        controls.push(Vec4::new(1.0, 0.0, z, 1.0));
+        controls.push(Vec4::new(w, w, z * w, w));
+        controls.push(Vec4::new(0.0, 1.0, z, 1.0));
+    }
+    NurbsSurface::new(
+        2,
+        1,
+        3,
+        2,
+        controls,
+        vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        vec![0.0, 0.0, 1.0, 1.0],
+    )
+}
+
+#[wasm_bindgen_test]
+pub fn test_corners() {
+    let surface = create_surface();
+    assert_close(&surface.point(0.0, 0.0), &Vec3::new(0.0, 0.0, 0.0), 1e-6);
+    assert_close(&surface.point(1.0, 0.0), &Vec3::new(2.0, 0.0, 0.0), 1e-6);
+    assert_close(&surface.point(0.0, 1.0), &Vec3::new(0.0, 2.0, 0.0), 1e-6);
+    assert_close(&surface.point(1.0, 1.0), &Vec3::new(2.0, 2.0, 0.0), 1e-6);
+    // Quadratic bump in v
+    assert_close(&surface.point(0.5, 0.5), &Vec3::new(1.0, 1.0, 0.5), 1e-6);
+}
+
+#[wasm_bindgen_test]
+pub fn test_cylinder() {
+    let surface = quarter_cylinder();
+    for i in 0..=10 {
+        for j in 0..=4 {
+            let u = i as f32 / 10.0;
+            let v = j as f32 / 4.0;
+            let p = surface.point(u, v);
+            assert!((p.x * p.x + p.y * p.y - 1.0).abs() < 1e-5);
+            assert!((p.z - v).abs() < 1e-5);
+            // Normal points away from the axis
+            let normal = surface.normal(u, v);
+            assert_close(&normal, &Vec3::new(p.x, p.y, 0.0), 1e-4);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_derivatives_match_finite_differences() {
+    let h = 1e-2;
+    for surface in [create_surface(), quarter_cylinder()] {
+        for i in 1..5 {
+            for j in 1..5 {
+                let u = i as f32 / 5.0;
+                let v = j as f32 / 5.0;
+                let ders = surface.derivatives(u, v, 2);
+                assert_close(&ders[0][0], &surface.point(u, v), 1e-6);
+
+                let du = Vec3::to_scaled(
+                    &Vec3::subtract(&surface.point(u + h, v), &surface.point(u - h, v)),
+                    0.5 / h,
+                );
+                let dv = Vec3::to_scaled(
+                    &Vec3::subtract(&surface.point(u, v + h), &surface.point(u, v - h)),
+                    0.5 / h,
+                );
+                assert_close(&ders[1][0], &du, 1e-2);
+                assert_close(&ders[0][1], &dv, 1e-2);
+
+                let duv = Vec3::to_scaled(
+                    &Vec3::subtract(
+                        &Vec3::subtract(&surface.point(u + h, v + h), &surface.point(u + h, v - h)),
+                        &Vec3::subtract(&surface.point(u - h, v + h), &surface.point(u - h, v - h)),
+                    ),
+                    0.25 / (h * h),
+                );
+                assert_close(&ders[1][1], &duv, 5e-2);
+            }
+        }
+    }
+}
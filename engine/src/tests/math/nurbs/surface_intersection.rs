@@ -0,0 +1,167 @@
+use crate::{
+    geometry::{surface_generators::sphere::create_sphere_nurbs, utils::placement},
+    math::{
+        linear_algebra::vec3::Vec3,
+        nurbs::{
+            surface::NurbsSurface,
+            surface_intersection::{intersect_surfaces, SurfaceIntersectionCurve},
+        },
+    },
+};
+
+use crate::tests::utils::assert_close;
+
+use wasm_bindgen_test::*;
+
+const TOLERANCE: f32 = 1e-3;
+
+/// Bilinear patch, u runs from the first corner to the second and v from the first to the third
+fn parallelogram(corner: Vec3, to_u: Vec3, to_v: Vec3) -> NurbsSurface {
+    let controls = [
+        corner,
+        Vec3::add(&corner, &to_u),
+        Vec3::add(&corner, &to_v),
+        Vec3::add(&Vec3::add(&corner, &to_u), &to_v),
+    ];
+    NurbsSurface::new(
+        1,
+        1,
+        2,
+        2,
+        controls.iter().map(|control| control.append(1.0)).collect(),
+        vec![0.0, 0.0, 1.0, 1.0],
+        vec![0.0, 0.0, 1.0, 1.0],
+    )
+}
+
+fn sample_params(curve: &SurfaceIntersectionCurve, count: usize) -> Vec<f32> {
+    let (start, end) = curve.curve.domain();
+    (0..=count)
+        .map(|i| start + (end - start) * i as f32 / count as f32)
+        .collect()
+}
+
+/// The curve and its images on both surfaces agree along the whole curve
+fn assert_on_surfaces(curve: &SurfaceIntersectionCurve, a: &NurbsSurface, b: &NurbsSurface) {
+    for t in sample_params(curve, 50) {
+        let point = curve.curve.point(t);
+        let on_a = curve.curve_a.point(t);
+        let on_b = curve.curve_b.point(t);
+        assert_close(&a.point(on_a.x, on_a.y), &point, 1e-2);
+        assert_close(&b.point(on_b.x, on_b.y), &point, 1e-2);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_plane_sphere_circles() {
+    let sphere = create_sphere_nurbs(
+        &placement(
+            &Vec3::new(0.0, 0.0, 0.0),
+            &Vec3::new(1.0, 0.0, 0.0),
+            &Vec3::new(0.0, 0.0, 1.0),
+        )
+        .unwrap(),
+        3.0,
+    );
+    let (start_u, end_u) = sphere.domain_u();
+    let (start_v, end_v) = sphere.domain_v();
+    let sphere_seeds = |point: &Vec3| {
+        let seeds = |start: f32, end: f32| -> Vec<f32> {
+            (0..=8)
+                .map(|i| start + (end - start) * i as f32 / 8.0)
+                .collect()
+        };
+        let (u, v, _) = sphere.closest_point(point, &seeds(start_u, end_u), &seeds(start_v, end_v));
+        (u, v)
+    };
+
+    // Across x = -1, a circle of radius sqrt(8) away from the seam of the sphere
+    let plane = parallelogram(
+        Vec3::new(-1.0, -4.0, -4.0),
+        Vec3::new(0.0, 8.0, 0.0),
+        Vec3::new(0.0, 0.0, 8.0),
+    );
+    let radius = 8.0_f32.sqrt();
+    // Two rough seeds on the same circle give one curve
+    let seeds: Vec<[f32; 4]> = [Vec3::new(-1.0, radius, 0.0), Vec3::new(-1.0, 0.0, -radius)]
+        .iter()
+        .map(|point| {
+            let (u, v) = sphere_seeds(&Vec3::add(point, &Vec3::new(0.1, 0.1, 0.1)));
+            [
+                u,
+                v,
+                (point.y + 4.0) / 8.0 + 0.05,
+                (point.z + 4.0) / 8.0 - 0.05,
+            ]
+        })
+        .collect();
+    let curves = intersect_surfaces(&sphere, &plane, &seeds, TOLERANCE);
+    assert_eq!(curves.len(), 1);
+    let curve = &curves[0];
+    assert!(curve.closed);
+    for t in sample_params(curve, 100) {
+        let point = curve.curve.point(t);
+        assert!((point.x + 1.0).abs() < 1e-2);
+        assert!((point.len() - 3.0).abs() < 1e-2);
+    }
+    let (start, end) = curve.curve.domain();
+    assert_close(&curve.curve.point(start), &curve.curve.point(end), 1e-4);
+    // No kink where the curve meets itself
+    let tangent = |t: f32| curve.curve.derivatives(t, 1)[1].to_normalized();
+    assert!(Vec3::angle_between(&tangent(start), &tangent(end)) < 1e-2);
+    assert_on_surfaces(curve, &sphere, &plane);
+
+    // Across z = 1 the circle runs over the seam, so its ends meet in space but not in (u, v)
+    let plane = parallelogram(
+        Vec3::new(-4.0, -4.0, 1.0),
+        Vec3::new(8.0, 0.0, 0.0),
+        Vec3::new(0.0, 8.0, 0.0),
+    );
+    let point = Vec3::new(-radius, 0.0, 1.0);
+    let (u, v) = sphere_seeds(&point);
+    let curves = intersect_surfaces(&sphere, &plane, &[[u, v, 0.2, 0.5]], TOLERANCE);
+    assert_eq!(curves.len(), 1);
+    assert!(curves[0].closed);
+    for t in sample_params(&curves[0], 100) {
+        let point = curves[0].curve.point(t);
+        assert!((point.z - 1.0).abs() < 1e-2);
+        assert!((point.len() - 3.0).abs() < 1e-2);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_plane_plane_line() {
+    let a = parallelogram(
+        Vec3::new(-1.0, -1.0, 0.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 0.0),
+    );
+    let b = parallelogram(
+        Vec3::new(0.3, -2.0, -1.0),
+        Vec3::new(0.0, 4.0, 0.0),
+        Vec3::new(0.0, 0.0, 2.0),
+    );
+    let curves = intersect_surfaces(&a, &b, &[[0.7, 0.4, 0.5, 0.4]], TOLERANCE);
+    assert_eq!(curves.len(), 1);
+    let curve = &curves[0];
+    assert!(!curve.closed);
+    // Ends on the border of the smaller plane
+    let (start, end) = curve.curve.domain();
+    let ends = [curve.curve.point(start), curve.curve.point(end)];
+    let (low, high) = if ends[0].y < ends[1].y {
+        (ends[0], ends[1])
+    } else {
+        (ends[1], ends[0])
+    };
+    assert_close(&low, &Vec3::new(0.3, -1.0, 0.0), 1e-4);
+    assert_close(&high, &Vec3::new(0.3, 1.0, 0.0), 1e-4);
+    assert_on_surfaces(curve, &a, &b);
+
+    // Seeds that are nowhere near give nothing
+    let apart = parallelogram(
+        Vec3::new(5.0, -2.0, -1.0),
+        Vec3::new(0.0, 4.0, 0.0),
+        Vec3::new(0.0, 0.0, 2.0),
+    );
+    assert!(intersect_surfaces(&a, &apart, &[[0.5, 0.5, 0.5, 0.5]], TOLERANCE).is_empty());
+}
@@ -1,3 +1,8 @@
+pub mod geometry;
 pub mod gpu_algorithms;
+pub mod gpu_mesh_intersection;
+pub mod gpu_ray_tracing;
+pub mod gpu_samplers;
 pub mod math;
-
+pub mod scene;
+pub mod utils;
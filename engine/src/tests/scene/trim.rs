@@ -0,0 +1,61 @@
+use crate::{
+    math::{linear_algebra::vec4::Vec4, nurbs::curve::NurbsCurve},
+    scene::trim::{read_trim_loops, write_trim_curve},
+};
+
+use wasm_bindgen_test::*;
+
+/// One loop of a single closed quadratic in the layout of Scene::set_surface_trims
+fn loop_data() -> Vec<f32> {
+    let curve = NurbsCurve::new(
+        2,
+        vec![
+            Vec4::new(0.2, 0.2, 0.0, 1.0),
+            Vec4::new(0.8, 0.2, 0.0, 1.0),
+            Vec4::new(0.5, 0.8, 0.0, 1.0),
+            Vec4::new(0.2, 0.2, 0.0, 1.0),
+        ],
+        vec![0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0],
+    );
+    let mut data = vec![1.0];
+    write_trim_curve(&curve, &mut data);
+    data
+}
+
+/// Index of the first knot in loop_data
+const FIRST_KNOT: usize = 3 + 4 * 3;
+
+#[wasm_bindgen_test]
+fn test_read_trim_loops() {
+    let loops = read_trim_loops(&loop_data()).unwrap();
+    assert_eq!(loops.len(), 1);
+    assert!(read_trim_loops(&loop_data()[..FIRST_KNOT]).is_none());
+}
+
+#[wasm_bindgen_test]
+fn test_reject_decreasing_knots() {
+    let mut data = loop_data();
+    data[FIRST_KNOT + 3] = 2.0;
+    assert!(read_trim_loops(&data).is_none());
+
+    // Knots that leave no domain
+    let mut data = loop_data();
+    data[FIRST_KNOT..].fill(0.0);
+    assert!(read_trim_loops(&data).is_none());
+}
+
+#[wasm_bindgen_test]
+fn test_reject_bad_values() {
+    // u of the first control, its weight and a knot
+    for (index, value) in [
+        (3, f32::NAN),
+        (3, f32::INFINITY),
+        (5, 0.0),
+        (5, -1.0),
+        (FIRST_KNOT + 3, f32::NAN),
+    ] {
+        let mut data = loop_data();
+        data[index] = value;
+        assert!(read_trim_loops(&data).is_none());
+    }
+}
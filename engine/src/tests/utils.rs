@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use crate::math::{
+    linear_algebra::{vec3::Vec3, vec4::Vec4},
+    nurbs::curve::NurbsCurve,
+};
+
+pub fn assert_close(a: &Vec3, b: &Vec3, tolerance: f32) {
+    assert!(
+        Vec3::subtract(a, b).len() < tolerance,
+        "{} is not close to {}",
+        a,
+        b
+    );
+}
+
+/// Rational quadratic quarter circle of radius 1 in the xy plane
+pub fn quarter_circle() -> NurbsCurve {
+    let w = std::f32::consts::FRAC_1_SQRT_2;
+    NurbsCurve::new(
+        2,
+        vec![
+            Vec4::new(1.0, 0.0, 0.0, 1.0),
+            Vec4::new(w, w, 0.0, w),
+            Vec4::new(0.0, 1.0, 0.0, 1.0),
+        ],
+        vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+    )
+}
+
+/// Rational cubic with two spans
+pub fn rational_cubic() -> NurbsCurve {
+    NurbsCurve::new(
+        3,
+        vec![
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+            Vec4::new(2.0, 4.0, 0.0, 2.0),
+            Vec4::new(3.0, 2.0, 1.0, 1.0),
+            Vec4::new(2.0, 0.0, 0.5, 0.5),
+            Vec4::new(5.0, 1.0, 0.0, 1.0),
+        ],
+        vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 2.0, 2.0, 2.0],
+    )
+}
+
+/// Checks the curves share a domain and stay within tolerance of each other across it
+pub fn assert_same_shape(a: &NurbsCurve, b: &NurbsCurve, tolerance: f32) {
+    assert_eq!(a.domain(), b.domain());
+    let (start, end) = a.domain();
+    for i in 0..=50 {
+        let t = start + (end - start) * i as f32 / 50.0;
+        let distance = Vec3::subtract(&a.point(t), &b.point(t)).len();
+        assert!(
+            distance < tolerance,
+            "curves differ by {} at {}",
+            distance,
+            t
+        );
+    }
+}
+
+/// Byte offset of each member of a struct in WGSL source, and the size of the struct
+pub fn wgsl_struct_layout(source: &str, name: &str) -> (HashMap<String, usize>, usize) {
+    let module = naga::front::wgsl::parse_str(source).expect("shader does not parse");
+    let res = module
+        .types
+        .iter()
+        .find_map(|(_, ty)| match &ty.inner {
+            naga::TypeInner::Struct { members, span } if ty.name.as_deref() == Some(name) => {
+                let offsets = members
+                    .iter()
+                    .map(|member| (member.name.clone().unwrap(), member.offset as usize))
+                    .collect();
+                Some((offsets, *span as usize))
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no struct {} in the shader", name));
+    res
+}
+
+/// count f32s starting at offset
+pub fn read_f32s(bytes: &[u8], offset: usize, count: usize) -> Vec<f32> {
+    (0..count)
+        .map(|i| bytemuck::pod_read_unaligned(&bytes[offset + 4 * i..offset + 4 * i + 4]))
+        .collect()
+}
+
+pub fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    bytemuck::pod_read_unaligned(&bytes[offset..offset + 4])
+}
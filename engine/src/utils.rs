@@ -39,6 +39,17 @@ pub(crate) const fn compute_uniform_bind_group_layout_entry(
     }
 }
 
+/// Workgroups in each of three dimensions for count single thread workgroups,
+/// so large counts stay under the 65535 per dimension limit.
+/// Shaders flatten the id like generator_fast_build_2 does and return past count.
+pub(crate) fn dispatch_size_3d(count: u32) -> u32 {
+    let mut size = f32::powf(count as f32, 1.0 / 3.0).ceil() as u32;
+    while (size as u64).pow(3) < count as u64 {
+        size += 1;
+    }
+    size
+}
+
 pub(crate) fn create_compute_pipeline(
     device: &wgpu::Device,
     label: &str,
@@ -66,6 +77,70 @@ pub(crate) fn create_compute_pipeline(
     })
 }
 
+/// A copy of a buffer into a mappable read buffer, submitted and waiting to be mapped.
+/// Submit while the instance is locked and read after the guard is dropped,
+/// the mapping only finishes once control returns to the event loop.
+pub struct PendingReadback {
+    mapping: Option<(
+        wgpu::Buffer,
+        futures::channel::oneshot::Receiver<Result<(), wgpu::BufferAsyncError>>,
+    )>,
+}
+
+impl PendingReadback {
+    /// Copies the first size bytes of buffer after the commands in encoder and submits them.
+    pub(crate) fn submit(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mut encoder: wgpu::CommandEncoder,
+        buffer: &wgpu::Buffer,
+        size: u64,
+    ) -> Self {
+        let read_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback read buffer"),
+            size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_buffer_to_buffer(buffer, 0, &read_buffer, 0, size);
+
+        let idx = queue.submit([encoder.finish()]);
+        device.poll(wgpu::Maintain::WaitForSubmissionIndex(idx));
+
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        read_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, |result| {
+                let _ = sender.send(result);
+            });
+
+        Self {
+            mapping: Some((read_buffer, receiver)),
+        }
+    }
+
+    /// Nothing to read, for when there was no work to submit.
+    pub(crate) fn empty() -> Self {
+        Self { mapping: None }
+    }
+
+    pub async fn read<T: bytemuck::Pod>(self) -> Vec<T> {
+        let Some((read_buffer, receiver)) = self.mapping else {
+            return Vec::new();
+        };
+
+        receiver
+            .await
+            .expect("communication failed")
+            .expect("buffer reading failed");
+
+        let res: Vec<T> = bytemuck::cast_slice(&read_buffer.slice(..).get_mapped_range()).to_vec();
+        read_buffer.unmap();
+
+        res
+    }
+}
+
 // TODO: make this return a slice
 pub(crate) async fn dump_buffer<T>(
     device: &wgpu::Device,
@@ -1,7 +1,10 @@
 use std::{rc::Rc, time::Instant};
 
 use crate::{
-    math::linear_algebra::{mat4::Mat4, vec3::Vec3},
+    math::{
+        geometry::ray::Ray,
+        linear_algebra::{mat4::Mat4, vec3::Vec3},
+    },
     render::renderer::Renderer,
 };
 
@@ -219,6 +222,36 @@ impl Camera {
         );
     }
 
+    /// Ray from the camera through a pixel.
+    /// x and y are in pixels from the top left corner.
+    pub fn pixel_to_ray(&self, x: f32, y: f32, width: f32, height: f32) -> Ray {
+        let ndc_x = 2.0 * x / width - 1.0;
+        let ndc_y = 1.0 - 2.0 * y / height;
+
+        // Same basis as Mat4::look_at
+        let backward = Vec3::subtract(&self.position, &self.focal_point).to_normalized();
+        let right = Vec3::cross(&self.up, &backward).to_normalized();
+        let up = Vec3::cross(&backward, &right);
+
+        let half_height = f32::tan(self.fovy / 2.0);
+        let half_width = half_height * self.aspect;
+
+        let direction = Vec3::add(
+            &Vec3::to_scaled(&backward, -1.0),
+            &Vec3::add(
+                &Vec3::to_scaled(&right, ndc_x * half_width),
+                &Vec3::to_scaled(&up, ndc_y * half_height),
+            ),
+        );
+
+        Ray::new(self.position, direction)
+    }
+
+    /// World space size of a pixel at a distance of 1 from the camera.
+    pub fn get_pixel_size(&self, height: f32) -> f32 {
+        2.0 * f32::tan(self.fovy / 2.0) / height
+    }
+
     pub fn get_view_proj_buffer(&self) -> &wgpu::Buffer {
         &self.view_proj_buffer
     }
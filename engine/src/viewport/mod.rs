@@ -2,6 +2,7 @@ pub mod camera;
 pub mod viewport_interface;
 use std::rc::Rc;
 
+use crate::math::geometry::{frustum::Frustum, ray::Ray};
 use crate::math::linear_algebra::mat4::Mat4;
 use crate::render::renderer::Renderer;
 
@@ -144,4 +145,35 @@ impl ViewportInternal {
     pub fn get_camera_mut(&mut self) -> &mut Camera {
         &mut self.camera
     }
+
+    pub fn get_camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// x and y are in pixels from the top left corner of the canvas.
+    pub fn pixel_to_ray(&self, x: f32, y: f32) -> Ray {
+        self.camera.pixel_to_ray(
+            x,
+            y,
+            self.canvas.width() as f32,
+            self.canvas.height() as f32,
+        )
+    }
+
+    /// Frustum through a rectangle of pixels, corners can be given in any order.
+    pub fn pixel_rect_to_frustum(&self, x0: f32, y0: f32, x1: f32, y1: f32) -> Frustum {
+        let (left, right) = (x0.min(x1), x0.max(x1));
+        let (top, bottom) = (y0.min(y1), y0.max(y1));
+        Frustum::new(
+            &self.pixel_to_ray(left, top),
+            &self.pixel_to_ray(right, top),
+            &self.pixel_to_ray(right, bottom),
+            &self.pixel_to_ray(left, bottom),
+        )
+    }
+
+    /// World space size of a pixel at a distance of 1 from the camera.
+    pub fn get_pixel_size(&self) -> f32 {
+        self.camera.get_pixel_size(self.canvas.height() as f32)
+    }
 }
@@ -1,8 +1,10 @@
-import { WebCadInstance, CameraType, get_samples_per_segment } from '../../engine/pkg'
+import { WebCadInstance, CameraType } from '../../engine/pkg'
 
 import { Queue } from './queue';
 
 let instance = await WebCadInstance.new_instance();
+// Max chordal deviation and max angle between neighbouring samples in radians
+instance.set_sampling_tolerance(0.01, 10 * Math.PI / 180);
 
 let scene = instance.create_scene();
 
@@ -32,10 +34,8 @@ function update_stats() {
   let control_point_count = document.getElementById("control point count");
   let total_samples_display = document.getElementById("total samples");
 
-  // I feel like this function should be in a namespace
-  let sps = get_samples_per_segment();
-
-  let sample_count = (control_count_u - 1) * (control_count_v - 1) * sps * sps;
+  // Sampling is adaptive, so ask the surface how many samples it ended up with
+  let sample_count = scene.get_surface_sample_count(surface);
   total_samples += sample_count;
   fps.innerHTML = "FPS: " + samples_this_second_queue.get_size().toString();
   surface_vertex_count.innerHTML = "Surface vertex count: " + numberWithCommas(sample_count);